@@ -0,0 +1,25 @@
+use std::{env, process::Command};
+
+/// Runs a command and returns its trimmed output, if it succeeds
+fn output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8(output.stdout).ok().map(|s| s.trim().to_owned())
+}
+
+fn main() {
+    let commit = output("git", &["rev-parse", "--short", "HEAD"]).unwrap_or_else(|| String::from("unknown"));
+
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| String::from("rustc"));
+    let rustc_version = output(&rustc, &["--version"]).unwrap_or_else(|| String::from("unknown"));
+
+    println!("cargo:rustc-env=GIT_COMMIT={}", commit);
+    println!("cargo:rustc-env=RUSTC_VERSION={}", rustc_version);
+
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+}
@@ -0,0 +1,383 @@
+## Dispatcher and default replies
+
+no-permissions = Du hast keine Berechtigung, diesen Befehl auszuführen!
+missing-permissions = Du benötigst die Berechtigungen { $permissions }, um diesen Befehl auszuführen!
+subcommand-missing =
+    Gib einen der Unterbefehle an:
+    { $usages }
+embed-success = Erfolg!
+embed-failure = Fehler!
+embed-confirmation = Bestätigung!
+embed-warning = Warnung!
+embed-info = Information
+reply-page = Seite { $page }/{ $count }
+reply-attached = Die Ausgabe ist zu lang, um angezeigt zu werden, daher wurde sie als { $file } angehängt.
+
+## Command categories
+
+category-custom = Benutzerdefiniert
+category-developer = Entwickler
+category-general = Allgemein
+category-utility = Werkzeuge
+
+## Help
+
+help-title = { $bot } Hilfe
+help-category-commands = Befehle: { $category }
+help-developer-only = { $command } (nur für Entwickler)
+help-category = Kategorie
+help-aliases = Aliasse
+help-usages = Verwendung
+help-no-command = Zu dieser Anfrage wurde kein Befehl gefunden!
+help-permissions = Benötigte Berechtigungen
+help-subcommands = Unterbefehle
+help-default-subcommand = (Standard)
+help-no-description = Es wurde keine Beschreibung angegeben.
+help-description-analytics = Sendet die meistgenutzten Befehle, ihre Fehlerraten und Latenzen sowie die täglich aktiven Benutzer
+help-description-help = Sendet eine Liste der Befehle des Bots oder hilft beim angegebenen Befehl
+help-description-language = Sendet deine aktuelle Sprache oder ändert deine Sprache oder die des Servers
+help-description-language-reset = Setzt deine Sprache zurück, sodass der Bot dir wieder in der Sprache des Servers antwortet
+help-description-language-server = Ändert die Sprache des Servers, die für alle gilt, die keine eigene gewählt haben
+help-description-language-server-reset = Setzt die Sprache des Servers auf Englisch zurück
+help-description-language-server-set = Ändert die Sprache des Servers
+help-description-language-set = Ändert die Sprache, in der der Bot dir antwortet
+help-description-language-show = Sendet deine aktuelle Sprache, die des Servers und die verfügbaren
+help-description-ping = Sendet die REST- und Gateway-Latenz des Bots und mit `edit` die Dauer einer Bearbeitung
+help-description-privacy = Sendet, ob deine Befehlsnutzung aufgezeichnet wird, oder lässt dich ihr widersprechen oder wieder zustimmen
+help-description-privacy-opt-in = Verknüpft die Befehle, die du ausführst, wieder mit dir
+help-description-privacy-opt-out = Verknüpft die Befehle, die du ausführst, nicht mehr mit dir, auch die bereits ausgeführten
+help-description-privacy-status = Sendet, ob die Befehle, die du ausführst, mit dir verknüpft werden
+help-description-script = Verwaltet die geskripteten Befehle des Servers, die Admins in Rhai schreiben
+help-description-script-add = Fügt dem Server einen geskripteten Befehl hinzu, dessen Code der optionalen Beschreibung in einem Codeblock folgt
+help-description-script-edit = Ersetzt die Beschreibung und den Code eines geskripteten Befehls
+help-description-script-list = Sendet die geskripteten Befehle des Servers
+help-description-script-remove = Entfernt einen geskripteten Befehl vom Server
+help-description-script-show = Sendet den Code eines geskripteten Befehls
+help-description-shards = Sendet Status, Latenz und Serveranzahl jedes Shards
+help-description-shutdown = Fährt den Bot herunter
+help-description-stats = Sendet Laufzeit, Ressourcenverbrauch, Cachegrößen und Build-Informationen des Bots
+help-description-tag = Sendet einen Tag, einen eigenen Textbefehl des Servers, oder verwaltet die Tags des Servers
+help-description-tag-add = Fügt dem Server einen Tag hinzu, dessen Text `{ "{user}" }`, `{ "{user.name}" }`, `{ "{channel}" }`, `{ "{args}" }`, `{ "{args.1}" }` und so weiter enthalten kann
+help-description-tag-delete = Löscht einen Tag vom Server, was nur sein Besitzer und Server-Manager können
+help-description-tag-edit = Ersetzt den Text eines Tags, was nur sein Besitzer und Server-Manager können
+help-description-tag-info = Sendet, wem ein Tag gehört, wann er erstellt wurde und wie oft er verwendet wurde
+help-description-tag-list = Sendet die Tags des Servers, die meistverwendeten zuerst
+help-description-tag-show = Sendet einen Tag, der auch als eigener Befehl ausgeführt werden kann
+help-description-theme = Sendet das Embed-Design des Servers oder ändert dessen Farben, Symbole, Fußzeile, Zeitstempel und Antworten
+help-description-theme-color = Ändert die Farbe eines Embed-Typs oder stellt mit `reset` die Standardfarbe wieder her
+help-description-theme-footer = Ändert die Fußzeile aller Embeds oder entfernt sie mit `reset`
+help-description-theme-icon = Ändert das Symbol eines Embed-Typs, das entweder ein Emoji oder eine https-Bild-URL ist
+help-description-theme-reply = Schaltet Antworten auf die Nachrichten, die Befehle ausführen, ein oder aus
+help-description-theme-reset = Setzt das Embed-Design des Servers auf die Standardwerte des Bots zurück
+help-description-theme-show = Sendet das Embed-Design des Servers
+help-description-theme-timestamp = Schaltet die Zeitstempel der Embeds ein oder aus
+help-description-time = Sendet die aktuelle Uhrzeit an den angegebenen Orten oder in den angegebenen Zeitzonen und speichert deine eigene Zeitzone
+help-description-time-reset = Vergisst deine Zeitzone
+help-description-time-set = Speichert deine Zeitzone, in der der Bot Uhrzeiten und Daten anzeigt
+help-description-time-show = Sendet die aktuelle Uhrzeit an den angegebenen Orten oder in den angegebenen Zeitzonen oder, ohne Angabe, in deiner eigenen
+help-description-uptime = Sendet die aktuelle Laufzeit des Bots oder seine Verfügbarkeit und letzten Ausfälle
+help-description-uptime-current = Sendet die aktuelle Laufzeit des Bots
+help-description-uptime-history = Sendet die Verfügbarkeit und die letzten Ausfälle des Bots
+help-description-weather = Sendet das Wetter oder eine Vorhersage am angegebenen Ort oder verwaltet die Wetterwarnungen des Kanals
+help-description-weather-alerts = Verwaltet die Wetterwarnungen, die der Kanal erhält
+help-description-weather-alerts-list = Sendet die Orte, für die der Kanal Wetterwarnungen erhält
+help-description-weather-alerts-subscribe = Abonniert Wetterwarnungen für den angegebenen Ort in diesem Kanal
+help-description-weather-alerts-unsubscribe = Beendet das Abonnement der Wetterwarnungen für den angegebenen Ort in diesem Kanal
+help-description-weather-current = Sendet das aktuelle Wetter am angegebenen Ort
+help-description-weather-forecast = Sendet die Fünf-Tage-Vorhersage für den angegebenen Ort
+
+## Shutdown
+
+shutdown-confirmation = Bist du sicher?
+
+## Shards
+
+shards-title = Shards ({ $count })
+shards-none = Der Shard-Manager ist nicht verfügbar!
+shards-entry = **Shard { $shard }**: { $stage }, { $latency }, { $guilds ->
+        [one] { $guilds } Server
+       *[other] { $guilds } Server
+    }
+shards-current = {" "}(dieser Shard)
+
+## Analytics
+
+analytics-title = Befehlsstatistik · Letzte { $period }
+analytics-no-data = In diesem Zeitraum wurden keine Befehle ausgeführt!
+analytics-command = **{ $rank }.** `{ $command }`: { $uses }-mal genutzt, { $errors } % Fehler, p50 { $p50 } ms, p95 { $p95 } ms
+analytics-overview = Überblick
+analytics-overview-value =
+    { $total } Ausführungen von { $commands } Befehlen
+    { $errors } % Fehler
+analytics-daily-users = Täglich aktive Benutzer
+analytics-daily-users-value =
+    { $average } im Durchschnitt
+    { $peak } in der Spitze über { $days ->
+        [one] { $days } Tag
+       *[other] { $days } Tage
+    }
+analytics-no-users = Keine Benutzerdaten
+
+## Ping
+
+ping-measuring = *Wird gemessen…*
+ping-measuring-edit = *Bearbeitung wird gemessen…*
+ping-title = Latenz
+ping-rest = REST
+ping-gateway = Gateway
+ping-edit = Bearbeitung
+ping-no-heartbeat = Noch kein Heartbeat
+ping-milliseconds = { $ping } ms
+ping-shard = Shard { $shard } · { $stage }
+
+## Uptime
+
+uptime-title = Laufzeit
+uptime-last-reboot = Letzter Neustart
+uptime-last-reboot-local = Letzter Neustart: { $time }
+uptime-history-title = Verfügbarkeit
+uptime-history-day = Letzte 24 Stunden
+uptime-history-week = Letzte 7 Tage
+uptime-history-month = Letzte 30 Tage
+uptime-history-availability = { $percentage } %
+uptime-history-no-data = Keine Daten
+uptime-history-outages = Letzte Ausfälle
+uptime-history-no-outages = Keine Ausfälle aufgezeichnet
+uptime-history-outage = <t:{ $start }:f> für { $duration }
+uptime-history-ongoing-outage = <t:{ $start }:f>, seit { $duration } andauernd
+uptime-history-tracked-since = Aufgezeichnet seit
+
+## Stats
+
+stats-title = Statistiken von { $bot }
+stats-unavailable = Nicht verfügbar
+stats-uptime = Laufzeit
+stats-memory = Arbeitsspeicher
+stats-cpu-time = CPU-Zeit
+stats-tasks = Tasks
+stats-tasks-value = { $tasks } auf { $workers } Workern
+stats-cache = Cache
+stats-cache-value =
+    { $guilds } Server
+    { $channels } Kanäle
+    { $users } Benutzer
+stats-commands-run = Ausgeführte Befehle
+stats-reconnects = Neuverbindungen
+stats-version = Version
+stats-rustc = Compiler
+
+## Language
+
+language-current =
+    Deine Sprache ist { $language }, und die Sprache des Servers ist { $server_language }.
+    Verfügbare Sprachen: { $available }
+language-unknown = „{ $query }“ ist keine unterstützte Sprache! Verfügbare Sprachen: { $available }
+language-set = Deine Sprache wurde auf { $language } gesetzt!
+language-reset = Deine Sprache wurde zurückgesetzt!
+language-not-set = Du hast keine Sprache festgelegt!
+language-server-set = Die Sprache des Servers wurde auf { $language } gesetzt!
+language-server-reset = Die Sprache des Servers wurde zurückgesetzt!
+language-server-not-set = Für den Server wurde keine Sprache festgelegt!
+
+## Privacy
+
+privacy-status-recorded = Die Befehle, die du ausführst, werden anonym aufgezeichnet, um den Bot zu verbessern, und der anonymisierte Benutzer wird nach { $days } Tagen vergessen. Mit `privacy opt-out` kannst du widersprechen!
+privacy-status-opted-out = Du hast widersprochen, daher werden die Befehle, die du ausführst, nicht mit dir verknüpft!
+privacy-opted-out = Du hast der Nutzungsstatistik widersprochen, und deine bisherigen Befehle sind nicht mehr mit dir verknüpft!
+privacy-opted-in = Du hast der Nutzungsstatistik wieder zugestimmt!
+
+## Scripted commands and tags
+
+custom-invalid-name = `{ $name }` ist kein gültiger Name, verwende bis zu 32 Kleinbuchstaben, Ziffern, Bindestriche und Unterstriche!
+custom-builtin-name = `{ $name }` ist bereits der Name eines eingebauten Befehls!
+custom-name-taken = Der Server hat bereits einen geskripteten Befehl oder einen Tag namens `{ $name }`!
+
+## Scripted commands
+
+script-no-source = Gib den Code des geskripteten Befehls an!
+script-invalid = Das Skript kann nicht kompiliert werden: { $error }
+script-limit = Der Server kann nicht mehr als { $limit } geskriptete Befehle haben!
+script-not-found = Der Server hat keinen geskripteten Befehl namens `{ $name }`!
+script-added = Der geskriptete Befehl `{ $name }` wurde hinzugefügt!
+script-edited = Der geskriptete Befehl `{ $name }` wurde bearbeitet!
+script-removed = Der geskriptete Befehl `{ $name }` wurde entfernt!
+script-list-empty = Der Server hat keine geskripteten Befehle!
+script-list =
+    Der Server hat { $count ->
+        [one] { $count } geskripteten Befehl
+       *[other] { $count } geskriptete Befehle
+    }:
+    { $scripts }
+script-source =
+    Geschrieben von { $author }:
+    ```rust
+    { $source }
+    ```
+script-timed-out = Das Skript hat zu lange gebraucht und wurde angehalten!
+script-failed = Das Skript ist fehlgeschlagen: { $error }
+script-busy = Auf dem Server laufen bereits { $limit } Skripte, bitte versuche es gleich noch einmal!
+
+## Tags
+
+tag-no-content = Gib den Text des Tags an!
+tag-too-long = Ein Tag kann nicht länger als { $limit } Zeichen sein!
+tag-subcommand-name = `{ $name }` kann kein Name eines Tags sein, da `tag { $name }` ein eigener Befehl ist!
+tag-limit = Der Server kann nicht mehr als { $limit } Tags haben!
+tag-not-found = Der Server hat keinen Tag namens `{ $name }`!
+tag-not-owner = Nur der Besitzer von `{ $name }` und Server-Manager können ihn ändern!
+tag-added = Der Tag `{ $name }` wurde hinzugefügt!
+tag-edited = Der Tag `{ $name }` wurde bearbeitet!
+tag-deleted = Der Tag `{ $name }` wurde gelöscht!
+tag-list-empty = Der Server hat keine Tags!
+tag-list =
+    Der Server hat { $count ->
+        [one] { $count } Tag
+       *[other] { $count } Tags
+    }:
+    { $tags }
+tag-uses = { $count ->
+    [one] { $count } Verwendung
+   *[other] { $count } Verwendungen
+}
+tag-info = **{ $name }**
+    Besitzer: { $owner }
+    Erstellt: { $created }
+    Verwendungen: { $uses }
+
+## Themes
+
+theme-title = Server-Design
+theme-current =
+    Fußzeile: { $footer }
+    Zeitstempel: { $timestamp }
+    Antworten auf Befehle: { $reply }
+theme-on = an
+theme-off = aus
+theme-no-footer = keine
+theme-unknown-type = Gib einen Embed-Typ an: { $types }!
+theme-invalid-color = { $color } ist keine Hex-Farbe wie `#1ABC9C`!
+theme-invalid-icon = { $icon } ist weder ein Emoji noch eine https-URL eines Bildes!
+theme-invalid-switch = Gib entweder `on` oder `off` an!
+theme-color-set = Die Farbe von `{ $name }`-Embeds wurde auf { $color } gesetzt!
+theme-color-reset = Die Farbe von `{ $name }`-Embeds wurde zurückgesetzt!
+theme-icon-set = Das Symbol von `{ $name }`-Embeds wurde gesetzt!
+theme-icon-reset = Das Symbol von `{ $name }`-Embeds wurde zurückgesetzt!
+theme-footer-set = Die Fußzeile wurde gesetzt!
+theme-footer-reset = Die Fußzeile wurde entfernt!
+theme-timestamp-set = Zeitstempel sind jetzt { $state }!
+theme-reply-set = Antworten auf Befehle sind jetzt { $state }!
+theme-reset = Das Design des Servers wurde zurückgesetzt!
+
+## Time
+
+time-no-argument = Du hast keinen Ort und keine Zeitzone angegeben!
+time-not-found = Zur Anfrage „{ $query }“ wurde kein Ort und keine Zeitzone gefunden!
+time-unknown-zone = Die Zeitzone von { $location } ist unbekannt!
+time-too-many = Du kannst höchstens { $limit } Orte oder Zeitzonen auf einmal angeben!
+time-set = Deine Zeitzone wurde auf { $zone } gesetzt!
+time-reset = Deine Zeitzone wurde zurückgesetzt!
+time-not-set = Du hast keine Zeitzone festgelegt!
+time-not-set-hint = Du hast keine Zeitzone festgelegt! Verwende dazu `{ $prefix }time set <Ort/Zeitzone>`.
+time-title = Weltuhr
+time-dst = Sommerzeit
+time-difference = { $difference } gegenüber { $reference }
+time-difference-same = Gleiche Zeit wie { $reference }
+time-reference-you = dir
+
+## Arguments
+
+args-missing = Du hast { $name } nicht angegeben!
+
+## Durations
+
+duration-empty = Es wurde keine Dauer angegeben!
+duration-invalid-number = „{ $value }“ ist keine gültige Zahl!
+duration-missing-unit = „{ $value }“ hat keine Zeiteinheit! Versuche etwas wie „{ $value }m“ oder „{ $value } hours“.
+duration-unknown-unit = „{ $unit }“ ist keine bekannte Zeiteinheit! Verwende milliseconds, seconds, minutes, hours, days, weeks oder years.
+duration-invalid-time = „{ $time }“ ist keine gültige Uhrzeit! Versuche etwas wie „5pm“ oder „17:30“.
+duration-time-in-past = Die angegebene Zeit ist bereits vergangen!
+duration-overflow = Die Dauer ist zu lang!
+duration-not-positive = Die Dauer muss länger als null sein!
+
+## Weather
+
+weather-not-found = Zu dieser Anfrage wurde kein Ort gefunden!
+weather-provided-by = Bereitgestellt von OpenWeather
+weather-condition = Wetterlage
+weather-temperature = Temperatur
+weather-feels-like = Gefühlt { $temperature }
+weather-dew-point = Taupunkt { $temperature }
+weather-wind = Wind
+weather-wind-speed = { $speed } m/s
+weather-beaufort = { $name } (Beaufort { $force })
+weather-gusts = Böen bis { $speed } m/s
+weather-humidity = Luftfeuchtigkeit
+weather-cloudiness = Bewölkung
+weather-pressure = Luftdruck
+weather-air-quality = Luftqualität
+weather-uv-index = UV-Index
+weather-sunrise = Sonnenaufgang
+weather-sunset = Sonnenuntergang
+weather-current-date = Aktuelles Datum
+weather-forecast-title = Vorhersage für { $location }
+weather-precipitation = { $amount } mm
+
+aqi-1 = Gut
+aqi-2 = Mäßig gut
+aqi-3 = Mäßig
+aqi-4 = Schlecht
+aqi-5 = Sehr schlecht
+
+uv-low = Niedrig
+uv-moderate = Mäßig
+uv-high = Hoch
+uv-very-high = Sehr hoch
+uv-extreme = Extrem
+
+beaufort-0 = Windstille
+beaufort-1 = Leiser Zug
+beaufort-2 = Leichte Brise
+beaufort-3 = Schwache Brise
+beaufort-4 = Mäßige Brise
+beaufort-5 = Frische Brise
+beaufort-6 = Starker Wind
+beaufort-7 = Steifer Wind
+beaufort-8 = Stürmischer Wind
+beaufort-9 = Sturm
+beaufort-10 = Schwerer Sturm
+beaufort-11 = Orkanartiger Sturm
+beaufort-12 = Orkan
+
+compass-n = Nord
+compass-nne = Nordnordost
+compass-ne = Nordost
+compass-ene = Ostnordost
+compass-e = Ost
+compass-ese = Ostsüdost
+compass-se = Südost
+compass-sse = Südsüdost
+compass-s = Süd
+compass-ssw = Südsüdwest
+compass-sw = Südwest
+compass-wsw = Westsüdwest
+compass-w = West
+compass-wnw = Westnordwest
+compass-nw = Nordwest
+compass-nnw = Nordnordwest
+
+## Weather alerts
+
+alerts-list-empty = Dieser Kanal hat keine Wetterwarnungen abonniert!
+alerts-list =
+    Dieser Kanal erhält Wetterwarnungen für { $count ->
+        [one] { $count } Ort
+       *[other] { $count } Orte
+    }:
+    { $locations }
+alerts-already-subscribed = Dieser Kanal hat Warnungen für { $location } bereits abonniert!
+alerts-subscribed = Dieser Kanal erhält ab jetzt Wetterwarnungen für { $location }!
+alerts-not-subscribed = Dieser Kanal hat keine Warnungen für { $location } abonniert!
+alerts-unsubscribed = Dieser Kanal erhält keine Wetterwarnungen für { $location } mehr!
+alerts-alert-title = { $event } in { $location }
+alerts-alert-period = Von <t:{ $start }:f> bis <t:{ $end }:f>
+alerts-alert-issuer = Herausgegeben von { $sender }
@@ -0,0 +1,383 @@
+## Dispatcher and default replies
+
+no-permissions = You do not have permissions to execute the command!
+missing-permissions = You need the { $permissions } permissions to execute the command!
+subcommand-missing =
+    Specify one of the subcommands:
+    { $usages }
+embed-success = Success!
+embed-failure = Failure!
+embed-confirmation = Confirmation!
+embed-warning = Warning!
+embed-info = Information
+reply-page = Page { $page }/{ $count }
+reply-attached = The output is too long to be shown, so it has been attached as { $file }.
+
+## Command categories
+
+category-custom = Custom
+category-developer = Developer
+category-general = General
+category-utility = Utility
+
+## Help
+
+help-title = { $bot } Help
+help-category-commands = { $category } Commands
+help-developer-only = { $command } (developer-only)
+help-category = Category
+help-aliases = Aliases
+help-usages = Usages
+help-no-command = No command has been found by the query!
+help-permissions = Required Permissions
+help-subcommands = Subcommands
+help-default-subcommand = (default)
+help-no-description = No description has been given.
+help-description-analytics = Sends the most used commands, their error rates and latencies, and the daily active users
+help-description-help = Sends a list of the bot's commands or provides help for the specified command
+help-description-language = Sends your current language or changes your or the server's language
+help-description-language-reset = Resets your language, so the bot replies to you in the server's language again
+help-description-language-server = Changes the language of the server, which applies to everyone who has not chosen their own
+help-description-language-server-reset = Resets the server's language to English
+help-description-language-server-set = Changes the server's language
+help-description-language-set = Changes the language the bot replies to you in
+help-description-language-show = Sends your current language, the server's and the available ones
+help-description-ping = Sends the bot's REST and gateway latency and, with `edit`, the time an edit takes
+help-description-privacy = Sends whether your command usage is recorded or lets you opt out of or back into it
+help-description-privacy-opt-in = Links the commands you run to you again
+help-description-privacy-opt-out = Stops linking the commands you run to you, including those you have already run
+help-description-privacy-status = Sends whether the commands you run are linked to you
+help-description-script = Manages the server's scripted commands, which admins write in Rhai
+help-description-script-add = Adds a scripted command to the server, whose code follows the optional description in a code block
+help-description-script-edit = Replaces the description and the code of a scripted command
+help-description-script-list = Sends the server's scripted commands
+help-description-script-remove = Removes a scripted command from the server
+help-description-script-show = Sends the code of a scripted command
+help-description-shards = Sends the status, latency and guild count of every shard
+help-description-shutdown = Shuts the bot down
+help-description-stats = Sends the bot's uptime, resource usage, cache sizes and build information
+help-description-tag = Sends a tag, a server's own text command, or manages the server's tags
+help-description-tag-add = Adds a tag to the server, whose text may contain `{ "{user}" }`, `{ "{user.name}" }`, `{ "{channel}" }`, `{ "{args}" }`, `{ "{args.1}" }` and so on
+help-description-tag-delete = Deletes a tag from the server, which only its owner and server managers can do
+help-description-tag-edit = Replaces the text of a tag, which only its owner and server managers can do
+help-description-tag-info = Sends who owns a tag, when it was created and how often it has been used
+help-description-tag-list = Sends the server's tags, the most used first
+help-description-tag-show = Sends a tag, which can also be run as a command of its own
+help-description-theme = Sends the server's embed theme or changes its colors, icons, footer, timestamps and replies
+help-description-theme-color = Changes the color of an embed type or, with `reset`, restores the default one
+help-description-theme-footer = Changes the footer of every embed or, with `reset`, removes it
+help-description-theme-icon = Changes the icon of an embed type, which is either an emoji or an https image URL
+help-description-theme-reply = Turns replying to the messages that run commands on or off
+help-description-theme-reset = Resets the server's embed theme to the bot's defaults
+help-description-theme-show = Sends the server's embed theme
+help-description-theme-timestamp = Turns the timestamps of embeds on or off
+help-description-time = Sends the current time in the specified locations or time zones and lets you save your own time zone
+help-description-time-reset = Forgets your time zone
+help-description-time-set = Saves your time zone, which the bot's times and dates are shown in
+help-description-time-show = Sends the current time in the specified locations or time zones or, without any, in your own
+help-description-uptime = Sends the bot's current uptime or its availability and recent outages
+help-description-uptime-current = Sends the bot's current uptime
+help-description-uptime-history = Sends the bot's availability and recent outages
+help-description-weather = Sends the weather or a forecast in the specified location, or manages the channel's weather alerts
+help-description-weather-alerts = Manages the weather alerts the channel receives
+help-description-weather-alerts-list = Sends the locations the channel receives weather alerts for
+help-description-weather-alerts-subscribe = Subscribes the channel to weather alerts for the specified location
+help-description-weather-alerts-unsubscribe = Unsubscribes the channel from weather alerts for the specified location
+help-description-weather-current = Sends the current weather in the specified location
+help-description-weather-forecast = Sends the five-day forecast for the specified location
+
+## Shutdown
+
+shutdown-confirmation = Are you sure?
+
+## Shards
+
+shards-title = Shards ({ $count })
+shards-none = The shard manager is not available!
+shards-entry = **Shard { $shard }**: { $stage }, { $latency }, { $guilds ->
+        [one] { $guilds } server
+       *[other] { $guilds } servers
+    }
+shards-current = {" "}(this shard)
+
+## Analytics
+
+analytics-title = Command Analytics · Last { $period }
+analytics-no-data = No commands have been run during this period!
+analytics-command = **{ $rank }.** `{ $command }`: { $uses } uses, { $errors }% errors, p50 { $p50 } ms, p95 { $p95 } ms
+analytics-overview = Overview
+analytics-overview-value =
+    { $total } runs of { $commands } commands
+    { $errors }% errors
+analytics-daily-users = Daily Active Users
+analytics-daily-users-value =
+    { $average } on average
+    { $peak } at peak over { $days ->
+        [one] { $days } day
+       *[other] { $days } days
+    }
+analytics-no-users = No user data
+
+## Ping
+
+ping-measuring = *Measuring…*
+ping-measuring-edit = *Measuring the edit…*
+ping-title = Latency
+ping-rest = REST
+ping-gateway = Gateway
+ping-edit = Edit round trip
+ping-no-heartbeat = No heartbeat yet
+ping-milliseconds = { $ping } ms
+ping-shard = Shard { $shard } · { $stage }
+
+## Uptime
+
+uptime-title = Uptime
+uptime-last-reboot = Last Reboot
+uptime-last-reboot-local = Last reboot: { $time }
+uptime-history-title = Availability
+uptime-history-day = Last 24 Hours
+uptime-history-week = Last 7 Days
+uptime-history-month = Last 30 Days
+uptime-history-availability = { $percentage }%
+uptime-history-no-data = No data
+uptime-history-outages = Recent Outages
+uptime-history-no-outages = No outages recorded
+uptime-history-outage = <t:{ $start }:f> for { $duration }
+uptime-history-ongoing-outage = <t:{ $start }:f>, ongoing for { $duration }
+uptime-history-tracked-since = Tracked since
+
+## Stats
+
+stats-title = { $bot } Statistics
+stats-unavailable = Unavailable
+stats-uptime = Uptime
+stats-memory = Memory
+stats-cpu-time = CPU Time
+stats-tasks = Tasks
+stats-tasks-value = { $tasks } on { $workers } workers
+stats-cache = Cache
+stats-cache-value =
+    { $guilds } guilds
+    { $channels } channels
+    { $users } users
+stats-commands-run = Commands Run
+stats-reconnects = Reconnects
+stats-version = Version
+stats-rustc = Compiler
+
+## Language
+
+language-current =
+    Your language is { $language }, and the server's language is { $server_language }.
+    Available languages: { $available }
+language-unknown = "{ $query }" is not a supported language! Available languages: { $available }
+language-set = Your language has been set to { $language }!
+language-reset = Your language has been reset!
+language-not-set = You have not set your language!
+language-server-set = The server's language has been set to { $language }!
+language-server-reset = The server's language has been reset!
+language-server-not-set = The server's language has not been set!
+
+## Privacy
+
+privacy-status-recorded = The commands you run are recorded anonymously to improve the bot, and the anonymized user is forgotten after { $days } days. Use `privacy opt-out` to stop it!
+privacy-status-opted-out = You have opted out, so the commands you run are not linked to you!
+privacy-opted-out = You have opted out of usage analytics, and your past commands are no longer linked to you!
+privacy-opted-in = You have opted back into usage analytics!
+
+## Scripted commands and tags
+
+custom-invalid-name = `{ $name }` is not a valid name, use up to 32 lowercase letters, digits, dashes and underscores!
+custom-builtin-name = `{ $name }` is already the name of a built-in command!
+custom-name-taken = The server already has a scripted command or a tag named `{ $name }`!
+
+## Scripted commands
+
+script-no-source = Specify the code of the scripted command!
+script-invalid = The script cannot be compiled: { $error }
+script-limit = The server cannot have more than { $limit } scripted commands!
+script-not-found = The server has no scripted command named `{ $name }`!
+script-added = The scripted command `{ $name }` has been added!
+script-edited = The scripted command `{ $name }` has been edited!
+script-removed = The scripted command `{ $name }` has been removed!
+script-list-empty = The server has no scripted commands!
+script-list =
+    The server has { $count ->
+        [one] { $count } scripted command
+       *[other] { $count } scripted commands
+    }:
+    { $scripts }
+script-source =
+    Written by { $author }:
+    ```rust
+    { $source }
+    ```
+script-timed-out = The script has taken too long and has been stopped!
+script-failed = The script has failed: { $error }
+script-busy = The server is already running { $limit } scripts, please try again in a moment!
+
+## Tags
+
+tag-no-content = Specify the text of the tag!
+tag-too-long = A tag cannot be longer than { $limit } characters!
+tag-subcommand-name = `{ $name }` cannot be the name of a tag, as `tag { $name }` is a command of its own!
+tag-limit = The server cannot have more than { $limit } tags!
+tag-not-found = The server has no tag named `{ $name }`!
+tag-not-owner = Only the owner of `{ $name }` and server managers can change it!
+tag-added = The tag `{ $name }` has been added!
+tag-edited = The tag `{ $name }` has been edited!
+tag-deleted = The tag `{ $name }` has been deleted!
+tag-list-empty = The server has no tags!
+tag-list =
+    The server has { $count ->
+        [one] { $count } tag
+       *[other] { $count } tags
+    }:
+    { $tags }
+tag-uses = { $count ->
+    [one] { $count } use
+   *[other] { $count } uses
+}
+tag-info = **{ $name }**
+    Owner: { $owner }
+    Created: { $created }
+    Uses: { $uses }
+
+## Themes
+
+theme-title = Server theme
+theme-current =
+    Footer: { $footer }
+    Timestamps: { $timestamp }
+    Replies to commands: { $reply }
+theme-on = on
+theme-off = off
+theme-no-footer = none
+theme-unknown-type = Specify an embed type: { $types }!
+theme-invalid-color = { $color } is not a hex color like `#1ABC9C`!
+theme-invalid-icon = { $icon } is neither an emoji nor an https URL of an image!
+theme-invalid-switch = Specify either `on` or `off`!
+theme-color-set = The color of `{ $name }` embeds has been set to { $color }!
+theme-color-reset = The color of `{ $name }` embeds has been reset!
+theme-icon-set = The icon of `{ $name }` embeds has been set!
+theme-icon-reset = The icon of `{ $name }` embeds has been reset!
+theme-footer-set = The footer has been set!
+theme-footer-reset = The footer has been removed!
+theme-timestamp-set = Timestamps have been turned { $state }!
+theme-reply-set = Replies to commands have been turned { $state }!
+theme-reset = The server's theme has been reset!
+
+## Time
+
+time-no-argument = You have provided no location or time zone!
+time-not-found = No location or time zone has been found by the query "{ $query }"!
+time-unknown-zone = The time zone of { $location } is unknown!
+time-too-many = You can list no more than { $limit } locations or time zones at once!
+time-set = Your time zone has been set to { $zone }!
+time-reset = Your time zone has been reset!
+time-not-set = You have not set your time zone!
+time-not-set-hint = You have not set your time zone! Use `{ $prefix }time set <location/time zone>` to do so.
+time-title = World Clock
+time-dst = Daylight saving time
+time-difference = { $difference } from { $reference }
+time-difference-same = Same time as { $reference }
+time-reference-you = you
+
+## Arguments
+
+args-missing = You have not provided the { $name }!
+
+## Durations
+
+duration-empty = No duration has been provided!
+duration-invalid-number = "{ $value }" is not a valid number!
+duration-missing-unit = "{ $value }" has no time unit! Try something like "{ $value }m" or "{ $value } hours".
+duration-unknown-unit = "{ $unit }" is not a known time unit! Use milliseconds, seconds, minutes, hours, days, weeks, or years.
+duration-invalid-time = "{ $time }" is not a valid time of day! Try something like "5pm" or "17:30".
+duration-time-in-past = The specified time has already passed!
+duration-overflow = The duration is too long!
+duration-not-positive = The duration must be longer than zero!
+
+## Weather
+
+weather-not-found = No location has been found by the query!
+weather-provided-by = Provided by OpenWeather
+weather-condition = Condition
+weather-temperature = Temperature
+weather-feels-like = Feels like { $temperature }
+weather-dew-point = Dew point { $temperature }
+weather-wind = Wind
+weather-wind-speed = { $speed } m/s
+weather-beaufort = { $name } (Beaufort { $force })
+weather-gusts = Gusts up to { $speed } m/s
+weather-humidity = Humidity
+weather-cloudiness = Cloudiness
+weather-pressure = Pressure
+weather-air-quality = Air Quality
+weather-uv-index = UV Index
+weather-sunrise = Sunrise
+weather-sunset = Sunset
+weather-current-date = Current Date
+weather-forecast-title = { $location } Forecast
+weather-precipitation = { $amount } mm
+
+aqi-1 = Good
+aqi-2 = Fair
+aqi-3 = Moderate
+aqi-4 = Poor
+aqi-5 = Very Poor
+
+uv-low = Low
+uv-moderate = Moderate
+uv-high = High
+uv-very-high = Very High
+uv-extreme = Extreme
+
+beaufort-0 = Calm
+beaufort-1 = Light air
+beaufort-2 = Light breeze
+beaufort-3 = Gentle breeze
+beaufort-4 = Moderate breeze
+beaufort-5 = Fresh breeze
+beaufort-6 = Strong breeze
+beaufort-7 = Near gale
+beaufort-8 = Gale
+beaufort-9 = Strong gale
+beaufort-10 = Storm
+beaufort-11 = Violent storm
+beaufort-12 = Hurricane force
+
+compass-n = North
+compass-nne = North-northeast
+compass-ne = Northeast
+compass-ene = East-northeast
+compass-e = East
+compass-ese = East-southeast
+compass-se = Southeast
+compass-sse = South-southeast
+compass-s = South
+compass-ssw = South-southwest
+compass-sw = Southwest
+compass-wsw = West-southwest
+compass-w = West
+compass-wnw = West-northwest
+compass-nw = Northwest
+compass-nnw = North-northwest
+
+## Weather alerts
+
+alerts-list-empty = The channel is not subscribed to any weather alerts!
+alerts-list =
+    The channel receives weather alerts for { $count ->
+        [one] { $count } location
+       *[other] { $count } locations
+    }:
+    { $locations }
+alerts-already-subscribed = The channel is already subscribed to alerts for { $location }!
+alerts-subscribed = The channel will now receive weather alerts for { $location }!
+alerts-not-subscribed = The channel is not subscribed to alerts for { $location }!
+alerts-unsubscribed = The channel will no longer receive weather alerts for { $location }!
+alerts-alert-title = { $event } in { $location }
+alerts-alert-period = From <t:{ $start }:f> to <t:{ $end }:f>
+alerts-alert-issuer = Issued by { $sender }
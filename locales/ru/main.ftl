@@ -0,0 +1,418 @@
+## Dispatcher and default replies
+
+no-permissions = У вас нет прав на выполнение этой команды!
+missing-permissions = Для выполнения этой команды вам нужны права { $permissions }!
+subcommand-missing =
+    Укажите одну из подкоманд:
+    { $usages }
+embed-success = Успех!
+embed-failure = Ошибка!
+embed-confirmation = Подтверждение!
+embed-warning = Предупреждение!
+embed-info = Информация
+reply-page = Страница { $page }/{ $count }
+reply-attached = Вывод слишком длинный, поэтому он прикреплён как { $file }.
+
+## Command categories
+
+category-custom = Пользовательские
+category-developer = Разработчик
+category-general = Общее
+category-utility = Утилиты
+
+## Help
+
+help-title = Справка { $bot }
+help-category-commands = Команды: { $category }
+help-developer-only = { $command } (только для разработчиков)
+help-category = Категория
+help-aliases = Псевдонимы
+help-usages = Использование
+help-no-command = По запросу не найдено ни одной команды!
+help-permissions = Необходимые права
+help-subcommands = Подкоманды
+help-default-subcommand = (по умолчанию)
+help-no-description = Описание не указано.
+help-description-analytics = Отправляет самые используемые команды, их долю ошибок и задержки, а также число активных пользователей в день
+help-description-help = Отправляет список команд бота или справку по указанной команде
+help-description-language = Отправляет ваш текущий язык или меняет ваш язык или язык сервера
+help-description-language-reset = Сбрасывает ваш язык, чтобы бот снова отвечал вам на языке сервера
+help-description-language-server = Меняет язык сервера, который действует для всех, кто не выбрал свой
+help-description-language-server-reset = Сбрасывает язык сервера на английский
+help-description-language-server-set = Меняет язык сервера
+help-description-language-set = Меняет язык, на котором бот вам отвечает
+help-description-language-show = Отправляет ваш текущий язык, язык сервера и доступные языки
+help-description-ping = Отправляет REST- и gateway-задержку бота, а с `edit` — время редактирования сообщения
+help-description-privacy = Отправляет, записывается ли использование вами команд, или позволяет отказаться от этого или снова согласиться
+help-description-privacy-opt-in = Снова связывает с вами выполняемые вами команды
+help-description-privacy-opt-out = Перестаёт связывать с вами выполняемые вами команды, включая уже выполненные
+help-description-privacy-status = Отправляет, связываются ли с вами выполняемые вами команды
+help-description-script = Управляет скриптовыми командами сервера, которые администраторы пишут на Rhai
+help-description-script-add = Добавляет на сервер скриптовую команду, код которой следует за необязательным описанием в блоке кода
+help-description-script-edit = Заменяет описание и код скриптовой команды
+help-description-script-list = Отправляет скриптовые команды сервера
+help-description-script-remove = Удаляет скриптовую команду с сервера
+help-description-script-show = Отправляет код скриптовой команды
+help-description-shards = Отправляет статус, задержку и число серверов каждого шарда
+help-description-shutdown = Выключает бота
+help-description-stats = Отправляет время работы, потребление ресурсов, размеры кэша и сведения о сборке бота
+help-description-tag = Отправляет тег, собственную текстовую команду сервера, или управляет тегами сервера
+help-description-tag-add = Добавляет на сервер тег, текст которого может содержать `{ "{user}" }`, `{ "{user.name}" }`, `{ "{channel}" }`, `{ "{args}" }`, `{ "{args.1}" }` и так далее
+help-description-tag-delete = Удаляет тег с сервера, что могут только его владелец и управляющие сервером
+help-description-tag-edit = Заменяет текст тега, что могут только его владелец и управляющие сервером
+help-description-tag-info = Отправляет, кому принадлежит тег, когда он был создан и сколько раз использован
+help-description-tag-list = Отправляет теги сервера, начиная с самых используемых
+help-description-tag-show = Отправляет тег, который также можно вызвать как отдельную команду
+help-description-theme = Отправляет оформление эмбедов сервера или меняет его цвета, значки, подпись, отметки времени и ответы
+help-description-theme-color = Меняет цвет типа эмбедов или, с `reset`, возвращает цвет по умолчанию
+help-description-theme-footer = Меняет подпись всех эмбедов или, с `reset`, убирает её
+help-description-theme-icon = Меняет значок типа эмбедов: эмодзи или https-ссылку на изображение
+help-description-theme-reply = Включает или выключает ответы на сообщения, вызывающие команды
+help-description-theme-reset = Сбрасывает оформление эмбедов сервера к настройкам бота по умолчанию
+help-description-theme-show = Отправляет оформление эмбедов сервера
+help-description-theme-timestamp = Включает или выключает отметки времени в эмбедах
+help-description-time = Отправляет текущее время в указанных местах или часовых поясах и позволяет сохранить свой часовой пояс
+help-description-time-reset = Забывает ваш часовой пояс
+help-description-time-set = Сохраняет ваш часовой пояс, в котором бот показывает время и даты
+help-description-time-show = Отправляет текущее время в указанных местах или часовых поясах или, если ничего не указано, в вашем
+help-description-uptime = Отправляет текущее время работы бота или его доступность и последние сбои
+help-description-uptime-current = Отправляет текущее время работы бота
+help-description-uptime-history = Отправляет доступность и последние сбои бота
+help-description-weather = Отправляет погоду или прогноз в указанном месте или управляет погодными предупреждениями канала
+help-description-weather-alerts = Управляет погодными предупреждениями, которые получает канал
+help-description-weather-alerts-list = Отправляет места, для которых канал получает погодные предупреждения
+help-description-weather-alerts-subscribe = Подписывает канал на погодные предупреждения для указанного места
+help-description-weather-alerts-unsubscribe = Отписывает канал от погодных предупреждений для указанного места
+help-description-weather-current = Отправляет текущую погоду в указанном месте
+help-description-weather-forecast = Отправляет прогноз на пять дней для указанного места
+
+## Shutdown
+
+shutdown-confirmation = Вы уверены?
+
+## Shards
+
+shards-title = Шарды ({ $count })
+shards-none = Менеджер шардов недоступен!
+shards-entry = **Шард { $shard }**: { $stage }, { $latency }, { $guilds ->
+        [one] { $guilds } сервер
+        [few] { $guilds } сервера
+       *[many] { $guilds } серверов
+    }
+shards-current = {" "}(этот шард)
+
+## Analytics
+
+analytics-title = Статистика команд · Последние { $period }
+analytics-no-data = За этот период не было выполнено ни одной команды!
+analytics-command = **{ $rank }.** `{ $command }`: { $uses ->
+        [one] { $uses } использование
+        [few] { $uses } использования
+       *[many] { $uses } использований
+    }, { $errors } % ошибок, p50 { $p50 } мс, p95 { $p95 } мс
+analytics-overview = Обзор
+analytics-overview-value =
+    { $total ->
+        [one] { $total } запуск
+        [few] { $total } запуска
+       *[many] { $total } запусков
+    } { $commands ->
+        [one] { $commands } команды
+       *[other] { $commands } команд
+    }
+    { $errors } % ошибок
+analytics-daily-users = Активные пользователи в день
+analytics-daily-users-value =
+    { $average } в среднем
+    { $peak } в пике за { $days ->
+        [one] { $days } день
+        [few] { $days } дня
+       *[many] { $days } дней
+    }
+analytics-no-users = Нет данных о пользователях
+
+## Ping
+
+ping-measuring = *Измерение…*
+ping-measuring-edit = *Измерение редактирования…*
+ping-title = Задержка
+ping-rest = REST
+ping-gateway = Шлюз
+ping-edit = Редактирование
+ping-no-heartbeat = Heartbeat ещё не получен
+ping-milliseconds = { $ping } мс
+ping-shard = Шард { $shard } · { $stage }
+
+## Uptime
+
+uptime-title = Время работы
+uptime-last-reboot = Последний перезапуск
+uptime-last-reboot-local = Последний перезапуск: { $time }
+uptime-history-title = Доступность
+uptime-history-day = Последние 24 часа
+uptime-history-week = Последние 7 дней
+uptime-history-month = Последние 30 дней
+uptime-history-availability = { $percentage } %
+uptime-history-no-data = Нет данных
+uptime-history-outages = Последние сбои
+uptime-history-no-outages = Сбоев не зафиксировано
+uptime-history-outage = <t:{ $start }:f> на { $duration }
+uptime-history-ongoing-outage = <t:{ $start }:f>, продолжается уже { $duration }
+uptime-history-tracked-since = Отслеживается с
+
+## Stats
+
+stats-title = Статистика { $bot }
+stats-unavailable = Недоступно
+stats-uptime = Время работы
+stats-memory = Память
+stats-cpu-time = Время ЦП
+stats-tasks = Задачи
+stats-tasks-value = { $tasks } на { $workers ->
+        [one] { $workers } потоке
+       *[other] { $workers } потоках
+    }
+stats-cache = Кэш
+stats-cache-value =
+    { $guilds ->
+        [one] { $guilds } сервер
+        [few] { $guilds } сервера
+       *[many] { $guilds } серверов
+    }
+    { $channels ->
+        [one] { $channels } канал
+        [few] { $channels } канала
+       *[many] { $channels } каналов
+    }
+    { $users ->
+        [one] { $users } пользователь
+        [few] { $users } пользователя
+       *[many] { $users } пользователей
+    }
+stats-commands-run = Выполнено команд
+stats-reconnects = Переподключения
+stats-version = Версия
+stats-rustc = Компилятор
+
+## Language
+
+language-current =
+    Ваш язык — { $language }, язык сервера — { $server_language }.
+    Доступные языки: { $available }
+language-unknown = «{ $query }» не является поддерживаемым языком! Доступные языки: { $available }
+language-set = Ваш язык изменён на { $language }!
+language-reset = Ваш язык сброшен!
+language-not-set = Вы не выбирали язык!
+language-server-set = Язык сервера изменён на { $language }!
+language-server-reset = Язык сервера сброшен!
+language-server-not-set = Язык сервера не выбран!
+
+## Privacy
+
+privacy-status-recorded = Выполняемые вами команды анонимно записываются для улучшения бота, а анонимизированный пользователь забывается через { $days ->
+        [one] { $days } день
+        [few] { $days } дня
+       *[many] { $days } дней
+    }. Используйте `privacy opt-out`, чтобы отказаться!
+privacy-status-opted-out = Вы отказались, поэтому выполняемые вами команды не связываются с вами!
+privacy-opted-out = Вы отказались от статистики использования, и ваши прошлые команды больше не связаны с вами!
+privacy-opted-in = Вы снова согласились на статистику использования!
+
+## Scripted commands and tags
+
+custom-invalid-name = `{ $name }` не является допустимым названием, используйте до 32 строчных латинских букв, цифр, дефисов и подчёркиваний!
+custom-builtin-name = `{ $name }` уже является названием встроенной команды!
+custom-name-taken = На сервере уже есть скриптовая команда или тег `{ $name }`!
+
+## Scripted commands
+
+script-no-source = Укажите код скриптовой команды!
+script-invalid = Скрипт не компилируется: { $error }
+script-limit = На сервере не может быть больше { $limit } скриптовых команд!
+script-not-found = На сервере нет скриптовой команды `{ $name }`!
+script-added = Скриптовая команда `{ $name }` добавлена!
+script-edited = Скриптовая команда `{ $name }` изменена!
+script-removed = Скриптовая команда `{ $name }` удалена!
+script-list-empty = На сервере нет скриптовых команд!
+script-list =
+    На сервере { $count ->
+        [one] { $count } скриптовая команда
+        [few] { $count } скриптовые команды
+       *[many] { $count } скриптовых команд
+    }:
+    { $scripts }
+script-source =
+    Автор: { $author }
+    ```rust
+    { $source }
+    ```
+script-timed-out = Скрипт выполнялся слишком долго и был остановлен!
+script-failed = Скрипт завершился с ошибкой: { $error }
+script-busy = На сервере уже выполняется максимум скриптов ({ $limit }), попробуйте ещё раз чуть позже!
+
+## Tags
+
+tag-no-content = Укажите текст тега!
+tag-too-long = Тег не может быть длиннее { $limit } символов!
+tag-subcommand-name = `{ $name }` не может быть названием тега, так как `tag { $name }` является отдельной командой!
+tag-limit = На сервере не может быть больше { $limit } тегов!
+tag-not-found = На сервере нет тега `{ $name }`!
+tag-not-owner = Изменять `{ $name }` могут только его владелец и управляющие сервером!
+tag-added = Тег `{ $name }` добавлен!
+tag-edited = Тег `{ $name }` изменён!
+tag-deleted = Тег `{ $name }` удалён!
+tag-list-empty = На сервере нет тегов!
+tag-list =
+    На сервере { $count ->
+        [one] { $count } тег
+        [few] { $count } тега
+       *[many] { $count } тегов
+    }:
+    { $tags }
+tag-uses = { $count ->
+    [one] { $count } использование
+    [few] { $count } использования
+   *[many] { $count } использований
+}
+tag-info = **{ $name }**
+    Владелец: { $owner }
+    Создан: { $created }
+    Использований: { $uses }
+
+## Themes
+
+theme-title = Оформление сервера
+theme-current =
+    Подпись: { $footer }
+    Отметки времени: { $timestamp }
+    Ответы на команды: { $reply }
+theme-on = вкл.
+theme-off = выкл.
+theme-no-footer = нет
+theme-unknown-type = Укажите тип эмбеда: { $types }!
+theme-invalid-color = { $color } не является шестнадцатеричным цветом вроде `#1ABC9C`!
+theme-invalid-icon = { $icon } не является ни эмодзи, ни https-ссылкой на изображение!
+theme-invalid-switch = Укажите `on` или `off`!
+theme-color-set = Цвет эмбедов `{ $name }` изменён на { $color }!
+theme-color-reset = Цвет эмбедов `{ $name }` сброшен!
+theme-icon-set = Значок эмбедов `{ $name }` изменён!
+theme-icon-reset = Значок эмбедов `{ $name }` сброшен!
+theme-footer-set = Подпись изменена!
+theme-footer-reset = Подпись удалена!
+theme-timestamp-set = Отметки времени: { $state }!
+theme-reply-set = Ответы на команды: { $state }!
+theme-reset = Оформление сервера сброшено!
+
+## Time
+
+time-no-argument = Вы не указали место или часовой пояс!
+time-not-found = По запросу «{ $query }» не найдено ни места, ни часового пояса!
+time-unknown-zone = Часовой пояс места { $location } неизвестен!
+time-too-many = Можно указать не более { $limit } мест или часовых поясов за раз!
+time-set = Ваш часовой пояс изменён на { $zone }!
+time-reset = Ваш часовой пояс сброшен!
+time-not-set = Вы не указывали свой часовой пояс!
+time-not-set-hint = Вы не указывали свой часовой пояс! Используйте для этого `{ $prefix }time set <место/часовой пояс>`.
+time-title = Мировое время
+time-dst = Летнее время
+time-difference = { $difference } относительно { $reference }
+time-difference-same = То же время, что и { $reference }
+time-reference-you = у вас
+
+## Arguments
+
+args-missing = Вы не указали { $name }!
+
+## Durations
+
+duration-empty = Длительность не указана!
+duration-invalid-number = «{ $value }» не является числом!
+duration-missing-unit = У «{ $value }» нет единицы времени! Попробуйте, например, «{ $value }m» или «{ $value } hours».
+duration-unknown-unit = «{ $unit }» не является известной единицей времени! Используйте milliseconds, seconds, minutes, hours, days, weeks или years.
+duration-invalid-time = «{ $time }» не является допустимым временем суток! Попробуйте, например, «5pm» или «17:30».
+duration-time-in-past = Указанное время уже прошло!
+duration-overflow = Слишком большая длительность!
+duration-not-positive = Длительность должна быть больше нуля!
+
+## Weather
+
+weather-not-found = По запросу не найдено ни одного места!
+weather-provided-by = Данные OpenWeather
+weather-condition = Погода
+weather-temperature = Температура
+weather-feels-like = Ощущается как { $temperature }
+weather-dew-point = Точка росы { $temperature }
+weather-wind = Ветер
+weather-wind-speed = { $speed } м/с
+weather-beaufort = { $name } (Бофорт { $force })
+weather-gusts = Порывы до { $speed } м/с
+weather-humidity = Влажность
+weather-cloudiness = Облачность
+weather-pressure = Давление
+weather-air-quality = Качество воздуха
+weather-uv-index = УФ-индекс
+weather-sunrise = Восход
+weather-sunset = Закат
+weather-current-date = Текущая дата
+weather-forecast-title = Прогноз: { $location }
+weather-precipitation = { $amount } мм
+
+aqi-1 = Хорошее
+aqi-2 = Удовлетворительное
+aqi-3 = Умеренное
+aqi-4 = Плохое
+aqi-5 = Очень плохое
+
+uv-low = Низкий
+uv-moderate = Умеренный
+uv-high = Высокий
+uv-very-high = Очень высокий
+uv-extreme = Экстремальный
+
+beaufort-0 = Штиль
+beaufort-1 = Тихий ветер
+beaufort-2 = Лёгкий ветер
+beaufort-3 = Слабый ветер
+beaufort-4 = Умеренный ветер
+beaufort-5 = Свежий ветер
+beaufort-6 = Сильный ветер
+beaufort-7 = Крепкий ветер
+beaufort-8 = Очень крепкий ветер
+beaufort-9 = Шторм
+beaufort-10 = Сильный шторм
+beaufort-11 = Жестокий шторм
+beaufort-12 = Ураган
+
+compass-n = Северный
+compass-nne = Северо-северо-восточный
+compass-ne = Северо-восточный
+compass-ene = Восточно-северо-восточный
+compass-e = Восточный
+compass-ese = Восточно-юго-восточный
+compass-se = Юго-восточный
+compass-sse = Юго-юго-восточный
+compass-s = Южный
+compass-ssw = Юго-юго-западный
+compass-sw = Юго-западный
+compass-wsw = Западно-юго-западный
+compass-w = Западный
+compass-wnw = Западно-северо-западный
+compass-nw = Северо-западный
+compass-nnw = Северо-северо-западный
+
+## Weather alerts
+
+alerts-list-empty = Канал не подписан ни на какие погодные предупреждения!
+alerts-list =
+    Канал получает погодные предупреждения для { $count ->
+        [one] { $count } места
+       *[other] { $count } мест
+    }:
+    { $locations }
+alerts-already-subscribed = Канал уже подписан на предупреждения для { $location }!
+alerts-subscribed = Канал теперь будет получать погодные предупреждения для { $location }!
+alerts-not-subscribed = Канал не подписан на предупреждения для { $location }!
+alerts-unsubscribed = Канал больше не будет получать погодные предупреждения для { $location }!
+alerts-alert-title = { $event } в { $location }
+alerts-alert-period = С <t:{ $start }:f> по <t:{ $end }:f>
+alerts-alert-issuer = Источник: { $sender }
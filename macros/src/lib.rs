@@ -0,0 +1,646 @@
+//! The `#[command]` attribute, which turns an async function into one of the bot's commands,
+//! and `message_id!`, which checks the message IDs passed to `tr!` against the catalog
+
+use proc_macro::TokenStream;
+
+use proc_macro2::TokenStream as TokenStream2;
+
+use quote::{format_ident, quote};
+
+use std::{env, fs, path::Path};
+
+use syn::{
+    parse::{Parse, ParseStream, Parser},
+    parse2,
+    punctuated::Punctuated,
+    BinOp,
+    Error,
+    Expr,
+    FnArg,
+    GenericArgument,
+    Ident,
+    ItemFn,
+    Lit,
+    LitStr,
+    Pat,
+    PathArguments,
+    Result,
+    Token,
+    Type,
+};
+
+/// Declares a command, e.g.
+///
+/// ```ignore
+/// #[command(name = "analytics", description = "Sends the most used commands", category = Developer)]
+/// async fn analytics(cx: &Context, message: &Message, period: Option<DurationArgument>) -> CommandResult {
+///     ...
+/// }
+/// ```
+///
+/// generates an `AnalyticsCommand` implementing `Command`. `aliases = ["..."]`, `usages = [["..."]]` and
+/// `permissions = MANAGE_CHANNELS` are optional.
+///
+/// Every parameter after the context and the message is an `Argument` taken from the words the user has typed,
+/// one word each except for the last parameter, which takes the rest. `Option<T>` makes an argument optional,
+/// an optional `Flag` is only set when the user has written the parameter's name, and a single `args: &[&str]` parameter receives the words as they are instead.
+#[proc_macro_attribute]
+pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
+    expand(attr.into(), item.into())
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Expands to the message ID it is given, failing to compile if the English catalog has no such message
+#[proc_macro]
+pub fn message_id(input: TokenStream) -> TokenStream {
+    let id = syn::parse_macro_input!(input as LitStr);
+
+    check_message_id(&id)
+        .map(|_| quote!(#id))
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// The other catalogs are checked against the English one by the bot's tests
+const CATALOG: &str = "locales/en-US/main.ftl";
+
+fn check_message_id(id: &LitStr) -> Result<()> {
+    let dir = env::var("CARGO_MANIFEST_DIR").map_err(|e| Error::new(id.span(), e))?;
+    let catalog = fs::read_to_string(Path::new(&dir).join(CATALOG))
+        .map_err(|e| Error::new(id.span(), format!("failed to read `{}`: {}", CATALOG, e)))?;
+
+    if catalog_ids(&catalog).any(|message| message == id.value()) {
+        Ok(())
+    } else {
+        Err(Error::new(id.span(), format!("the catalog has no message `{}`", id.value())))
+    }
+}
+
+/// The IDs of the messages in a Fluent catalog, which are the only unindented lines starting with a letter
+fn catalog_ids(catalog: &str) -> impl Iterator<Item = &str> {
+    catalog.lines().filter_map(|line| {
+        let (id, _) = line.split_once('=')?;
+        let id = id.trim_end();
+        let valid = id.starts_with(|c: char| c.is_ascii_alphabetic())
+            && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+        valid.then_some(id)
+    })
+}
+
+/// Discord rejects longer slash command descriptions
+const MAX_DESCRIPTION_LENGTH: usize = 100;
+
+const KEYS: [&str; 6] = ["name", "description", "category", "aliases", "usages", "permissions"];
+
+struct Declaration {
+    name: LitStr,
+    description: LitStr,
+    category: Ident,
+    aliases: Vec<LitStr>,
+    usages: Option<Vec<Vec<LitStr>>>,
+    /// The names of `Permissions` flags, e.g. `MANAGE_CHANNELS | MANAGE_GUILD`
+    permissions: Vec<Ident>,
+}
+
+struct Property {
+    key: Ident,
+    value: Expr,
+}
+
+impl Parse for Property {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let key = input.parse()?;
+
+        input.parse::<Token![=]>()?;
+
+        Ok(Self { key, value: input.parse()? })
+    }
+}
+
+enum Parameters {
+    /// The words are passed on untouched
+    Raw,
+    Typed(Vec<Parameter>),
+}
+
+struct Parameter {
+    name: Ident,
+    /// The type that implements `Argument`, i.e. without the `Option`
+    kind: Type,
+    optional: bool,
+}
+
+impl Parameter {
+    /// The name shown in usages, e.g. `command name (optional)` for `command_name: Option<T>`
+    fn usage(&self) -> String {
+        let name = self.name.to_string().replace('_', " ");
+
+        if self.optional {
+            format!("{} (optional)", name)
+        } else {
+            name
+        }
+    }
+}
+
+fn string(value: &Expr) -> Result<LitStr> {
+    match value {
+        Expr::Lit(expr) => match &expr.lit {
+            Lit::Str(lit) => Ok(lit.clone()),
+            lit => Err(Error::new_spanned(lit, "expected a string")),
+        },
+        _ => Err(Error::new_spanned(value, "expected a string")),
+    }
+}
+
+fn permissions(value: &Expr) -> Result<Vec<Ident>> {
+    match value {
+        Expr::Binary(expr) if matches!(expr.op, BinOp::BitOr(_)) => {
+            let mut flags = permissions(&expr.left)?;
+
+            flags.extend(permissions(&expr.right)?);
+
+            Ok(flags)
+        }
+        Expr::Path(path) if path.path.get_ident().is_some() => Ok(vec![path.path.get_ident().cloned().unwrap()]),
+        _ => Err(Error::new_spanned(value, "expected permissions such as `MANAGE_CHANNELS | MANAGE_GUILD`")),
+    }
+}
+
+fn strings(value: &Expr) -> Result<Vec<LitStr>> {
+    match value {
+        Expr::Array(array) => array.elems.iter().map(string).collect(),
+        _ => Err(Error::new_spanned(value, "expected an array of strings")),
+    }
+}
+
+/// Names are matched against a single lowercased word, so anything else could never be run
+fn check_name(name: &LitStr) -> Result<()> {
+    let value = name.value();
+
+    if value.is_empty() || value.contains(char::is_whitespace) {
+        Err(Error::new_spanned(name, "a command name must be a single word"))
+    } else if value != value.to_lowercase() {
+        Err(Error::new_spanned(name, "a command name must be lowercase"))
+    } else {
+        Ok(())
+    }
+}
+
+impl Declaration {
+    fn parse(attr: TokenStream2) -> Result<Self> {
+        let properties = Punctuated::<Property, Token![,]>::parse_terminated.parse2(attr)?;
+
+        let mut name = None;
+        let mut description = None;
+        let mut category = None;
+        let mut aliases = None;
+        let mut usages = None;
+        let mut required_permissions = None;
+
+        for Property { key, value } in properties {
+            let duplicate = match key.to_string().as_str() {
+                "name" => name.replace(string(&value)?).is_some(),
+                "description" => description.replace(string(&value)?).is_some(),
+                "category" => match &value {
+                    Expr::Path(path) if path.path.get_ident().is_some() => {
+                        category.replace(path.path.get_ident().cloned().unwrap()).is_some()
+                    }
+                    _ => return Err(Error::new_spanned(value, "expected a category such as `General`")),
+                },
+                "aliases" => aliases.replace(strings(&value)?).is_some(),
+                "usages" => match &value {
+                    Expr::Array(array) => {
+                        let value = array.elems.iter().map(strings).collect::<Result<Vec<_>>>()?;
+
+                        usages.replace(value).is_some()
+                    }
+                    _ => return Err(Error::new_spanned(value, "expected an array of usages such as `[[\"location\"]]`")),
+                },
+                "permissions" => required_permissions.replace(permissions(&value)?).is_some(),
+                _ => {
+                    return Err(Error::new_spanned(
+                        &key,
+                        format!("unknown property `{}`, expected one of {}", key, KEYS.join(", ")),
+                    ));
+                }
+            };
+
+            if duplicate {
+                return Err(Error::new_spanned(&key, format!("`{}` is declared more than once", key)));
+            }
+        }
+
+        let missing = |key: &str| Error::new(proc_macro2::Span::call_site(), format!("a command needs a `{}`", key));
+
+        let declaration = Self {
+            name: name.ok_or_else(|| missing("name"))?,
+            description: description.ok_or_else(|| missing("description"))?,
+            category: category.ok_or_else(|| missing("category"))?,
+            aliases: aliases.unwrap_or_default(),
+            usages,
+            permissions: required_permissions.unwrap_or_default(),
+        };
+
+        check_name(&declaration.name)?;
+
+        for alias in &declaration.aliases {
+            check_name(alias)?;
+
+            if alias.value() == declaration.name.value() {
+                return Err(Error::new_spanned(alias, "an alias cannot repeat the command's name"));
+            }
+        }
+
+        let description = declaration.description.value();
+
+        if description.is_empty() || description.chars().count() > MAX_DESCRIPTION_LENGTH {
+            return Err(Error::new_spanned(
+                &declaration.description,
+                format!("a description must be between 1 and {} characters long", MAX_DESCRIPTION_LENGTH),
+            ));
+        }
+
+        Ok(declaration)
+    }
+}
+
+/// Unwraps `Option<T>` to `T`
+fn option_argument(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return None,
+    };
+
+    let segment = path.segments.last().filter(|segment| segment.ident == "Option")?;
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) if arguments.args.len() == 1 => match arguments.args.first() {
+            Some(GenericArgument::Type(ty)) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether the type is `&[&str]`
+fn is_raw(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => match &*reference.elem {
+            Type::Slice(slice) => matches!(&*slice.elem, Type::Reference(word) if matches!(&*word.elem, Type::Path(path) if path.path.is_ident("str"))),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn parameters(function: &ItemFn) -> Result<Parameters> {
+    let signature = &function.sig;
+
+    if signature.asyncness.is_none() {
+        return Err(Error::new_spanned(signature.fn_token, "a command must be an async function"));
+    }
+
+    if !signature.generics.params.is_empty() {
+        return Err(Error::new_spanned(&signature.generics, "a command cannot be generic"));
+    }
+
+    if signature.inputs.len() < 2 {
+        return Err(Error::new_spanned(
+            &signature.inputs,
+            "a command takes the context and the message before its arguments",
+        ));
+    }
+
+    let mut inputs = Vec::new();
+
+    for input in &signature.inputs {
+        match input {
+            FnArg::Receiver(receiver) => return Err(Error::new_spanned(receiver, "a command cannot take `self`")),
+            FnArg::Typed(typed) => inputs.push(typed),
+        }
+    }
+
+    let arguments = &inputs[2..];
+
+    if let [raw] = arguments {
+        if is_raw(&raw.ty) {
+            return Ok(Parameters::Raw);
+        }
+    }
+
+    let mut parameters = Vec::<Parameter>::new();
+
+    for argument in arguments {
+        if is_raw(&argument.ty) {
+            return Err(Error::new_spanned(argument, "`&[&str]` has to be the only argument"));
+        }
+
+        let name = match &*argument.pat {
+            Pat::Ident(pat) => pat.ident.clone(),
+            pat => return Err(Error::new_spanned(pat, "an argument needs a plain name")),
+        };
+
+        let (kind, optional) = match option_argument(&argument.ty) {
+            Some(kind) => (kind.clone(), true),
+            None => ((*argument.ty).clone(), false),
+        };
+
+        if !optional && parameters.iter().any(|parameter| parameter.optional) {
+            return Err(Error::new_spanned(argument, "a required argument cannot follow an optional one"));
+        }
+
+        parameters.push(Parameter { name, kind, optional });
+    }
+
+    Ok(Parameters::Typed(parameters))
+}
+
+/// `weather_alerts` becomes `WeatherAlertsCommand`
+fn struct_name(function: &Ident) -> Ident {
+    let name = function.to_string()
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+
+            chars.next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<String>();
+
+    format_ident!("{}Command", name, span = function.span())
+}
+
+fn expand(attr: TokenStream2, item: TokenStream2) -> Result<TokenStream2> {
+    let declaration = Declaration::parse(attr)?;
+    let function = parse2::<ItemFn>(item)?;
+    let parameters = parameters(&function)?;
+
+    let Declaration { name, description, category, aliases, usages, permissions } = &declaration;
+
+    let function_name = &function.sig.ident;
+    let command = struct_name(function_name);
+
+    let usages = match (usages, &parameters) {
+        (Some(usages), _) => usages.iter().map(|usage| quote!(vec![#(#usage),*])).collect::<Vec<_>>(),
+        (None, Parameters::Typed(parameters)) if !parameters.is_empty() => {
+            let usage = parameters.iter().map(Parameter::usage);
+
+            vec![quote!(vec![#(#usage),*])]
+        }
+        (None, _) => Vec::new(),
+    };
+
+    let (options, invoke) = match &parameters {
+        Parameters::Raw => (Vec::new(), quote!(#function_name(cx, message, args).await)),
+        Parameters::Typed(parameters) => {
+            let options = parameters.iter()
+                .map(|Parameter { name, kind, optional }| {
+                    let name = name.to_string();
+                    let required = !optional;
+
+                    quote! {
+                        crate::cmds::CommandOption {
+                            name: #name,
+                            description: <#kind as crate::cmds::args::Argument>::kind(),
+                            required: #required,
+                            flag: <#kind as crate::cmds::args::Argument>::is_flag(),
+                        }
+                    }
+                })
+                .collect();
+
+            let names = parameters.iter().map(|parameter| &parameter.name).collect::<Vec<_>>();
+
+            let parsed = parameters.iter()
+                .enumerate()
+                .map(|(i, parameter)| {
+                    let Parameter { name, kind, optional } = parameter;
+                    let last = i == parameters.len() - 1;
+                    let name_str = name.to_string();
+                    let take = quote!(crate::cmds::args::take::<#kind>(&mut args, #last, #name_str, message)?);
+
+                    if *optional {
+                        quote!(let #name = #take;)
+                    } else {
+                        let usage = parameter.usage();
+
+                        quote! {
+                            let #name = #take.ok_or_else(|| {
+                                crate::i18n::tr!(crate::i18n::get_language(message), "args-missing", name = #usage)
+                            })?;
+                        }
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let invoke = if parsed.is_empty() {
+                quote!(#function_name(cx, message).await)
+            } else {
+                quote! {
+                    let mut args = args;
+
+                    #(#parsed)*
+
+                    #function_name(cx, message, #(#names),*).await
+                }
+            };
+
+            (options, invoke)
+        }
+    };
+
+    // Keeps `args` from being reported as unused by commands that take no arguments
+    let args = match &parameters {
+        Parameters::Typed(parameters) if parameters.is_empty() => format_ident!("_args"),
+        _ => format_ident!("args"),
+    };
+
+    Ok(quote! {
+        pub struct #command;
+
+        #[::serenity::async_trait]
+        impl crate::cmds::Command for #command {
+            fn name(&self) -> &'static str {
+                #name
+            }
+
+            fn description(&self) -> &'static str {
+                #description
+            }
+
+            fn category(&self) -> crate::cmds::CommandCategory {
+                crate::cmds::CommandCategory::#category
+            }
+
+            fn aliases(&self) -> ::std::vec::Vec<&str> {
+                vec![#(#aliases),*]
+            }
+
+            fn usages(&self) -> crate::cmds::CommandUsages {
+                vec![#(#usages),*]
+            }
+
+            fn permissions(&self) -> ::serenity::model::permissions::Permissions {
+                ::serenity::model::permissions::Permissions::empty()
+                    #(| ::serenity::model::permissions::Permissions::#permissions)*
+            }
+
+            fn options(&self) -> ::std::vec::Vec<crate::cmds::CommandOption> {
+                vec![#(#options),*]
+            }
+
+            async fn invoke(
+                &self,
+                cx: &::serenity::client::Context,
+                message: &::serenity::model::channel::Message,
+                #args: &[&str],
+            ) -> ::std::result::Result<(), ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Send + ::std::marker::Sync>> {
+                #invoke
+            }
+        }
+
+        #function
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use syn::{parse_quote, Block, File, ImplItem, Item};
+
+    #[test]
+    fn catalog_messages_are_found() {
+        let catalog = "# A comment\nping = Pong!\n-brand = Bot\nhelp-title = Help\n    .tooltip = = Help =\n  continued = text\n";
+
+        assert_eq!(catalog_ids(catalog).collect::<Vec<_>>(), vec!["ping", "help-title"]);
+    }
+
+    fn error(attr: TokenStream2, item: TokenStream2) -> String {
+        match expand(attr, item) {
+            Ok(_) => panic!("The declaration was accepted"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    /// The expansion parsed back into items, so that the tests compare syntax rather than how tokens are printed
+    fn expand_items(attr: TokenStream2, item: TokenStream2) -> File {
+        parse2(expand(attr, item).unwrap()).unwrap()
+    }
+
+    /// The body of the generated `impl Command` method called `name`
+    fn method(items: &File, name: &str) -> Block {
+        items.items.iter()
+            .find_map(|item| match item {
+                Item::Impl(implementation) => implementation.items.iter().find_map(|item| match item {
+                    ImplItem::Method(method) if method.sig.ident == name => Some(method.block.clone()),
+                    _ => None,
+                }),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("The expansion has no method `{}`", name))
+    }
+
+    #[test]
+    fn typed_arguments_are_parsed_in_order() {
+        let items = expand_items(
+            quote!(name = "remind", description = "Reminds you", category = Utility, aliases = ["remindme"]),
+            quote!(async fn remind(cx: &Context, message: &Message, delay: DurationArgument, note_text: Option<Text>) -> CommandResult {}),
+        );
+
+        assert!(matches!(&items.items[0], Item::Struct(command) if command.ident == "RemindCommand"));
+        assert!(matches!(&items.items[1], Item::Impl(implementation) if *implementation.self_ty == parse_quote!(RemindCommand)));
+        assert!(matches!(&items.items[2], Item::Fn(function) if function.sig.ident == "remind"));
+
+        assert_eq!(method(&items, "aliases"), parse_quote!({ vec!["remindme"] }));
+        assert_eq!(method(&items, "usages"), parse_quote!({ vec![vec!["delay", "note text (optional)"]] }));
+        assert_eq!(method(&items, "options"), parse_quote!({
+            vec![
+                crate::cmds::CommandOption {
+                    name: "delay",
+                    description: <DurationArgument as crate::cmds::args::Argument>::kind(),
+                    required: true,
+                    flag: <DurationArgument as crate::cmds::args::Argument>::is_flag(),
+                },
+                crate::cmds::CommandOption {
+                    name: "note_text",
+                    description: <Text as crate::cmds::args::Argument>::kind(),
+                    required: false,
+                    flag: <Text as crate::cmds::args::Argument>::is_flag(),
+                }
+            ]
+        }));
+        assert_eq!(method(&items, "invoke"), parse_quote!({
+            let mut args = args;
+
+            let delay = crate::cmds::args::take::<DurationArgument>(&mut args, false, "delay", message)?.ok_or_else(|| {
+                crate::i18n::tr!(crate::i18n::get_language(message), "args-missing", name = "delay")
+            })?;
+            let note_text = crate::cmds::args::take::<Text>(&mut args, true, "note_text", message)?;
+
+            remind(cx, message, delay, note_text).await
+        }));
+    }
+
+    #[test]
+    fn permissions_are_combined() {
+        let items = expand_items(
+            quote!(name = "subscribe", description = "Subscribes", category = Utility, permissions = MANAGE_CHANNELS | MANAGE_GUILD),
+            quote!(async fn subscribe(cx: &Context, message: &Message, args: &[&str]) -> CommandResult {}),
+        );
+
+        assert_eq!(method(&items, "permissions"), parse_quote!({
+            ::serenity::model::permissions::Permissions::empty()
+                | ::serenity::model::permissions::Permissions::MANAGE_CHANNELS
+                | ::serenity::model::permissions::Permissions::MANAGE_GUILD
+        }));
+
+        assert!(error(
+            quote!(name = "subscribe", description = "Subscribes", category = Utility, permissions = "MANAGE_GUILD"),
+            quote!(async fn subscribe(cx: &Context, message: &Message) -> CommandResult {}),
+        ).starts_with("expected permissions"));
+    }
+
+    #[test]
+    fn raw_arguments_are_passed_on() {
+        let items = expand_items(
+            quote!(name = "privacy", description = "Sends your privacy settings", category = Utility, usages = [["opt-out"], ["opt-in"]]),
+            quote!(async fn privacy(cx: &Context, message: &Message, args: &[&str]) -> CommandResult {}),
+        );
+
+        assert_eq!(method(&items, "usages"), parse_quote!({ vec![vec!["opt-out"], vec!["opt-in"]] }));
+        assert_eq!(method(&items, "options"), parse_quote!({ vec![] }));
+        assert_eq!(method(&items, "invoke"), parse_quote!({ privacy(cx, message, args).await }));
+    }
+
+    #[test]
+    fn invalid_declarations_are_rejected() {
+        let item = quote!(async fn ping(cx: &Context, message: &Message) -> CommandResult {});
+
+        assert_eq!(error(quote!(description = "Pong", category = General), item.clone()), "a command needs a `name`");
+        assert_eq!(error(quote!(name = "Ping", description = "Pong", category = General), item.clone()), "a command name must be lowercase");
+        assert_eq!(error(quote!(name = "ping pong", description = "Pong", category = General), item.clone()), "a command name must be a single word");
+        assert_eq!(error(quote!(name = "ping", name = "pong", description = "Pong", category = General), item.clone()), "`name` is declared more than once");
+        assert_eq!(error(quote!(name = "ping", description = "", category = General), item.clone()), "a description must be between 1 and 100 characters long");
+        assert_eq!(error(quote!(name = "ping", description = "Pong", category = General, aliases = ["ping"]), item.clone()), "an alias cannot repeat the command's name");
+        assert!(error(quote!(name = "ping", description = "Pong", category = General, hidden = true), item).starts_with("unknown property `hidden`"));
+
+        let attr = quote!(name = "ping", description = "Pong", category = General);
+
+        assert_eq!(error(attr.clone(), quote!(fn ping(cx: &Context, message: &Message) {})), "a command must be an async function");
+        assert_eq!(error(attr.clone(), quote!(async fn ping(cx: &Context) {})), "a command takes the context and the message before its arguments");
+        assert_eq!(
+            error(attr.clone(), quote!(async fn ping(cx: &Context, message: &Message, edit: Option<Word>, times: Number) {})),
+            "a required argument cannot follow an optional one",
+        );
+        assert_eq!(
+            error(attr, quote!(async fn ping(cx: &Context, message: &Message, edit: Word, args: &[&str]) {})),
+            "`&[&str]` has to be the only argument",
+        );
+    }
+}
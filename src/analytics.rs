@@ -0,0 +1,335 @@
+//! Local command usage analytics. Users are only stored as salted hashes,
+//! which are stripped after `ANALYTICS_USER_RETENTION` and never recorded for users who opted out.
+
+use crate::{config, metrics::Outcome, storage::JsonStore};
+
+use chrono::Utc;
+
+use lazy_static::lazy_static;
+
+use serde::{Deserialize, Serialize};
+
+use sha2::{Digest, Sha256};
+
+use std::{collections::HashSet, time::Duration};
+
+use tracing::error;
+
+#[cfg(feature = "developer-commands")]
+use chrono::{NaiveDate, NaiveDateTime};
+
+#[cfg(feature = "developer-commands")]
+use itertools::Itertools;
+
+#[cfg(feature = "developer-commands")]
+use std::collections::HashMap;
+
+#[cfg(feature = "utility-commands")]
+use std::io;
+
+lazy_static! {
+    pub static ref ANALYTICS: JsonStore<Analytics> = JsonStore::open("analytics.json");
+    pub static ref OPTED_OUT_USERS: JsonStore<HashSet<u64>> = JsonStore::open("analytics_opt_out.json");
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Analytics {
+    /// Generated on first use so that user hashes cannot be reversed by hashing every possible ID
+    salt: String,
+    pub records: Vec<CommandRecord>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommandRecord {
+    pub command: String,
+    pub guild: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    pub outcome: Outcome,
+    pub latency_ms: u64,
+    /// Unix time in seconds
+    pub time: i64,
+}
+
+#[cfg(feature = "developer-commands")]
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+    pub total: usize,
+    pub failures: usize,
+    /// Sorted by usage, the most used first
+    pub commands: Vec<CommandSummary>,
+    /// Distinct users per day, for the days within the user retention
+    pub daily_active_users: Vec<(NaiveDate, usize)>,
+}
+
+#[cfg(feature = "developer-commands")]
+#[derive(Debug, PartialEq)]
+pub struct CommandSummary {
+    pub name: String,
+    pub uses: usize,
+    pub failures: usize,
+    pub p50: Duration,
+    pub p95: Duration,
+}
+
+impl Analytics {
+    fn hash_user(&mut self, user: u64) -> String {
+        if self.salt.is_empty() {
+            self.salt = base64::encode(rand::random::<[u8; 32]>());
+        }
+
+        hash_user(&self.salt, user)
+    }
+
+    fn prune(&mut self, now: i64) {
+        let cutoff = now - config::ANALYTICS_RETENTION.as_secs() as i64;
+        let user_cutoff = now - config::ANALYTICS_USER_RETENTION.as_secs() as i64;
+
+        self.records.retain(|record| record.time >= cutoff);
+
+        for record in self.records.iter_mut().filter(|record| record.time < user_cutoff) {
+            record.user = None;
+        }
+    }
+}
+
+fn hash_user(salt: &str, user: u64) -> String {
+    let digest = Sha256::new()
+        .chain_update(salt)
+        .chain_update(user.to_be_bytes())
+        .finalize();
+
+    digest.iter()
+        .take(16)
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Uses nearest-rank percentiles, `latencies` have to be sorted
+#[cfg(feature = "developer-commands")]
+fn percentile(latencies: &[u64], percentile: f64) -> Duration {
+    if latencies.is_empty() {
+        return Duration::ZERO;
+    }
+
+    let rank = (percentile * latencies.len() as f64).ceil() as usize;
+
+    Duration::from_millis(latencies[rank.clamp(1, latencies.len()) - 1])
+}
+
+#[cfg(feature = "developer-commands")]
+impl Summary {
+    /// Summarizes the records made since `since` (Unix time in seconds)
+    pub fn from_records(records: &[CommandRecord], since: i64) -> Self {
+        let records = records.iter()
+            .filter(|record| record.time >= since)
+            .collect::<Vec<_>>();
+
+        let commands = records.iter()
+            .into_group_map_by(|record| record.command.as_str())
+            .into_iter()
+            .map(|(name, records)| {
+                // Refused commands never ran, so their latency means nothing
+                let latencies = records.iter()
+                    .filter(|record| record.outcome != Outcome::Denied)
+                    .map(|record| record.latency_ms)
+                    .sorted()
+                    .collect::<Vec<_>>();
+
+                CommandSummary {
+                    name: name.to_owned(),
+                    uses: records.len(),
+                    failures: records.iter().filter(|record| record.outcome == Outcome::Failure).count(),
+                    p50: percentile(&latencies, 0.5),
+                    p95: percentile(&latencies, 0.95),
+                }
+            })
+            .sorted_by(|a, b| b.uses.cmp(&a.uses).then_with(|| a.name.cmp(&b.name)))
+            .collect::<Vec<_>>();
+
+        let mut users_per_day = HashMap::<NaiveDate, HashSet<&str>>::new();
+
+        for record in &records {
+            if let Some(ref user) = record.user {
+                let day = NaiveDateTime::from_timestamp(record.time, 0).date();
+
+                users_per_day.entry(day).or_default().insert(user);
+            }
+        }
+
+        Self {
+            total: records.len(),
+            failures: commands.iter().map(|command| command.failures).sum(),
+            commands,
+            daily_active_users: users_per_day.into_iter()
+                .map(|(day, users)| (day, users.len()))
+                .sorted()
+                .collect(),
+        }
+    }
+}
+
+pub fn is_opted_out(user: u64) -> bool {
+    OPTED_OUT_USERS.read().contains(&user)
+}
+
+/// Stops recording the user and forgets which of the existing records were theirs
+#[cfg(feature = "utility-commands")]
+pub async fn opt_out(user: u64) -> io::Result<()> {
+    OPTED_OUT_USERS.update(|users| users.insert(user)).await?;
+
+    ANALYTICS.update(|analytics| {
+        let hash = analytics.hash_user(user);
+
+        for record in analytics.records.iter_mut().filter(|record| record.user.as_ref() == Some(&hash)) {
+            record.user = None;
+        }
+    }).await
+}
+
+#[cfg(feature = "utility-commands")]
+pub async fn opt_in(user: u64) -> io::Result<()> {
+    OPTED_OUT_USERS.update(|users| users.remove(&user)).await.map(|_| ())
+}
+
+/// Records the command usage in memory, it is written by `flush`
+pub fn record(command: &str, guild: Option<u64>, user: u64, outcome: Outcome, latency: Duration) {
+    let opted_out = is_opted_out(user);
+    let now = Utc::now().timestamp();
+
+    ANALYTICS.modify(|analytics| {
+        let user = if opted_out { None } else { Some(analytics.hash_user(user)) };
+
+        analytics.records.push(CommandRecord {
+            command: command.to_owned(),
+            guild,
+            user,
+            outcome,
+            latency_ms: latency.as_millis() as u64,
+            time: now,
+        });
+    });
+}
+
+/// Drops the expired records and writes the ones made since the last flush
+pub async fn flush() {
+    ANALYTICS.modify(|analytics| analytics.prune(Utc::now().timestamp()));
+
+    if let Err(e) = ANALYTICS.flush().await {
+        error!(error = %e, "Failed to save the command usage");
+    }
+}
+
+pub async fn flush_periodically() {
+    let mut interval = tokio::time::interval(config::ANALYTICS_FLUSH_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        flush().await;
+    }
+}
+
+#[cfg(feature = "developer-commands")]
+pub fn summarize(since: i64) -> Summary {
+    Summary::from_records(&ANALYTICS.read().records, since)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "developer-commands")]
+    const DAY: i64 = 24 * 60 * 60;
+
+    fn record(command: &str, user: &str, outcome: Outcome, latency_ms: u64, time: i64) -> CommandRecord {
+        CommandRecord {
+            command: command.to_owned(),
+            guild: None,
+            user: Some(user.to_owned()),
+            outcome,
+            latency_ms,
+            time,
+        }
+    }
+
+    #[test]
+    fn users_are_hashed_with_the_salt() {
+        assert_eq!(hash_user("salt", 1), hash_user("salt", 1));
+        assert_ne!(hash_user("salt", 1), hash_user("salt", 2));
+        assert_ne!(hash_user("salt", 1), hash_user("pepper", 1));
+        assert_eq!(hash_user("salt", 1).len(), 32);
+    }
+
+    #[cfg(feature = "developer-commands")]
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let latencies = (1..=20).collect::<Vec<u64>>();
+
+        assert_eq!(percentile(&latencies, 0.5), Duration::from_millis(10));
+        assert_eq!(percentile(&latencies, 0.95), Duration::from_millis(19));
+        assert_eq!(percentile(&[7], 0.95), Duration::from_millis(7));
+        assert_eq!(percentile(&[], 0.5), Duration::ZERO);
+    }
+
+    #[cfg(feature = "developer-commands")]
+    #[test]
+    fn records_are_summarized() {
+        let records = vec![
+            record("weather", "a", Outcome::Success, 500, 0),
+            record("ping", "a", Outcome::Success, 100, DAY),
+            record("ping", "b", Outcome::Failure, 300, DAY + 1),
+            record("ping", "b", Outcome::Denied, 0, DAY + 2),
+            record("help", "a", Outcome::Success, 10, 2 * DAY),
+        ];
+
+        let summary = Summary::from_records(&records, DAY);
+
+        assert_eq!(summary.total, 4);
+        assert_eq!(summary.failures, 1);
+
+        assert_eq!(summary.commands, vec![
+            CommandSummary {
+                name: String::from("ping"),
+                uses: 3,
+                failures: 1,
+                p50: Duration::from_millis(100),
+                p95: Duration::from_millis(300),
+            },
+            CommandSummary {
+                name: String::from("help"),
+                uses: 1,
+                failures: 0,
+                p50: Duration::from_millis(10),
+                p95: Duration::from_millis(10),
+            },
+        ]);
+
+        assert_eq!(summary.daily_active_users, vec![
+            (NaiveDate::from_ymd(1970, 1, 2), 2),
+            (NaiveDate::from_ymd(1970, 1, 3), 1),
+        ]);
+    }
+
+    #[test]
+    fn old_records_lose_their_users_before_being_dropped() {
+        let retention = config::ANALYTICS_RETENTION.as_secs() as i64;
+        let user_retention = config::ANALYTICS_USER_RETENTION.as_secs() as i64;
+
+        let mut analytics = Analytics {
+            salt: String::from("salt"),
+            records: vec![
+                record("ping", "a", Outcome::Success, 100, 0),
+                record("ping", "a", Outcome::Success, 100, retention - user_retention),
+                record("ping", "a", Outcome::Success, 100, retention),
+            ],
+        };
+
+        analytics.prune(retention + 1);
+
+        assert_eq!(analytics.records.len(), 2);
+        assert_eq!(analytics.records[0].user, None);
+        assert_eq!(analytics.records[1].user.as_deref(), Some("a"));
+    }
+}
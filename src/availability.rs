@@ -0,0 +1,255 @@
+//! A persistent history of starts, stops, connections and disconnections,
+//! which outlives restarts unlike `START_TIME` and is used to work out the bot's availability
+
+use crate::{config, storage::JsonStore};
+
+use chrono::Utc;
+
+use lazy_static::lazy_static;
+
+use serde::{Deserialize, Serialize};
+
+use tracing::error;
+
+lazy_static! {
+    pub static ref UPTIME_HISTORY: JsonStore<UptimeHistory> = JsonStore::open("uptime_history.json");
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Start,
+    Connect,
+    Disconnect,
+    Stop,
+    /// The previous run ended without a `Stop`, recorded at the time it was last seen running
+    Crash,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UptimeEvent {
+    pub kind: EventKind,
+    /// Unix time in seconds
+    pub time: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UptimeHistory {
+    pub events: Vec<UptimeEvent>,
+    /// The last time the bot was known to be running
+    pub last_seen: Option<i64>,
+}
+
+#[cfg(feature = "general-commands")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Outage {
+    pub start: i64,
+    /// `None` while the outage is still going on
+    pub end: Option<i64>,
+}
+
+#[cfg(feature = "general-commands")]
+impl Outage {
+    pub fn duration(&self, now: i64) -> i64 {
+        self.end.unwrap_or(now) - self.start
+    }
+}
+
+impl UptimeHistory {
+    fn is_connected(&self) -> bool {
+        matches!(self.events.last(), Some(event) if event.kind == EventKind::Connect)
+    }
+
+    fn push(&mut self, kind: EventKind, time: i64) {
+        // Several shards or both `ready` and a stage update may report the same change
+        let redundant = match kind {
+            EventKind::Connect => self.is_connected(),
+            EventKind::Disconnect => !self.is_connected(),
+            _ => false,
+        };
+
+        if !redundant {
+            self.events.push(UptimeEvent { kind, time });
+        }
+
+        self.last_seen = Some(time);
+        self.prune(time);
+    }
+
+    /// Drops expired events, keeping the latest of them since it tells the state at the start of the retention period
+    fn prune(&mut self, now: i64) {
+        let cutoff = now - config::UPTIME_HISTORY_RETENTION.as_secs() as i64;
+        let expired = self.events.iter().take_while(|event| event.time < cutoff).count();
+
+        self.events.drain(..expired.saturating_sub(1));
+    }
+
+    /// The time since which the history has been recorded
+    #[cfg(feature = "general-commands")]
+    pub fn tracked_since(&self) -> Option<i64> {
+        self.events.first().map(|event| event.time)
+    }
+
+    /// Every period from losing the connection to Discord to getting it back, oldest first.
+    /// The time before the very first connection is not an outage.
+    #[cfg(feature = "general-commands")]
+    pub fn outages(&self) -> Vec<Outage> {
+        let mut outages = Vec::new();
+        let mut connected = false;
+        let mut down_since = None;
+
+        for event in &self.events {
+            match event.kind {
+                EventKind::Connect => {
+                    if let Some(start) = down_since.take() {
+                        outages.push(Outage { start, end: Some(event.time) });
+                    }
+
+                    connected = true;
+                }
+                EventKind::Disconnect | EventKind::Stop | EventKind::Crash => {
+                    if connected {
+                        down_since = Some(event.time);
+                    }
+
+                    connected = false;
+                }
+                EventKind::Start => connected = false,
+            }
+        }
+
+        if let Some(start) = down_since {
+            outages.push(Outage { start, end: None });
+        }
+
+        outages
+    }
+
+    /// The percentage of time the bot was connected over the `period` (in seconds) up to `now`,
+    /// or `None` if nothing was recorded during it
+    #[cfg(feature = "general-commands")]
+    pub fn availability(&self, period: i64, now: i64) -> Option<f64> {
+        let since = self.tracked_since()?.max(now - period);
+        let total = now - since;
+
+        if total <= 0 {
+            return None;
+        }
+
+        let downtime: i64 = self.outages()
+            .iter()
+            .map(|outage| (outage.end.unwrap_or(now).min(now) - outage.start.max(since)).max(0))
+            .sum();
+
+        Some(100.0 * (total - downtime) as f64 / total as f64)
+    }
+}
+
+pub async fn record(kind: EventKind) {
+    let result = UPTIME_HISTORY.update(|history| history.push(kind, Utc::now().timestamp())).await;
+
+    if let Err(e) = result {
+        error!(error = %e, ?kind, "Failed to record an uptime event");
+    }
+}
+
+/// Records the start of a new run, noting a crash first if the previous run was not stopped cleanly
+pub async fn record_start() {
+    let result = UPTIME_HISTORY.update(|history| {
+        let stopped = matches!(history.events.last(), Some(event) if event.kind == EventKind::Stop);
+
+        if let (false, Some(last_seen)) = (stopped, history.last_seen) {
+            history.push(EventKind::Crash, last_seen);
+        }
+
+        history.push(EventKind::Start, Utc::now().timestamp());
+    }).await;
+
+    if let Err(e) = result {
+        error!(error = %e, "Failed to record the start");
+    }
+}
+
+/// Periodically notes that the bot is still running so that crashes can be dated
+pub async fn keep_alive() {
+    let mut interval = tokio::time::interval(config::UPTIME_KEEP_ALIVE_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let result = UPTIME_HISTORY.update(|history| history.last_seen = Some(Utc::now().timestamp())).await;
+
+        if let Err(e) = result {
+            error!(error = %e, "Failed to update the uptime history");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 60 * 60;
+
+    fn history(events: &[(EventKind, i64)]) -> UptimeHistory {
+        let mut history = UptimeHistory::default();
+
+        for &(kind, time) in events {
+            history.push(kind, time);
+        }
+
+        history
+    }
+
+    #[cfg(feature = "general-commands")]
+    #[test]
+    fn outages_span_from_losing_to_regaining_the_connection() {
+        use EventKind::*;
+
+        let history = history(&[
+            (Start, 0),
+            (Connect, 10),
+            (Disconnect, HOUR),
+            (Disconnect, HOUR + 5),
+            (Connect, HOUR + 60),
+            (Crash, 2 * HOUR),
+            (Start, 3 * HOUR),
+            (Connect, 3 * HOUR + 10),
+            (Stop, 4 * HOUR),
+        ]);
+
+        assert_eq!(history.outages(), vec![
+            Outage { start: HOUR, end: Some(HOUR + 60) },
+            Outage { start: 2 * HOUR, end: Some(3 * HOUR + 10) },
+            Outage { start: 4 * HOUR, end: None },
+        ]);
+    }
+
+    #[cfg(feature = "general-commands")]
+    #[test]
+    fn availability_only_counts_the_tracked_part_of_the_period() {
+        use EventKind::*;
+
+        let history = history(&[(Start, 0), (Connect, 0), (Disconnect, 9 * HOUR), (Connect, 10 * HOUR)]);
+
+        assert_eq!(history.availability(24 * HOUR, 20 * HOUR), Some(95.0));
+        assert_eq!(history.availability(HOUR, 20 * HOUR), Some(100.0));
+        assert_eq!(history.availability(12 * HOUR, 20 * HOUR), Some(100.0 * 11.0 / 12.0));
+
+        assert_eq!(UptimeHistory::default().availability(HOUR, 0), None);
+    }
+
+    #[test]
+    fn expired_events_are_pruned() {
+        use EventKind::*;
+
+        let retention = config::UPTIME_HISTORY_RETENTION.as_secs() as i64;
+        let history = history(&[(Connect, 0), (Disconnect, HOUR), (Connect, 2 * HOUR), (Disconnect, retention + 3 * HOUR)]);
+
+        assert_eq!(history.events, vec![
+            UptimeEvent { kind: Connect, time: 2 * HOUR },
+            UptimeEvent { kind: Disconnect, time: retention + 3 * HOUR },
+        ]);
+    }
+}
@@ -0,0 +1,118 @@
+use image::{ImageOutputFormat, RgbImage};
+
+use plotters::prelude::*;
+
+use std::{
+    error::Error,
+    io::Cursor,
+    sync::Once,
+};
+
+const CHART_WIDTH: u32 = 800;
+const CHART_HEIGHT: u32 = 400;
+
+static REGISTER_FONT: Once = Once::new();
+
+pub struct ForecastPoint {
+    pub label: String,
+    pub temp: f64,
+    pub precipitation: f64,
+}
+
+/// Renders a temperature line over precipitation bars into a PNG image
+pub fn render_forecast_chart(points: &[ForecastPoint]) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+    // The font is bundled so that no system font libraries are needed at runtime
+    REGISTER_FONT.call_once(|| {
+        let font = include_bytes!("../assets/DejaVuSans.ttf");
+
+        // `InvalidFont` does not implement `Debug`
+        plotters::style::register_font("sans-serif", FontStyle::Normal, font)
+            .map_err(|_| "InvalidFont")
+            .expect("the bundled font is valid");
+    });
+
+    if points.is_empty() {
+        return Err("There is no forecast data to render!".into());
+    }
+
+    let mut buffer = vec![0; (CHART_WIDTH * CHART_HEIGHT * 3) as usize];
+
+    {
+        let root = BitMapBackend::with_buffer(&mut buffer, (CHART_WIDTH, CHART_HEIGHT)).into_drawing_area();
+
+        root.fill(&WHITE).map_err(|e| e.to_string())?;
+
+        let (min_temp, max_temp) = points.iter()
+            .fold((f64::MAX, f64::MIN), |(min, max), p| (min.min(p.temp), max.max(p.temp)));
+        let max_precipitation = points.iter().map(|p| p.precipitation).fold(1.0, f64::max);
+        let x_range = 0.0..points.len() as f64;
+
+        let mut chart = ChartBuilder::on(&root)
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(40)
+            .right_y_label_area_size(40)
+            .build_cartesian_2d(x_range.clone(), (min_temp - 2.0).floor()..(max_temp + 2.0).ceil())
+            .map_err(|e| e.to_string())?
+            .set_secondary_coord(x_range, 0.0..max_precipitation * 1.2);
+
+        chart.configure_mesh()
+            .disable_x_mesh()
+            .x_labels(points.len().min(10))
+            .x_label_formatter(&|x| points.get(*x as usize).map(|p| p.label.clone()).unwrap_or_default())
+            .y_desc("\u{b0}C")
+            .draw()
+            .map_err(|e| e.to_string())?;
+
+        chart.configure_secondary_axes()
+            .y_desc("mm")
+            .draw()
+            .map_err(|e| e.to_string())?;
+
+        chart.draw_secondary_series(points.iter().enumerate().map(|(i, p)| {
+            let x = i as f64;
+
+            Rectangle::new([(x + 0.15, 0.0), (x + 0.85, p.precipitation)], BLUE.mix(0.4).filled())
+        })).map_err(|e| e.to_string())?;
+
+        chart.draw_series(LineSeries::new(
+            points.iter().enumerate().map(|(i, p)| (i as f64 + 0.5, p.temp)),
+            RED.stroke_width(2),
+        )).map_err(|e| e.to_string())?;
+
+        root.present().map_err(|e| e.to_string())?;
+    }
+
+    let image = RgbImage::from_raw(CHART_WIDTH, CHART_HEIGHT, buffer)
+        .ok_or("The chart buffer has an invalid size!")?;
+    let mut png = Cursor::new(Vec::new());
+
+    image.write_to(&mut png, ImageOutputFormat::Png)?;
+
+    Ok(png.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_png() {
+        let points = (0..40)
+            .map(|i| ForecastPoint {
+                label: format!("{}h", i * 3 % 24),
+                temp: (i as f64 / 3.0).sin() * 8.0,
+                precipitation: if i % 5 == 0 { 2.5 } else { 0.0 },
+            })
+            .collect::<Vec<ForecastPoint>>();
+
+        let png = render_forecast_chart(&points).unwrap();
+
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    }
+
+    #[test]
+    fn rejects_empty_forecast() {
+        assert!(render_forecast_chart(&[]).is_err());
+    }
+}
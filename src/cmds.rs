@@ -1,30 +1,63 @@
+use crate::config;
+
 use lazy_static::lazy_static;
 
+use serde_json::Value;
+
 use serenity::{
+    builder::{CreateApplicationCommandOption, CreateApplicationCommands},
     client::Context,
-    model::channel::Message,
+    model::{
+        channel::Message,
+        interactions::application_command::{ApplicationCommandInteractionDataOption, ApplicationCommandOptionType},
+        permissions::Permissions,
+    },
     async_trait,
 };
 
-use std::error::Error;
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    iter,
+};
+
+#[cfg(feature = "general-commands")]
+use crate::i18n::{translate, Language};
 
 lazy_static! {
-    pub static ref COMMANDS: Vec<Box<dyn Command>> = vec![
-        Box::new(dev::ShutdownCommand),
-        Box::new(general::HelpCommand),
-        Box::new(general::PingCommand),
-        Box::new(general::UptimeCommand),
-        Box::new(utility::WeatherCommand),
-        //Box::new(dev::TestCommand),
-    ];
+    /// Every command compiled into the bot, checked for clashing names when first used
+    pub static ref COMMANDS: CommandRegistry = {
+        #[cfg_attr(not(any(feature = "developer-commands", feature = "general-commands", feature = "utility-commands")), allow(unused_mut))]
+        let mut commands = CommandRegistry::builder();
+
+        #[cfg(feature = "developer-commands")]
+        dev::register(&mut commands);
+        #[cfg(feature = "general-commands")]
+        general::register(&mut commands);
+        #[cfg(feature = "utility-commands")]
+        utility::register(&mut commands);
+
+        commands.build().unwrap_or_else(|e| panic!("Failed to register the commands: {}", e))
+    };
 }
 
 pub type CommandUsages<'a> = Vec<Vec<&'a str>>;
 
+/// An argument as it is described to Discord for the command's slash counterpart
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandOption {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub required: bool,
+    /// Whether the argument is a single word that is either given or not, i.e. a boolean option
+    pub flag: bool,
+}
+
 #[async_trait]
 pub trait Command: Send + Sync {
-    fn name(&self) -> &'static str;
-    fn description(&self) -> &'static str;
+    fn name(&self) -> &str;
+    fn description(&self) -> &str;
     fn category(&self) -> CommandCategory;
 
     fn aliases(&self) -> Vec<&str> {
@@ -39,6 +72,26 @@ pub trait Command: Send + Sync {
         self.category() == CommandCategory::Developer
     }
 
+    /// The guild permissions a member needs, on top of those of the parent commands
+    fn permissions(&self) -> Permissions {
+        Permissions::empty()
+    }
+
+    /// The arguments of the command's slash counterpart, `#[command]` declares them from the typed arguments
+    fn options(&self) -> Vec<CommandOption> {
+        Vec::new()
+    }
+
+    /// The commands chosen by the first argument, which makes this command a group that is never invoked itself
+    fn subcommands(&self) -> &[Box<dyn Command>] {
+        &[]
+    }
+
+    /// The subcommand run when the first argument names none of them
+    fn default_subcommand(&self) -> Option<&'static str> {
+        None
+    }
+
     async fn invoke(
         &self,
         cx: &Context,
@@ -47,10 +100,14 @@ pub trait Command: Send + Sync {
     ) -> Result<(), Box<dyn Error + Send + Sync>>;
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum CommandCategory {
+    /// A guild's own commands, i.e. its scripts and tags
+    Custom,
     Developer,
+    #[cfg_attr(not(feature = "general-commands"), allow(dead_code))]
     General,
+    #[cfg_attr(not(feature = "utility-commands"), allow(dead_code))]
     Utility,
 }
 
@@ -60,564 +117,2847 @@ impl ToString for CommandCategory {
     }
 }
 
-mod dev {
-    use crate::{
-        cmds::{Command, CommandCategory},
-        utils::discord::{DefaultEmbedReplies, EmbedType},
-    };
+impl CommandCategory {
+    #[cfg(test)]
+    pub const ALL: [CommandCategory; 4] = [
+        CommandCategory::Custom,
+        CommandCategory::Developer,
+        CommandCategory::General,
+        CommandCategory::Utility,
+    ];
 
-    use serenity::{
-        client::Context,
-        model::channel::Message,
-        async_trait,
-    };
+    #[cfg(feature = "general-commands")]
+    pub fn translate(&self, language: Language) -> String {
+        translate(language, &format!("category-{}", self.to_string().to_lowercase()), None)
+    }
+}
 
-    use std::{
-        error::Error,
-        process::exit,
-    };
+/// A command that only groups its subcommands, e.g. `<weather forecast` and `<weather alerts`
+#[cfg(any(test, feature = "general-commands", feature = "utility-commands"))]
+pub struct CommandGroup {
+    name: &'static str,
+    description: &'static str,
+    category: CommandCategory,
+    aliases: Vec<&'static str>,
+    subcommands: Vec<Box<dyn Command>>,
+    default_subcommand: Option<&'static str>,
+}
 
-    use tokio::time::Duration;
+#[cfg(any(test, feature = "general-commands", feature = "utility-commands"))]
+impl CommandGroup {
+    pub fn new(name: &'static str, description: &'static str, category: CommandCategory) -> Self {
+        Self {
+            name,
+            description,
+            category,
+            aliases: Vec::new(),
+            subcommands: Vec::new(),
+            default_subcommand: None,
+        }
+    }
 
-    pub struct ShutdownCommand;
-    //pub struct TestCommand;
+    #[cfg_attr(not(feature = "utility-commands"), allow(dead_code))]
+    pub fn aliases(mut self, aliases: &[&'static str]) -> Self {
+        self.aliases.extend(aliases);
+        self
+    }
 
-    #[async_trait]
-    impl Command for ShutdownCommand {
-        fn name(&self) -> &'static str {
-            "shutdown"
-        }
+    pub fn subcommand(mut self, command: impl Command + 'static) -> Self {
+        self.subcommands.push(Box::new(command));
+        self
+    }
 
-        fn description(&self) -> &'static str {
-            "Shuts the bot down"
-        }
+    pub fn default_subcommand(mut self, name: &'static str) -> Self {
+        self.default_subcommand = Some(name);
+        self
+    }
+}
 
-        fn category(&self) -> CommandCategory {
-            CommandCategory::Developer
-        }
+#[cfg(any(test, feature = "general-commands", feature = "utility-commands"))]
+#[async_trait]
+impl Command for CommandGroup {
+    fn name(&self) -> &'static str {
+        self.name
+    }
 
-        async fn invoke(
-            &self,
-            cx: &Context,
-            message: &Message,
-            _args: &[&str],
-        ) -> Result<(), Box<dyn Error + Send + Sync>> {
-            const CHECK_MARK: char = '\u{2705}';
-            const CROSS_MARK: char = '\u{274E}';
+    fn description(&self) -> &'static str {
+        self.description
+    }
 
-            let msg = message.channel_id.send_default_reply(
-                &cx.http,
-                "Are you sure?",
-                EmbedType::Confirmation,
-            ).await.unwrap_or_else(|_| exit(0));
-
-            msg.react(&cx.http, CHECK_MARK).await?;
-            msg.react(&cx.http, CROSS_MARK).await?;
-
-            let check_reaction = message.channel_id
-                .await_reaction(&cx.shard)
-                .message_id(msg.id)
-                .author_id(message.author.id)
-                .filter(|r| {
-                    r.emoji.unicode_eq(&CHECK_MARK.to_string())
-                        || r.emoji.unicode_eq(&CROSS_MARK.to_string())
-                })
-                .timeout(Duration::from_secs(60))
-                .await;
-
-            match check_reaction {
-                Some(reaction) => {
-                    msg.delete(&cx.http).await?;
-
-                    if reaction.as_inner_ref().emoji.unicode_eq(&CHECK_MARK.to_string()) {
-                        exit(0)
-                    }
-                }
-                None => {
-                    msg.delete(&cx.http).await?;
-                }
+    fn category(&self) -> CommandCategory {
+        self.category
+    }
+
+    fn aliases(&self) -> Vec<&str> {
+        self.aliases.clone()
+    }
+
+    fn subcommands(&self) -> &[Box<dyn Command>] {
+        &self.subcommands
+    }
+
+    fn default_subcommand(&self) -> Option<&'static str> {
+        self.default_subcommand
+    }
+
+    /// Never called, the dispatcher either resolves a subcommand or reports that none has been given
+    async fn invoke(
+        &self,
+        _cx: &Context,
+        _message: &Message,
+        _args: &[&str],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+}
+
+/// Looks a command up by its name or one of its aliases, `name` has to be lowercase
+pub fn find<'a>(commands: &'a [Box<dyn Command>], name: &str) -> Option<&'a dyn Command> {
+    commands.iter()
+        .map(|c| c.as_ref())
+        .find(|c| c.name() == name || c.aliases().contains(&name))
+}
+
+/// Walks down from `command` into the subcommands named by the leading arguments, also following the default
+/// subcommands if `follow_defaults` is set. Returns the path from `command` to the subcommand and the arguments left.
+pub fn resolve<'a, 'b>(
+    command: &'a dyn Command,
+    args: &'b [&'b str],
+    follow_defaults: bool,
+) -> (Vec<&'a dyn Command>, &'b [&'b str]) {
+    let mut path = vec![command];
+    let mut args = args;
+
+    loop {
+        let current = path[path.len() - 1];
+        let named = args.first().and_then(|arg| find(current.subcommands(), &arg.to_lowercase()));
+
+        let next = match named {
+            Some(subcommand) => {
+                args = &args[1..];
+
+                subcommand
             }
+            None => match current.default_subcommand().filter(|_| follow_defaults) {
+                Some(name) => match find(current.subcommands(), name) {
+                    Some(subcommand) => subcommand,
+                    None => break,
+                },
+                None => break,
+            },
+        };
+
+        path.push(next);
+    }
 
-            Ok(())
+    (path, args)
+}
+
+/// The name a subcommand is typed as, e.g. `weather alerts list`
+pub fn qualified_name(path: &[&dyn Command]) -> String {
+    path.iter().map(|c| c.name()).collect::<Vec<_>>().join(" ")
+}
+
+/// The catalog message describing the command at the end of `path`, e.g. `help-description-weather-alerts-list`
+#[cfg(any(test, feature = "general-commands"))]
+pub fn description_id(path: &[&dyn Command]) -> String {
+    format!("help-description-{}", path.iter().map(|c| c.name()).collect::<Vec<_>>().join("-"))
+}
+
+/// Every way to run the command at the end of `path`, one line per usage of each of its subcommands
+pub fn usage_lines(path: &[&dyn Command]) -> Vec<String> {
+    let command = path[path.len() - 1];
+
+    if command.subcommands().is_empty() {
+        let name = qualified_name(path);
+        let usages = command.usages();
+
+        if usages.is_empty() {
+            return vec![format!("{}{}", config::PREFIX, name)];
         }
+
+        return usages.iter()
+            .map(|usage| {
+                let args = usage.iter()
+                    .map(|a| format!("<{}>", a))
+                    .collect::<Vec<String>>()
+                    .join(" ");
+
+                format!("{}{} {}", config::PREFIX, name, args)
+            })
+            .collect();
     }
 
-    /*#[async_trait]
-    impl Command for TestCommand {
-        fn name(&self) -> &'static str {
-            "test"
-        }
+    command.subcommands().iter()
+        .flat_map(|subcommand| {
+            let mut path = path.to_vec();
 
-        fn description(&self) -> &'static str {
-            "N/A"
+            path.push(subcommand.as_ref());
+
+            usage_lines(&path)
+        })
+        .collect()
+}
+
+/// Turns a slash command back into the words of the command typed with the prefix: the names of the command and
+/// its subcommands, then the values of the arguments in the order the command takes them.
+/// A flag that is set is written as its name, just like it would be typed.
+pub fn slash_words(command: &dyn Command, options: &[ApplicationCommandInteractionDataOption]) -> Vec<String> {
+    use ApplicationCommandOptionType::{SubCommand, SubCommandGroup};
+
+    let mut words = vec![command.name().to_owned()];
+    let mut command = command;
+    let mut options = options;
+
+    // A subcommand arrives as the only option, holding the options of its own
+    while let Some(option) = options.iter().find(|o| matches!(o.kind, SubCommand | SubCommandGroup)) {
+        match find(command.subcommands(), &option.name) {
+            Some(subcommand) => {
+                words.push(subcommand.name().to_owned());
+                command = subcommand;
+                options = &option.options;
+            }
+            None => break,
         }
+    }
 
-        fn category(&self) -> CommandCategory {
-            CommandCategory::Developer
+    for argument in command.options() {
+        match options.iter().find(|o| o.name == argument.name).and_then(|o| o.value.as_ref()) {
+            Some(Value::Bool(true)) if argument.flag => words.push(argument.name.to_owned()),
+            Some(Value::String(value)) => words.push(value.clone()),
+            _ => {}
         }
+    }
 
-        async fn invoke(
-            &self,
-            cx: &Context,
-            message: &Message,
-            _args: &[&str]
-        ) -> Result<(), Box<dyn Error + Send + Sync>> {
-            /*use serenity::collector::ComponentInteractionCollectorBuilder;
-            use serenity::futures::StreamExt;
-            use serenity::model::interactions::message_component::ButtonStyle;
-            use serenity::model::prelude::InteractionApplicationCommandCallbackDataFlags;
-
-            let msg = message.channel_id.send_message(&cx.http, |m| {
-                m.content("test").components(|c| {
-                    c.create_action_row(|r| {
-                        r.create_button(|button| {
-                            button
-                                .style(ButtonStyle::Secondary)
-                                .emoji('\u{1F98D}'.into())
-                                .label("Test")
-                                .custom_id(format!("{}-test", self.name()))
-                        })
-                    })
-                })
-            }).await?;
-
-            /*let events: Vec<_> = ComponentInteractionCollectorBuilder::new(&cx)
-                .message_id(msg.id)
-                .author_id(message.author.id)
-                .collect_limit(1)
-                .await
-                .collect()
-                .await;
-
-            if let Some(e) = events.first() {
-                e.create_interaction_response(&cx.http, |m| {
-                    m.interaction_response_data(|d| {
-                        d
-                            .content(&e.data.custom_id)
-                            .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
-                    })
-                }).await?;
-            }*/
-
-            let mut events = ComponentInteractionCollectorBuilder::new(&cx)
-                .message_id(msg.id)
-                .author_id(message.author.id)
-                //.collect_limit(1)
-                .await;
-
-            while let Some(e) = events.next().await {
-                e.create_interaction_response(&cx.http, |m| {
-                    m.interaction_response_data(|d| {
-                        d
-                            .content(&e.data.custom_id)
-                            .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
-                    })
-                }).await?;
-            }*/
+    words
+}
 
-            message.channel_id.send_default_reply(&cx.http, "test", EmbedType::Success).await?;
-            message.channel_id.send_default_reply(&cx.http, "test", EmbedType::Failure).await?;
-            message.channel_id.send_default_reply(&cx.http, "test", EmbedType::Confirmation).await?;
-            message.channel_id.send_default_reply(&cx.http, "test", EmbedType::Warning).await?;
+/// The commands the dispatcher and the help know about, each reachable by exactly one name or alias
+pub struct CommandRegistry {
+    commands: Vec<Box<dyn Command>>,
+}
 
-            Ok(())
+#[derive(Default)]
+pub struct CommandRegistryBuilder {
+    commands: Vec<Box<dyn Command>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RegistrationError {
+    /// A name or alias is empty or contains whitespace, so it could never be typed as a single argument
+    InvalidName(String),
+    /// A name or alias is used by two commands of the same group, or twice by the same one
+    Collision {
+        name: String,
+        first: String,
+        second: String,
+    },
+    /// A group's default subcommand is not one of its subcommands
+    UnknownDefault {
+        group: String,
+        default: &'static str,
+    },
+    /// Slash commands can only be nested two levels deep
+    TooDeep(String),
+}
+
+impl Display for RegistrationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistrationError::InvalidName(name) => write!(f, "\"{}\" is not a valid command name!", name),
+            RegistrationError::Collision { name, first, second } => {
+                write!(f, "\"{}\" is claimed by both {} and {}!", name, first, second)
+            }
+            RegistrationError::UnknownDefault { group, default } => {
+                write!(f, "{} has no subcommand {} to default to!", group, default)
+            }
+            RegistrationError::TooDeep(name) => write!(f, "{} is nested too deeply!", name),
         }
-    }*/
+    }
 }
 
-mod general {
-    use chrono::{DateTime, Utc};
+impl Error for RegistrationError {}
 
-    use crate::{
-        cmds::{Command, CommandCategory, CommandUsages, COMMANDS},
-        config,
-        utils::{
-            discord::{DefaultEmbedReplies, EmbedType},
-            time::as_text,
-        },
-        START_TIME,
-    };
+impl CommandRegistry {
+    /// A group of groups of subcommands is as deep as slash commands go
+    const MAX_DEPTH: usize = 2;
 
-    use itertools::Itertools;
+    pub fn builder() -> CommandRegistryBuilder {
+        CommandRegistryBuilder::default()
+    }
 
-    use serenity::{
-        client::Context,
-        model::channel::Message,
-        async_trait,
-    };
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Command> {
+        self.commands.iter().map(|c| c.as_ref())
+    }
 
-    use std::{
-        error::Error,
-        time::SystemTime,
-    };
+    /// Looks a top-level command up by its name or one of its aliases, `name` has to be lowercase
+    pub fn find(&self, name: &str) -> Option<&dyn Command> {
+        find(&self.commands, name)
+    }
 
-    pub struct HelpCommand;
-    pub struct PingCommand;
-    pub struct UptimeCommand;
+    /// Every command and subcommand along with the path leading to it
+    #[cfg(test)]
+    pub fn paths(&self) -> Vec<Vec<&dyn Command>> {
+        fn walk<'a>(path: Vec<&'a dyn Command>, paths: &mut Vec<Vec<&'a dyn Command>>) {
+            let command = path[path.len() - 1];
 
-    #[async_trait]
-    impl Command for HelpCommand {
-        fn name(&self) -> &'static str {
-            "help"
+            paths.push(path.clone());
+
+            for subcommand in command.subcommands() {
+                let mut path = path.clone();
+
+                path.push(subcommand.as_ref());
+                walk(path, paths);
+            }
         }
 
-        fn description(&self) -> &'static str {
-            "Sends a list of the bot's commands or provides help for the specified command"
+        let mut paths = Vec::new();
+
+        for command in self.iter() {
+            walk(vec![command], &mut paths);
         }
 
-        fn category(&self) -> CommandCategory {
-            CommandCategory::General
+        paths
+    }
+
+    /// Describes every command as a slash command, see `slash_words` for how they are run
+    pub fn slash_commands<'a>(&self, commands: &'a mut CreateApplicationCommands) -> &'a mut CreateApplicationCommands {
+        fn add_options(command: &dyn Command, option: &mut CreateApplicationCommandOption) {
+            for subcommand in command.subcommands() {
+                option.create_sub_option(|o| subcommand_option(subcommand.as_ref(), o));
+            }
+
+            for argument in command.options() {
+                option.create_sub_option(|o| argument_option(&argument, o));
+            }
         }
 
-        fn usages(&self) -> CommandUsages {
-            vec![vec!["command name (optional)"]]
+        fn argument_option<'a>(
+            argument: &CommandOption,
+            option: &'a mut CreateApplicationCommandOption,
+        ) -> &'a mut CreateApplicationCommandOption {
+            option
+                .name(argument.name)
+                .description(argument.description)
+                .kind(if argument.flag { ApplicationCommandOptionType::Boolean } else { ApplicationCommandOptionType::String })
+                .required(argument.required)
         }
 
-        async fn invoke(
-            &self,
-            cx: &Context,
-            message: &Message,
-            args: &[&str],
-        ) -> Result<(), Box<dyn Error + Send + Sync>> {
-            match args.first().map(|a| a.to_lowercase()) {
-                Some(cmd_name) => {
-                    let commands: Vec<&Box<dyn Command>> = (*COMMANDS)
-                        .iter()
-                        .filter(|c| c.name() == cmd_name || c.aliases().contains(&cmd_name.as_str()))
-                        .collect();
-
-                    if let Some(cmd) = commands.first() {
-                        let mut title = format!("{}{}", config::PREFIX, cmd.name());
-
-                        if cmd.is_developer() {
-                            title.push_str(" (developer-only)");
-                        }
-
-                        let bot_pfp = cx.http.get_current_user().await?.face();
-
-                        message.channel_id.send_message(&cx.http, |m| {
-                            m.embed(|embed| {
-                                let mut fields = vec![("Category", cmd.category().to_string(), false)];
-
-                                if !cmd.aliases().is_empty() {
-                                    let mut aliases = cmd.aliases();
-
-                                    aliases.sort();
-
-                                    fields.push(("Aliases", aliases.join(", "), false));
-                                }
-
-                                if !cmd.usages().is_empty() {
-                                    let usages = cmd.usages().iter().map(|u| {
-                                        u.iter()
-                                            .map(|a| format!("<{}>", a))
-                                            .collect::<Vec<String>>()
-                                            .join(" ")
-                                    }).collect::<Vec<String>>();
-
-                                    let usages = usages.iter()
-                                        .map(|u| format!("{}{} {}", config::PREFIX, cmd.name(), u))
-                                        .collect::<Vec<String>>()
-                                        .join("\n");
-
-                                    fields.push(("Usages", usages, false));
-                                }
-
-                                embed
-                                    .color(config::SUCCESS_COLOR)
-                                    .author(|a| a.name(title).icon_url(bot_pfp))
-                                    .description(cmd.description())
-                                    .fields(fields)
-                            })
-                        }).await?;
-                    } else {
-                        message.channel_id.send_default_reply(
-                            &cx.http,
-                            "No command has been found by the query!",
-                            EmbedType::Failure,
-                        ).await?;
-                    }
+        fn subcommand_option<'a>(
+            command: &dyn Command,
+            option: &'a mut CreateApplicationCommandOption,
+        ) -> &'a mut CreateApplicationCommandOption {
+            let kind = if command.subcommands().is_empty() {
+                ApplicationCommandOptionType::SubCommand
+            } else {
+                ApplicationCommandOptionType::SubCommandGroup
+            };
+
+            option.name(command.name()).description(command.description()).kind(kind);
+            add_options(command, option);
+
+            option
+        }
+
+        for command in self.iter() {
+            commands.create_application_command(|c| {
+                c.name(command.name()).description(command.description());
+
+                for subcommand in command.subcommands() {
+                    c.create_option(|o| subcommand_option(subcommand.as_ref(), o));
                 }
-                None => {
-                    let bot = cx.http.get_current_user().await?;
 
-                    let mut cmds_grouped: Vec<(CommandCategory, Vec<&Box<dyn Command>>)> = Vec::new();
+                for argument in command.options() {
+                    c.create_option(|o| argument_option(&argument, o));
+                }
 
-                    for (key, group) in &COMMANDS.iter().group_by(|c| c.category()) {
-                        cmds_grouped.push((key, group.collect()));
-                    }
+                c
+            });
+        }
 
-                    cmds_grouped.sort_by_key(|(c, _)| c.to_string());
+        commands
+    }
+}
 
-                    message.channel_id.send_message(&cx.http, |m| {
-                        m.embed(|embed| {
-                            let fields = cmds_grouped.into_iter().map(|(c, cmds)| {
-                                let mut cmds = cmds
-                                    .into_iter()
-                                    .map(|cmd| cmd.name())
-                                    .collect::<Vec<&str>>();
-
-                                cmds.sort();
-
-                                (format!("{} Commands", c.to_string()), cmds.join(", "), false)
-                            });
-
-                            embed
-                                .author(|a| a.name(format!("{} Help", bot.name)).icon_url(bot.face()))
-                                .color(config::SUCCESS_COLOR)
-                                .fields(fields)
-                        })
-                    }).await?;
+impl CommandRegistryBuilder {
+    #[cfg_attr(not(any(feature = "developer-commands", feature = "general-commands", feature = "utility-commands")), allow(dead_code))]
+    pub fn register(&mut self, command: impl Command + 'static) -> &mut Self {
+        self.commands.push(Box::new(command));
+        self
+    }
+
+    /// Checks the commands of one group, `parent` being the qualified name of the group
+    fn check(commands: &[Box<dyn Command>], parent: &str, depth: usize) -> Result<(), RegistrationError> {
+        let qualify = |name: &str| if parent.is_empty() { name.to_owned() } else { format!("{} {}", parent, name) };
+        let mut owners = HashMap::<String, String>::new();
+
+        for command in commands {
+            let qualified = qualify(command.name());
+
+            for name in iter::once(command.name()).chain(command.aliases()) {
+                // The dispatcher lowercases what it is given, so `Ping` could never be run
+                if name.is_empty() || name.contains(char::is_whitespace) || name != name.to_lowercase() {
+                    return Err(RegistrationError::InvalidName(name.to_owned()));
+                }
+
+                if let Some(first) = owners.insert(name.to_owned(), qualified.clone()) {
+                    return Err(RegistrationError::Collision { name: qualify(name), first, second: qualified });
                 }
             }
 
-            Ok(())
+            if command.subcommands().is_empty() {
+                continue;
+            }
+
+            if depth >= CommandRegistry::MAX_DEPTH {
+                return Err(RegistrationError::TooDeep(qualified));
+            }
+
+            if let Some(default) = command.default_subcommand() {
+                if find(command.subcommands(), default).is_none() {
+                    return Err(RegistrationError::UnknownDefault { group: qualified, default });
+                }
+            }
+
+            Self::check(command.subcommands(), &qualified, depth + 1)?;
         }
+
+        Ok(())
     }
 
-    #[async_trait]
-    impl Command for PingCommand {
-        fn name(&self) -> &'static str {
-            "ping"
-        }
+    pub fn build(self) -> Result<CommandRegistry, RegistrationError> {
+        Self::check(&self.commands, "", 0)?;
 
-        fn description(&self) -> &'static str {
-            "Sends the bot's current response latency"
+        Ok(CommandRegistry { commands: self.commands })
+    }
+}
+
+#[cfg(any(feature = "developer-commands", feature = "general-commands", feature = "utility-commands"))]
+pub mod args {
+    use serenity::model::channel::Message;
+
+    use std::error::Error;
+
+    #[cfg(feature = "developer-commands")]
+    use crate::{
+        i18n::{get_language, tr},
+        utils::time::{get_user_timezone, parse_duration_from, DurationParseError},
+    };
+
+    #[cfg(feature = "developer-commands")]
+    use chrono::{Duration, Utc};
+
+    #[cfg(feature = "developer-commands")]
+    use chrono_tz::Tz;
+
+    /// A typed command argument parsed from the raw text the user has provided
+    pub trait Argument: Sized {
+        /// The name shown in usages, e.g. `<duration>`
+        fn kind() -> &'static str;
+
+        /// A flag is only taken when the user has written the argument's name, e.g. `ping edit`
+        fn is_flag() -> bool {
+            false
         }
 
-        fn category(&self) -> CommandCategory {
-            CommandCategory::General
+        fn parse(input: &str, message: &Message) -> Result<Self, Box<dyn Error + Send + Sync>>;
+    }
+
+    /// A span of time such as `1d2h30m` or `2 weeks 3 days`, or the time until a moment such as
+    /// `tomorrow at 5pm`, which is resolved in the author's saved time zone
+    #[cfg(feature = "developer-commands")]
+    pub struct DurationArgument(pub Duration);
+
+    #[cfg(feature = "developer-commands")]
+    impl Argument for DurationArgument {
+        fn kind() -> &'static str {
+            "duration"
         }
 
-        fn aliases(&self) -> Vec<&str> {
-            vec!["latency"]
+        fn parse(input: &str, message: &Message) -> Result<Self, Box<dyn Error + Send + Sync>> {
+            let language = get_language(message);
+            let tz = get_user_timezone(*message.author.id.as_u64()).unwrap_or(Tz::UTC);
+
+            let duration = parse_duration_from(input, Utc::now().with_timezone(&tz)).map_err(|e| match e {
+                DurationParseError::Empty => tr!(language, "duration-empty"),
+                DurationParseError::InvalidNumber(value) => tr!(language, "duration-invalid-number", value = value),
+                DurationParseError::MissingUnit(value) => tr!(language, "duration-missing-unit", value = value),
+                DurationParseError::UnknownUnit(unit) => tr!(language, "duration-unknown-unit", unit = unit),
+                DurationParseError::InvalidTimeOfDay(time) => tr!(language, "duration-invalid-time", time = time),
+                DurationParseError::TimeInPast => tr!(language, "duration-time-in-past"),
+                DurationParseError::Overflow => tr!(language, "duration-overflow"),
+            })?;
+
+            if duration <= Duration::zero() {
+                return Err(tr!(language, "duration-not-positive").into());
+            }
+
+            Ok(Self(duration))
         }
+    }
 
-        async fn invoke(
-            &self,
-            cx: &Context,
-            message: &Message,
-            _args: &[&str],
-        ) -> Result<(), Box<dyn Error + Send + Sync>> {
-            let now = SystemTime::now();
-            let mut msg = message.channel_id.say(&cx.http, "*Measuring\u{2026}*").await?;
-            let ping = now.elapsed()?.as_millis();
-
-            msg.edit(&cx.http, |e| {
-                e.content(String::default()).embed(|embed| {
-                    embed
-                        .color(config::SUCCESS_COLOR)
-                        .author(|a| a.name("Rest Ping"))
-                        .description(format!("{} ms", ping))
-                })
-            }).await?;
+    /// Any text, e.g. a location or a tag's name
+    impl Argument for String {
+        fn kind() -> &'static str {
+            "text"
+        }
 
-            Ok(())
+        fn parse(input: &str, _message: &Message) -> Result<Self, Box<dyn Error + Send + Sync>> {
+            Ok(input.to_owned())
         }
     }
 
-    #[async_trait]
-    impl Command for UptimeCommand {
-        fn name(&self) -> &'static str {
-            "uptime"
+    /// Set when the user has written the argument's name
+    #[cfg_attr(not(feature = "general-commands"), allow(dead_code))]
+    pub struct Flag;
+
+    impl Argument for Flag {
+        fn kind() -> &'static str {
+            "flag"
         }
 
-        fn description(&self) -> &'static str {
-            "Sends the bot's current uptime"
+        fn is_flag() -> bool {
+            true
         }
 
-        fn category(&self) -> CommandCategory {
-            CommandCategory::General
+        fn parse(_input: &str, _message: &Message) -> Result<Self, Box<dyn Error + Send + Sync>> {
+            Ok(Flag)
         }
+    }
 
-        async fn invoke(
-            &self,
-            cx: &Context,
-            message: &Message,
-            _args: &[&str],
-        ) -> Result<(), Box<dyn Error + Send + Sync>> {
-            let uptime = SystemTime::now().duration_since(*START_TIME)?;
+    /// Takes the argument called `name` for `#[command]`, the last argument takes all the words that are left
+    pub fn take<T: Argument>(
+        args: &mut &[&str],
+        last: bool,
+        name: &str,
+        message: &Message,
+    ) -> Result<Option<T>, Box<dyn Error + Send + Sync>> {
+        while let Some((word, rest)) = args.split_first() {
+            if !word.is_empty() {
+                break;
+            }
 
-            message.channel_id.send_message(&cx.http, |m| {
-                m.embed(|embed| {
-                    let start_time: DateTime<Utc> = (*START_TIME).into();
+            *args = rest;
+        }
 
-                    embed
-                        .author(|a| a.name("Uptime"))
-                        .description(as_text(uptime.as_millis() as i64))
-                        .color(config::SUCCESS_COLOR)
-                        .footer(|f| f.text("Last Reboot"))
-                        .timestamp(&start_time)
-                })
-            }).await?;
+        let words = *args;
 
-            Ok(())
+        if words.is_empty() || T::is_flag() && !words[0].eq_ignore_ascii_case(name) {
+            return Ok(None);
         }
+
+        let input = if last {
+            words.join(" ")
+        } else {
+            words[0].to_owned()
+        };
+
+        *args = if last { &[] } else { &words[1..] };
+
+        T::parse(&input, message).map(Some)
     }
 }
 
-mod utility {
-    use chrono::{FixedOffset, NaiveDateTime, TimeZone, Utc};
-
+#[cfg(feature = "developer-commands")]
+mod dev {
     use crate::{
-        cmds::{Command, CommandCategory, CommandUsages},
-        config,
-        utils::{
-            discord::{DefaultEmbedReplies, EmbedType},
-            misc::get_wind_direction,
-        },
+        analytics,
+        availability::{self, EventKind},
+        cmds::{args::DurationArgument, CommandRegistryBuilder},
+        embeds::{self, ShardSummary},
+        i18n::tr,
+        tags,
+        utils::discord::{get_shards, DefaultEmbedReplies, EmbedType, LongReplies, ReplyOptions, ThemedMessage},
     };
 
-    use num_format::{Locale, ToFormattedString};
+    use chrono::Utc;
 
-    use openweather_async::{OpenWeather, Units};
+    use discord_test_rs_bot_macros::command;
 
     use serenity::{
         client::Context,
         model::channel::Message,
-        async_trait,
+        utils::shard_id,
     };
 
-    use std::error::Error;
+    use std::{
+        collections::HashMap,
+        error::Error,
+        process::exit,
+    };
 
-    pub struct WeatherCommand;
+    use tokio::time::Duration;
 
-    #[async_trait]
-    impl Command for WeatherCommand {
-        fn name(&self) -> &'static str {
-            "weather"
-        }
+    pub fn register(commands: &mut CommandRegistryBuilder) {
+        commands
+            .register(AnalyticsCommand)
+            .register(ShardsCommand)
+            .register(ShutdownCommand);
+    }
 
-        fn description(&self) -> &'static str {
-            "Sends the weather in the specified location"
+    #[command(
+        name = "analytics",
+        description = "Sends the most used commands, their error rates and latencies, and the daily active users",
+        category = Developer,
+    )]
+    async fn analytics(
+        cx: &Context,
+        message: &Message,
+        period: Option<DurationArgument>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+
+        let period = period.map(|p| p.0).unwrap_or_else(|| chrono::Duration::days(7));
+
+        let summary = analytics::summarize(Utc::now().timestamp() - period.num_seconds());
+
+        if summary.total == 0 {
+            return Err(tr!(language, "analytics-no-data").into());
         }
 
-        fn category(&self) -> CommandCategory {
-            CommandCategory::Utility
+        let embed = embeds::analytics(language, &summary, period);
+
+        message.channel_id.send_message(&cx.http, |m| {
+            m.themed_embed(&options, EmbedType::Info, |e| embed.apply(e, &options.theme, EmbedType::Info))
+        }).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "shards",
+        description = "Sends the status, latency and guild count of every shard",
+        category = Developer,
+    )]
+    async fn shards(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+
+        let mut shards = get_shards(cx).await.into_iter().collect::<Vec<_>>();
+
+        if shards.is_empty() {
+            return Err(tr!(language, "shards-none").into());
         }
 
-        fn usages(&self) -> CommandUsages {
-            vec![vec!["location"]]
+        shards.sort_by_key(|(id, _)| *id);
+
+        let shard_count = cx.cache.shard_count().await.max(1);
+        let mut guild_counts = HashMap::new();
+
+        for guild_id in cx.cache.guilds().await {
+            *guild_counts.entry(shard_id(*guild_id.as_u64(), shard_count)).or_insert(0usize) += 1;
         }
 
-        async fn invoke(
-            &self,
-            cx: &Context,
-            message: &Message,
-            args: &[&str],
-        ) -> Result<(), Box<dyn Error + Send + Sync>> {
-            if !args.is_empty() {
-                let api_key = config::WEATHER_API_KEY.as_str();
-                let openweather_api = OpenWeather::new(api_key, Units::Metric);
-                let weather = openweather_api.get_by_city(&args.join(" ")).await
-                    .map_err(|_| "No location has been found by the query!")?;
-                let bot_pfp = cx.http.get_current_user().await?.face();
-
-                message.channel_id.send_message(&cx.http, |m| {
-                    m.embed(|embed| {
-                        let url = format!("https://openweathermap.org/city/{}", weather.id);
-                        let location = if let Some(ref sys) = weather.sys {
-                            format!("{}, {}", weather.name, sys.country)
-                        } else {
-                            weather.name
-                        };
-
-                        let mut fields: Vec<(&str, String, bool)> = Vec::new();
-
-                        if let Some(weather_data) = weather.weather {
-                            if let Some(weather_data) = weather_data.first() {
-                                let main = &weather_data.main;
-
-                                fields.push(("Condition", main.to_string(), true));
-                            }
-                        }
-
-                        {
-                            let temp_c = weather.main.temp;
-                            let temp_f = temp_c * 1.8 + 32.0;
-                            let temp_output = {
-                                let degree_char = 0xb0 as char;
-
-                                format!(
-                                    "{c}{0}C/{f}{0}F",
-                                    degree_char,
-                                    c = temp_c as usize,
-                                    f = temp_f as usize,
-                                )
-                            };
-
-                            fields.push(("Temperature", temp_output, true));
-                        }
-
-                        {
-                            let wind = weather.wind;
-                            let mut wind_output = format!("{} m/s", wind.speed.round() as usize);
-
-                            if let Some(direction_name) = get_wind_direction(wind.deg) {
-                                wind_output.push_str(format!(", {}", direction_name).as_str());
-                            }
-
-                            fields.push(("Wind", wind_output, true));
-                        }
-
-                        {
-                            let humidity = weather.main.humidity as usize;
-
-                            fields.push(("Humidity", format!("{}%", humidity), true));
-                        }
-
-                        if let Some(cloudiness) = weather.clouds.all {
-                            fields.push(("Cloudiness", format!("{}%", cloudiness), true));
-                        }
-
-                        {
-                            let pressure = weather.main.pressure as usize;
-                            let pressure = format!("{} mbar", pressure.to_formatted_string(&Locale::en));
-
-                            fields.push(("Pressure", pressure, true));
-                        }
-
-                        if let Some(timezone_offset_secs) = weather.timezone {
-                            let offset = FixedOffset::east(timezone_offset_secs);
-
-                            if let Some(ref sys) = weather.sys {
-                                if let Some(sunrise_secs) = sys.sunrise {
-                                    let naive_sunrise = NaiveDateTime::from_timestamp(sunrise_secs as i64, 0);
-                                    let sunrise = offset.from_utc_datetime(&naive_sunrise);
-
-                                    fields.push(("Sunrise", sunrise.format("%I:%M %p").to_string(), true));
-                                }
-
-                                if let Some(sunset_secs) = sys.sunset {
-                                    let naive_sunset = NaiveDateTime::from_timestamp(sunset_secs as i64, 0);
-                                    let sunset = offset.from_utc_datetime(&naive_sunset);
-
-                                    fields.push(("Sunrise", sunset.format("%I:%M %p").to_string(), true));
-                                }
-                            }
-
-                            let local_date_time = offset.from_utc_datetime(&Utc::now().naive_utc());
-                            let formatted = local_date_time.format("%b %d, %Y, %r (UTC%:z)");
-
-                            fields.push(("Current Date", formatted.to_string(), false));
-                        }
-
-                        embed
-                            .color(config::SUCCESS_COLOR)
-                            .author(|a| a.name(location).icon_url(bot_pfp).url(url))
-                            .fields(fields)
-                            .footer(|f| f.text("Provided by OpenWeather"))
-                    })
+        let shards = shards.into_iter()
+            .map(|(id, shard)| ShardSummary {
+                id,
+                stage: shard.stage.to_string(),
+                latency: shard.latency,
+                guilds: guild_counts.get(&id).copied().unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+
+        let embed = embeds::shards(language, &shards, cx.shard_id, shard_count);
+
+        // The list goes into the description so it can be split however long it grows
+        let text = embed.description.clone().unwrap_or_default();
+
+        message.channel_id.send_long_reply(&cx.http, text, "shards.txt", EmbedType::Info, &options, |e| {
+            embed.apply(e, &options.theme, EmbedType::Info)
+        }).await?;
+
+        Ok(())
+    }
+
+    /// Records the stop and saves the command usage and tag uses recorded since the last flush before exiting
+    async fn stop() -> ! {
+        availability::record(EventKind::Stop).await;
+        analytics::flush().await;
+        tags::flush().await;
+
+        exit(0)
+    }
+
+    #[command(
+        name = "shutdown",
+        description = "Shuts the bot down",
+        category = Developer,
+    )]
+    async fn shutdown(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        const CHECK_MARK: char = '\u{2705}';
+        const CROSS_MARK: char = '\u{274E}';
+
+        let options = ReplyOptions::for_message(message);
+
+        let msg = match message.channel_id.send_default_reply(
+            &cx.http,
+            tr!(options.language, "shutdown-confirmation"),
+            EmbedType::Confirmation,
+            &options,
+        ).await {
+            Ok(msg) => msg,
+            Err(_) => stop().await,
+        };
+
+        msg.react(&cx.http, CHECK_MARK).await?;
+        msg.react(&cx.http, CROSS_MARK).await?;
+
+        let check_reaction = message.channel_id
+            .await_reaction(&cx.shard)
+            .message_id(msg.id)
+            .author_id(message.author.id)
+            .filter(|r| {
+                r.emoji.unicode_eq(&CHECK_MARK.to_string())
+                    || r.emoji.unicode_eq(&CROSS_MARK.to_string())
+            })
+            .timeout(Duration::from_secs(60))
+            .await;
+
+        match check_reaction {
+            Some(reaction) => {
+                msg.delete(&cx.http).await?;
+
+                if reaction.as_inner_ref().emoji.unicode_eq(&CHECK_MARK.to_string()) {
+                    stop().await
+                }
+            }
+            None => {
+                msg.delete(&cx.http).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "general-commands")]
+mod general {
+    use chrono::Utc;
+
+    use crate::{
+        availability::UPTIME_HISTORY,
+        cmds::{self, args::Flag, Command, CommandCategory, CommandGroup, CommandRegistryBuilder, COMMANDS},
+        embeds::{self, BotStats},
+        i18n::tr,
+        metrics,
+        scripting,
+        utils::{
+            discord::{get_shards, DefaultEmbedReplies, EmbedLimits, EmbedType, LongReplies, ReplyOptions, ThemedMessage},
+            process,
+            time::get_user_timezone,
+        },
+        COMMAND_COUNT,
+        CONNECT_COUNT,
+        START_TIME,
+    };
+
+    use discord_test_rs_bot_macros::command;
+
+    use serenity::{client::Context, model::channel::Message};
+
+    use std::{
+        error::Error,
+        sync::atomic::Ordering,
+        time::{Instant, SystemTime},
+    };
+
+    use tokio::runtime::Handle;
+
+    pub fn register(commands: &mut CommandRegistryBuilder) {
+        commands
+            .register(HelpCommand)
+            .register(PingCommand)
+            .register(StatsCommand)
+            .register(
+                CommandGroup::new("uptime", "Sends the bot's uptime or its availability and recent outages", CommandCategory::General)
+                    .subcommand(UptimeCurrentCommand)
+                    .subcommand(UptimeHistoryCommand)
+                    .default_subcommand("current"),
+            );
+    }
+
+    #[command(
+        name = "help",
+        description = "Sends a list of the bot's commands or provides help for the specified command",
+        category = General,
+    )]
+    async fn help(cx: &Context, message: &Message, command_name: Option<String>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+        let args = command_name.as_deref().unwrap_or_default().split_whitespace().collect::<Vec<_>>();
+
+        match args.first().map(|a| a.to_lowercase()) {
+            Some(cmd_name) => {
+                let scripted = scripting::find(guild_id, &cmd_name);
+                let cmd = COMMANDS.find(&cmd_name).or_else(|| scripted.as_ref().map(|s| s as &dyn Command));
+
+                if let Some(cmd) = cmd {
+                    let (path, _) = cmds::resolve(cmd, &args[1..], false);
+                    let bot_pfp = cx.http.get_current_user().await?.face();
+                    let embed = embeds::help_command(language, &path, &bot_pfp);
+
+                    message.channel_id.send_message(&cx.http, |m| {
+                        m.themed_embed(&options, EmbedType::Info, |e| embed.apply(e, &options.theme, EmbedType::Info))
+                    }).await?;
+                } else {
+                    message.channel_id.send_default_reply(
+                        &cx.http,
+                        tr!(language, "help-no-command"),
+                        EmbedType::Failure,
+                        &options,
+                    ).await?;
+                }
+            }
+            None => {
+                let bot = cx.http.get_current_user().await?;
+                let scripted = scripting::all(guild_id);
+                let commands = COMMANDS.iter()
+                    .chain(scripted.iter().map(|s| s as &dyn Command))
+                    .collect::<Vec<_>>();
+                let embed = embeds::help_overview(language, &commands, &bot.name, &bot.face());
+
+                // The list goes into the description so it can be split however long it grows
+                let text = embed.description.clone().unwrap_or_default();
+
+                message.channel_id.send_long_reply(&cx.http, text, "help.txt", EmbedType::Info, &options, |e| {
+                    embed.apply(e, &options.theme, EmbedType::Info)
                 }).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[command(
+        name = "ping",
+        description = "Sends the bot's current response latency",
+        category = General,
+        aliases = ["latency"],
+    )]
+    async fn ping(cx: &Context, message: &Message, edit: Option<Flag>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+
+        let now = Instant::now();
+        let mut msg = message.channel_id.send_message(&cx.http, |m| {
+            if let Some(reference) = options.reply_to {
+                m.reference_message(reference);
+            }
+
+            m.content(tr!(language, "ping-measuring"))
+        }).await?;
+        let rest_latency = now.elapsed();
+
+        metrics::record_rest_latency(rest_latency);
+
+        let shard = get_shards(cx).await.remove(&cx.shard_id);
+
+        let edit_latency = if edit.is_some() {
+            let now = Instant::now();
+
+            msg.edit(&cx.http, |e| e.content(tr!(language, "ping-measuring-edit"))).await?;
+
+            Some(now.elapsed())
+        } else {
+            None
+        };
+
+        let stage = shard.map(|shard| shard.stage.to_string());
+        let embed = embeds::ping(
+            language,
+            rest_latency,
+            shard.and_then(|shard| shard.latency),
+            edit_latency,
+            cx.shard_id,
+            stage.as_deref(),
+        );
+
+        msg.edit(&cx.http, |e| {
+            e.content(String::default()).embed(|e| {
+                embed.apply(options.theme.apply(e, EmbedType::Info), &options.theme, EmbedType::Info).limit()
+            })
+        }).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "current",
+        description = "Sends the bot's current uptime",
+        category = General,
+    )]
+    async fn uptime_current(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+
+        let embed = embeds::uptime(
+            language,
+            (*START_TIME).into(),
+            Utc::now(),
+            get_user_timezone(*message.author.id.as_u64()),
+        );
+
+        message.channel_id.send_message(&cx.http, |m| {
+            m.themed_embed(&options, EmbedType::Info, |e| embed.apply(e, &options.theme, EmbedType::Info))
+        }).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "history",
+        description = "Sends the bot's availability and recent outages",
+        category = General,
+    )]
+    async fn uptime_history(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+
+        let embed = embeds::uptime_history(language, &UPTIME_HISTORY.read(), Utc::now().timestamp());
+
+        message.channel_id.send_message(&cx.http, |m| {
+            m.themed_embed(&options, EmbedType::Info, |e| embed.apply(e, &options.theme, EmbedType::Info))
+        }).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "stats",
+        description = "Sends the bot's uptime, resource usage, cache sizes and build information",
+        category = General,
+        aliases = ["botinfo"],
+    )]
+    async fn stats(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+
+        let runtime = Handle::current().metrics();
+
+        let stats = BotStats {
+            uptime: SystemTime::now().duration_since(*START_TIME)?,
+            memory: process::memory_usage(),
+            cpu_time: process::cpu_time(),
+            tasks: runtime.num_alive_tasks(),
+            workers: runtime.num_workers(),
+            guilds: cx.cache.guild_count().await,
+            channels: cx.cache.guild_channel_count().await,
+            users: cx.cache.user_count().await,
+            commands_run: COMMAND_COUNT.load(Ordering::SeqCst),
+            reconnects: CONNECT_COUNT.load(Ordering::SeqCst).saturating_sub(1),
+            version: format!("{} ({})", env!("CARGO_PKG_VERSION"), env!("GIT_COMMIT")),
+            rustc_version: env!("RUSTC_VERSION").to_owned(),
+        };
+
+        let bot = cx.cache.current_user().await;
+        let embed = embeds::stats(language, &stats, &bot.name, &bot.face());
+
+        message.channel_id.send_message(&cx.http, |m| {
+            m.themed_embed(&options, EmbedType::Info, |e| embed.apply(e, &options.theme, EmbedType::Info))
+        }).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "utility-commands")]
+mod utility {
+    use chrono::{FixedOffset, NaiveDateTime, TimeZone, Utc};
+
+    use chrono_tz::Tz;
+
+    use crate::{
+        analytics,
+        chart::{render_forecast_chart, ForecastPoint},
+        cmds::{self, args::Argument, Command, CommandCategory, CommandGroup, CommandRegistryBuilder, COMMANDS},
+        config,
+        embeds,
+        i18n::{
+            get_guild_language,
+            get_language,
+            tr,
+            Language,
+            GUILD_LANGUAGES,
+            USER_LANGUAGES,
+        },
+        metrics,
+        scripting::{self, Script, SCRIPTS},
+        tags::{self, Tag, TAGS},
+        theme::{is_valid_icon, parse_color, GUILD_THEMES},
+        utils::{
+            discord::{has_permissions, DefaultEmbedReplies, EmbedType, ReplyOptions, ThemedMessage},
+            misc::format_temperature,
+            time::{get_user_timezone, parse_timezone, split_time_queries, USER_TIMEZONES},
+        },
+        weather::{self, AlertSubscription, CurrentWeather, GeoLocation, ALERT_SUBSCRIPTIONS},
+    };
+
+    use discord_test_rs_bot_macros::command;
+
+    use itertools::Itertools;
+
+    use openweather_async::{OpenWeather, Units};
+
+    use serenity::{
+        client::Context,
+        http::AttachmentType,
+        model::{
+            channel::Message,
+            permissions::Permissions,
+        },
+    };
+
+    use std::{
+        borrow::Cow,
+        collections::HashMap,
+        error::Error,
+    };
+
+    pub fn register(commands: &mut CommandRegistryBuilder) {
+        commands
+            .register(
+                CommandGroup::new("language", "Sends your current language or changes your or the server's language", CommandCategory::Utility)
+                    .aliases(&["lang", "locale"])
+                    .subcommand(LanguageShowCommand)
+                    .subcommand(LanguageSetCommand)
+                    .subcommand(LanguageResetCommand)
+                    .subcommand(
+                        CommandGroup::new("server", "Changes the server's language", CommandCategory::Utility)
+                            .subcommand(LanguageServerSetCommand)
+                            .subcommand(LanguageServerResetCommand),
+                    )
+                    .default_subcommand("show"),
+            )
+            .register(
+                CommandGroup::new("privacy", "Sends whether your command usage is recorded or lets you opt out of it", CommandCategory::Utility)
+                    .subcommand(PrivacyStatusCommand)
+                    .subcommand(PrivacyOptOutCommand)
+                    .subcommand(PrivacyOptInCommand)
+                    .default_subcommand("status"),
+            )
+            .register(
+                CommandGroup::new("script", "Manages the server's scripted commands", CommandCategory::Utility)
+                    .subcommand(ScriptAddCommand)
+                    .subcommand(ScriptEditCommand)
+                    .subcommand(ScriptRemoveCommand)
+                    .subcommand(ScriptListCommand)
+                    .subcommand(ScriptShowCommand)
+                    .default_subcommand("list"),
+            )
+            .register(
+                CommandGroup::new("tag", "Sends a tag or manages the server's tags", CommandCategory::Utility)
+                    .subcommand(TagAddCommand)
+                    .subcommand(TagEditCommand)
+                    .subcommand(TagDeleteCommand)
+                    .subcommand(TagListCommand)
+                    .subcommand(TagInfoCommand)
+                    .subcommand(TagShowCommand)
+                    .default_subcommand("show"),
+            )
+            .register(
+                CommandGroup::new("theme", "Sends or changes the server's embed theme", CommandCategory::Utility)
+                    .aliases(&["style"])
+                    .subcommand(ThemeShowCommand)
+                    .subcommand(ThemeColorCommand)
+                    .subcommand(ThemeIconCommand)
+                    .subcommand(ThemeFooterCommand)
+                    .subcommand(ThemeTimestampCommand)
+                    .subcommand(ThemeReplyCommand)
+                    .subcommand(ThemeResetCommand)
+                    .default_subcommand("show"),
+            )
+            .register(
+                CommandGroup::new("time", "Sends the current time in the specified locations or saves your time zone", CommandCategory::Utility)
+                    .aliases(&["clock", "timezone", "tz"])
+                    .subcommand(TimeShowCommand)
+                    .subcommand(TimeSetCommand)
+                    .subcommand(TimeResetCommand)
+                    .default_subcommand("show"),
+            )
+            .register(
+                CommandGroup::new("weather", "Sends the weather or a forecast, or manages weather alerts", CommandCategory::Utility)
+                    .subcommand(WeatherCurrentCommand)
+                    .subcommand(WeatherForecastCommand)
+                    .subcommand(
+                        CommandGroup::new("alerts", "Manages the channel's weather alerts", CommandCategory::Utility)
+                            .subcommand(WeatherAlertsListCommand)
+                            .subcommand(WeatherAlertsSubscribeCommand)
+                            .subcommand(WeatherAlertsUnsubscribeCommand),
+                    )
+                    .default_subcommand("current"),
+            );
+    }
+
+    /// Resolves either an IANA time zone name or a location to a display name and a time zone
+    async fn resolve_timezone(query: &str, language: Language) -> Result<(String, Tz), Box<dyn Error + Send + Sync>> {
+        if let Some(tz) = parse_timezone(query) {
+            return Ok((tz.name().to_owned(), tz));
+        }
+
+        let location = weather::geocode(query).await?
+            .ok_or_else(|| tr!(language, "time-not-found", query = query))?;
+        let one_call = weather::one_call(location.lat, location.lon).await?;
+        let tz = parse_timezone(&one_call.timezone)
+            .ok_or_else(|| tr!(language, "time-unknown-zone", location = location.display_name()))?;
+
+        Ok((location.display_name(), tz))
+    }
+
+    /// The languages a user or a server can choose from, e.g. `English (en-US), Deutsch (de)`
+    fn available_languages() -> String {
+        Language::ALL.iter()
+            .map(|l| format!("{} (`{}`)", l.native_name(), l.code()))
+            .join(", ")
+    }
+
+    /// A language by its code or its name, e.g. `de` or `Deutsch`
+    impl Argument for Language {
+        fn kind() -> &'static str {
+            "language"
+        }
+
+        fn parse(input: &str, message: &Message) -> Result<Self, Box<dyn Error + Send + Sync>> {
+            Language::parse(input).ok_or_else(|| {
+                tr!(get_language(message), "language-unknown", query = input, available = available_languages()).into()
+            })
+        }
+    }
+
+    /// Saves the author's language or, with `is_server`, the guild's
+    async fn set_language(
+        cx: &Context,
+        message: &Message,
+        new_language: Language,
+        is_server: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let (store, key) = if is_server {
+            (&*GUILD_LANGUAGES, message.guild_id.map(|id| *id.as_u64()).unwrap_or_default())
+        } else {
+            (&*USER_LANGUAGES, *message.author.id.as_u64())
+        };
+
+        store.update(|languages| languages.insert(key, new_language.code().to_owned())).await?;
+
+        // The confirmation is already sent in the newly effective language
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+        let name = new_language.native_name();
+
+        let reply = if is_server {
+            tr!(language, "language-server-set", language = name)
+        } else {
+            tr!(language, "language-set", language = name)
+        };
+
+        message.channel_id.send_default_reply(&cx.http, reply, EmbedType::Success, &options).await?;
+
+        Ok(())
+    }
+
+    /// Forgets the author's language or, with `is_server`, the guild's
+    async fn reset_language(cx: &Context, message: &Message, is_server: bool) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let language = get_language(message);
+
+        let (store, key) = if is_server {
+            (&*GUILD_LANGUAGES, message.guild_id.map(|id| *id.as_u64()).unwrap_or_default())
+        } else {
+            (&*USER_LANGUAGES, *message.author.id.as_u64())
+        };
+
+        let removed = store.update(|languages| languages.remove(&key)).await?;
+
+        if removed.is_none() {
+            let error = if is_server {
+                tr!(language, "language-server-not-set")
             } else {
-                message.channel_id.send_default_reply(
-                    &cx.http,
-                    "You have provided no arguments!",
-                    EmbedType::Failure,
-                ).await?;
+                tr!(language, "language-not-set")
+            };
+
+            return Err(error.into());
+        }
+
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+
+        let reply = if is_server {
+            tr!(language, "language-server-reset")
+        } else {
+            tr!(language, "language-reset")
+        };
+
+        message.channel_id.send_default_reply(&cx.http, reply, EmbedType::Success, &options).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "show",
+        description = "Sends your current language and the server's",
+        category = Utility,
+    )]
+    async fn language_show(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+
+        let reply = tr!(
+            language,
+            "language-current",
+            language = language.native_name(),
+            server_language = get_guild_language(message.guild_id.map(|id| *id.as_u64())).native_name(),
+            available = available_languages(),
+        );
+
+        message.channel_id.send_default_reply(&cx.http, reply, EmbedType::Success, &options).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "set",
+        description = "Changes your language",
+        category = Utility,
+    )]
+    async fn language_set(cx: &Context, message: &Message, language: Language) -> Result<(), Box<dyn Error + Send + Sync>> {
+        set_language(cx, message, language, false).await
+    }
+
+    #[command(
+        name = "reset",
+        description = "Resets your language to the server's",
+        category = Utility,
+    )]
+    async fn language_reset(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        reset_language(cx, message, false).await
+    }
+
+    #[command(
+        name = "set",
+        description = "Changes the server's language",
+        category = Utility,
+        permissions = MANAGE_GUILD,
+    )]
+    async fn language_server_set(cx: &Context, message: &Message, language: Language) -> Result<(), Box<dyn Error + Send + Sync>> {
+        set_language(cx, message, language, true).await
+    }
+
+    #[command(
+        name = "reset",
+        description = "Resets the server's language to English",
+        category = Utility,
+        permissions = MANAGE_GUILD,
+    )]
+    async fn language_server_reset(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        reset_language(cx, message, true).await
+    }
+
+    #[command(
+        name = "status",
+        description = "Sends whether your command usage is recorded",
+        category = Utility,
+    )]
+    async fn privacy_status(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+
+        let reply = if analytics::is_opted_out(*message.author.id.as_u64()) {
+            tr!(language, "privacy-status-opted-out")
+        } else {
+            tr!(language, "privacy-status-recorded", days = config::ANALYTICS_USER_RETENTION.as_secs() / (24 * 60 * 60))
+        };
+
+        message.channel_id.send_default_reply(&cx.http, reply, EmbedType::Success, &options).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "opt-out",
+        description = "Stops linking the commands you run to you",
+        category = Utility,
+        aliases = ["optout"],
+    )]
+    async fn privacy_opt_out(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+
+        analytics::opt_out(*message.author.id.as_u64()).await?;
+
+        message.channel_id.send_default_reply(
+            &cx.http,
+            tr!(options.language, "privacy-opted-out"),
+            EmbedType::Success,
+            &options,
+        ).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "opt-in",
+        description = "Links the commands you run to you again",
+        category = Utility,
+        aliases = ["optin"],
+    )]
+    async fn privacy_opt_in(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+
+        analytics::opt_in(*message.author.id.as_u64()).await?;
+
+        message.channel_id.send_default_reply(
+            &cx.http,
+            tr!(options.language, "privacy-opted-in"),
+            EmbedType::Success,
+            &options,
+        ).await?;
+
+        Ok(())
+    }
+
+    /// The embed type a theme setting applies to
+    impl Argument for EmbedType {
+        fn kind() -> &'static str {
+            "embed type"
+        }
+
+        fn parse(input: &str, message: &Message) -> Result<Self, Box<dyn Error + Send + Sync>> {
+            let types = EmbedType::ALL.iter()
+                .map(|t| format!("`{}`", t.to_string().to_lowercase()))
+                .join(", ");
+
+            EmbedType::parse(input)
+                .ok_or_else(|| tr!(get_language(message), "theme-unknown-type", types = types).into())
+        }
+    }
+
+    /// Either `on` or `off`
+    struct Switch(bool);
+
+    impl Argument for Switch {
+        fn kind() -> &'static str {
+            "on/off"
+        }
+
+        fn parse(input: &str, message: &Message) -> Result<Self, Box<dyn Error + Send + Sync>> {
+            match input.to_lowercase().as_str() {
+                "on" => Ok(Self(true)),
+                "off" => Ok(Self(false)),
+                _ => Err(tr!(get_language(message), "theme-invalid-switch").into()),
+            }
+        }
+    }
+
+    fn switch_name(value: bool, language: Language) -> String {
+        if value {
+            tr!(language, "theme-on")
+        } else {
+            tr!(language, "theme-off")
+        }
+    }
+
+    fn is_reset(value: &str) -> bool {
+        value.eq_ignore_ascii_case("reset")
+    }
+
+    /// Confirms a change of the theme, which the confirmation already follows
+    async fn confirm_theme(cx: &Context, message: &Message, reply: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+
+        message.channel_id.send_default_reply(&cx.http, reply, EmbedType::Success, &options).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "show",
+        description = "Sends the server's embed theme",
+        category = Utility,
+    )]
+    async fn theme_show(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let embed = embeds::theme(options.language, &options.theme);
+
+        message.channel_id.send_message(&cx.http, |m| {
+            m.themed_embed(&options, EmbedType::Info, |e| embed.apply(e, &options.theme, EmbedType::Info))
+        }).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "color",
+        description = "Changes the color of an embed type",
+        category = Utility,
+        aliases = ["colour"],
+        usages = [["embed type", "hex color/reset"]],
+        permissions = MANAGE_GUILD,
+    )]
+    async fn theme_color(
+        cx: &Context,
+        message: &Message,
+        embed_type: EmbedType,
+        color: String,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let language = get_language(message);
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+        let name = embed_type.to_string().to_lowercase();
+
+        let reply = if is_reset(&color) {
+            GUILD_THEMES.update(|themes| themes.entry(guild_id).or_default().colors.remove(&embed_type)).await?;
+
+            tr!(language, "theme-color-reset", name = name)
+        } else {
+            let color = parse_color(&color)
+                .ok_or_else(|| tr!(language, "theme-invalid-color", color = color.as_str()))?;
+
+            GUILD_THEMES.update(|themes| themes.entry(guild_id).or_default().colors.insert(embed_type, color)).await?;
+
+            tr!(language, "theme-color-set", name = name, color = format!("#{:06X}", color))
+        };
+
+        confirm_theme(cx, message, reply).await
+    }
+
+    #[command(
+        name = "icon",
+        description = "Changes the icon of an embed type",
+        category = Utility,
+        usages = [["embed type", "emoji/image URL/reset"]],
+        permissions = MANAGE_GUILD,
+    )]
+    async fn theme_icon(
+        cx: &Context,
+        message: &Message,
+        embed_type: EmbedType,
+        icon: String,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let language = get_language(message);
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+        let name = embed_type.to_string().to_lowercase();
+
+        let reply = if is_reset(&icon) {
+            GUILD_THEMES.update(|themes| themes.entry(guild_id).or_default().icons.remove(&embed_type)).await?;
+
+            tr!(language, "theme-icon-reset", name = name)
+        } else {
+            if !is_valid_icon(&icon) {
+                return Err(tr!(language, "theme-invalid-icon", icon = icon).into());
+            }
+
+            GUILD_THEMES.update(|themes| themes.entry(guild_id).or_default().icons.insert(embed_type, icon)).await?;
+
+            tr!(language, "theme-icon-set", name = name)
+        };
+
+        confirm_theme(cx, message, reply).await
+    }
+
+    #[command(
+        name = "footer",
+        description = "Changes the footer of every embed",
+        category = Utility,
+        usages = [["text/reset"]],
+        permissions = MANAGE_GUILD,
+    )]
+    async fn theme_footer(cx: &Context, message: &Message, text: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let language = get_language(message);
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+
+        let footer = (!is_reset(&text)).then(|| text);
+        let is_set = footer.is_some();
+
+        GUILD_THEMES.update(|themes| themes.entry(guild_id).or_default().footer = footer).await?;
+
+        let reply = if is_set {
+            tr!(language, "theme-footer-set")
+        } else {
+            tr!(language, "theme-footer-reset")
+        };
+
+        confirm_theme(cx, message, reply).await
+    }
+
+    #[command(
+        name = "timestamp",
+        description = "Turns the timestamps of embeds on or off",
+        category = Utility,
+        aliases = ["timestamps"],
+        usages = [["on/off"]],
+        permissions = MANAGE_GUILD,
+    )]
+    async fn theme_timestamp(cx: &Context, message: &Message, state: Switch) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let language = get_language(message);
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+        let Switch(value) = state;
+
+        GUILD_THEMES.update(|themes| themes.entry(guild_id).or_default().timestamp = value).await?;
+
+        confirm_theme(cx, message, tr!(language, "theme-timestamp-set", state = switch_name(value, language))).await
+    }
+
+    #[command(
+        name = "reply",
+        description = "Turns replying to the commands' messages on or off",
+        category = Utility,
+        aliases = ["replies"],
+        usages = [["on/off"]],
+        permissions = MANAGE_GUILD,
+    )]
+    async fn theme_reply(cx: &Context, message: &Message, state: Switch) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let language = get_language(message);
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+        let Switch(value) = state;
+
+        GUILD_THEMES.update(|themes| themes.entry(guild_id).or_default().reply = value).await?;
+
+        confirm_theme(cx, message, tr!(language, "theme-reply-set", state = switch_name(value, language))).await
+    }
+
+    #[command(
+        name = "reset",
+        description = "Resets the server's embed theme to the bot's defaults",
+        category = Utility,
+        permissions = MANAGE_GUILD,
+    )]
+    async fn theme_reset(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let language = get_language(message);
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+
+        GUILD_THEMES.update(|themes| themes.remove(&guild_id)).await?;
+
+        confirm_theme(cx, message, tr!(language, "theme-reset")).await
+    }
+
+    #[command(
+        name = "show",
+        description = "Sends the current time in the specified locations or time zones, or in your own",
+        category = Utility,
+        usages = [
+            ["location/time zone (optional)"],
+            ["location/time zone, location/time zone, \u{2026}"],
+        ],
+    )]
+    async fn time_show(cx: &Context, message: &Message, locations: Option<String>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+        let user_timezone = get_user_timezone(*message.author.id.as_u64());
+
+        let mut zones = Vec::new();
+
+        if let Some(input) = locations {
+            let queries = split_time_queries(&input);
+
+            if queries.len() > config::TIME_MAX_ZONES {
+                return Err(tr!(language, "time-too-many", limit = config::TIME_MAX_ZONES).into());
+            }
+
+            for query in queries {
+                zones.push(resolve_timezone(query, language).await?);
+            }
+
+            if zones.is_empty() {
+                return Err(tr!(language, "time-no-argument").into());
+            }
+        } else {
+            let tz = user_timezone.ok_or_else(|| tr!(language, "time-not-set-hint", prefix = config::PREFIX))?;
+
+            zones.push((tz.name().to_owned(), tz));
+        }
+
+        let embed = embeds::world_clock(language, &zones, user_timezone, Utc::now());
+
+        message.channel_id.send_message(&cx.http, |m| {
+            m.themed_embed(&options, EmbedType::Info, |e| embed.apply(e, &options.theme, EmbedType::Info))
+        }).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "set",
+        description = "Saves your time zone, which the bot's times and dates are shown in",
+        category = Utility,
+        usages = [["location/time zone"]],
+    )]
+    async fn time_set(cx: &Context, message: &Message, location: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+
+        let (_, tz) = resolve_timezone(&location, language).await?;
+
+        USER_TIMEZONES.update(|timezones| timezones.insert(*message.author.id.as_u64(), tz.name().to_owned())).await?;
+
+        message.channel_id.send_default_reply(
+            &cx.http,
+            tr!(language, "time-set", zone = tz.name()),
+            EmbedType::Success,
+            &options,
+        ).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "reset",
+        description = "Forgets your time zone",
+        category = Utility,
+    )]
+    async fn time_reset(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+
+        let removed = USER_TIMEZONES.update(|timezones| timezones.remove(message.author.id.as_u64())).await?;
+
+        if removed.is_none() {
+            return Err(tr!(language, "time-not-set").into());
+        }
+
+        message.channel_id.send_default_reply(
+            &cx.http,
+            tr!(language, "time-reset"),
+            EmbedType::Success,
+            &options,
+        ).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "current",
+        description = "Sends the current weather in the specified location",
+        category = Utility,
+    )]
+    async fn weather_current(cx: &Context, message: &Message, location: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+
+        let api_key = config::WEATHER_API_KEY.as_str();
+        let openweather_api = OpenWeather::new(api_key, Units::Metric);
+
+        let weather = metrics::track_api_call("openweather_weather", openweather_api.get_by_city(&location))
+            .await
+            .map_err(|_| tr!(language, "weather-not-found"))?;
+
+        let additional = weather::additional_conditions(weather.coord.lat as f64, weather.coord.lon as f64).await;
+        let bot_pfp = cx.http.get_current_user().await?.face();
+
+        let condition = weather.weather.as_ref().and_then(|conditions| conditions.first());
+        let current = CurrentWeather {
+            city_id: weather.id as u64,
+            location: match weather.sys {
+                Some(ref sys) => format!("{}, {}", weather.name, sys.country),
+                None => weather.name.clone(),
+            },
+            condition: condition.map(|c| c.description.clone()),
+            icon: condition.map(|c| c.icon.clone()),
+            temperature: weather.main.temp,
+            humidity: weather.main.humidity as f32,
+            pressure: weather.main.pressure as f32,
+            wind_speed: weather.wind.speed,
+            wind_direction: weather.wind.deg,
+            cloudiness: weather.clouds.all.map(|cloudiness| cloudiness as u32),
+            timezone: weather.timezone,
+            sunrise: weather.sys.as_ref().and_then(|sys| sys.sunrise).map(|secs| secs as i64),
+            sunset: weather.sys.as_ref().and_then(|sys| sys.sunset).map(|secs| secs as i64),
+        };
+
+        let embed = embeds::weather(language, &current, &additional, Utc::now(), &bot_pfp);
+
+        message.channel_id.send_message(&cx.http, |m| {
+            m.themed_embed(&options, EmbedType::Info, |e| embed.apply(e, &options.theme, EmbedType::Info))
+        }).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "forecast",
+        description = "Sends the five-day forecast for the specified location",
+        category = Utility,
+    )]
+    async fn weather_forecast(cx: &Context, message: &Message, location: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+
+        let location = weather::geocode(&location).await?
+            .ok_or_else(|| tr!(language, "weather-not-found"))?;
+        let forecast = weather::forecast(location.lat, location.lon).await?;
+        let offset = FixedOffset::east(forecast.city.timezone);
+
+        let entries = forecast.list.iter()
+            .map(|e| (offset.from_utc_datetime(&NaiveDateTime::from_timestamp(e.dt, 0)), e))
+            .collect::<Vec<_>>();
+
+        let points = entries.iter()
+            .map(|(date_time, e)| ForecastPoint {
+                label: date_time.format("%a %Hh").to_string(),
+                temp: e.main.temp,
+                precipitation: e.precipitation(),
+            })
+            .collect::<Vec<ForecastPoint>>();
+
+        let fields = entries.iter()
+            .group_by(|(date_time, _)| date_time.date())
+            .into_iter()
+            .map(|(date, group)| {
+                let group = group.map(|(_, e)| e).collect::<Vec<_>>();
+
+                let min = group.iter().map(|e| e.main.temp).fold(f64::MAX, f64::min);
+                let max = group.iter().map(|e| e.main.temp).fold(f64::MIN, f64::max);
+                let precipitation: f64 = group.iter().map(|e| e.precipitation()).sum();
+
+                let output = format!(
+                    "{} \u{2013} {}\n{}",
+                    format_temperature(min as f32),
+                    format_temperature(max as f32),
+                    tr!(language, "weather-precipitation", amount = format!("{:.1}", precipitation)),
+                );
+
+                (date.format("%a, %b %d").to_string(), output, true)
+            })
+            .collect::<Vec<(String, String, bool)>>();
+
+        let chart = tokio::task::spawn_blocking(move || render_forecast_chart(&points)).await??;
+        let bot_pfp = cx.http.get_current_user().await?.face();
+
+        message.channel_id.send_message(&cx.http, |m| {
+            m.add_file(AttachmentType::Bytes {
+                data: Cow::Owned(chart),
+                filename: "forecast.png".to_owned(),
+            });
+
+            m.themed_embed(&options, EmbedType::Info, |embed| {
+                let title = tr!(language, "weather-forecast-title", location = location.display_name());
+
+                options.theme.author(embed, EmbedType::Info, title, |a| a.icon_url(bot_pfp))
+                    .fields(fields)
+                    .image("attachment://forecast.png")
+                    .footer(|f| f.text(tr!(language, "weather-provided-by")))
+            })
+        }).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "list",
+        description = "Sends the locations the channel receives weather alerts for",
+        category = Utility,
+    )]
+    async fn weather_alerts_list(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+        let channel_id = *message.channel_id.as_u64();
+
+        let locations = ALERT_SUBSCRIPTIONS.read()
+            .iter()
+            .filter(|s| s.channel_id == channel_id)
+            .map(|s| s.location.clone())
+            .collect::<Vec<String>>();
+
+        if locations.is_empty() {
+            message.channel_id.send_default_reply(
+                &cx.http,
+                tr!(language, "alerts-list-empty"),
+                EmbedType::Failure,
+                &options,
+            ).await?;
+        } else {
+            message.channel_id.send_default_reply(
+                &cx.http,
+                tr!(language, "alerts-list", count = locations.len(), locations = locations.join("\n")),
+                EmbedType::Success,
+                &options,
+            ).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the location typed after `subscribe` or `unsubscribe`
+    async fn alert_location(query: &str, language: Language) -> Result<GeoLocation, Box<dyn Error + Send + Sync>> {
+        let location = weather::geocode(query).await?
+            .ok_or_else(|| tr!(language, "weather-not-found"))?;
+
+        Ok(location)
+    }
+
+    fn is_subscribed(subscription: &AlertSubscription, channel_id: u64, location: &GeoLocation) -> bool {
+        subscription.channel_id == channel_id && subscription.lat == location.lat && subscription.lon == location.lon
+    }
+
+    #[command(
+        name = "subscribe",
+        description = "Subscribes the channel to weather alerts for the specified location",
+        category = Utility,
+        permissions = MANAGE_CHANNELS,
+    )]
+    async fn weather_alerts_subscribe(cx: &Context, message: &Message, location: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+        let channel_id = *message.channel_id.as_u64();
+
+        let location = alert_location(&location, language).await?;
+        let name = location.display_name();
+
+        let added = ALERT_SUBSCRIPTIONS.update(|subscriptions| {
+            if subscriptions.iter().any(|s| is_subscribed(s, channel_id, &location)) {
+                return false;
+            }
+
+            subscriptions.push(AlertSubscription {
+                channel_id,
+                guild_id: message.guild_id.map(|id| *id.as_u64()),
+                location: name.clone(),
+                lat: location.lat,
+                lon: location.lon,
+                seen_alerts: HashMap::new(),
+            });
+
+            true
+        }).await?;
+
+        if !added {
+            return Err(tr!(language, "alerts-already-subscribed", location = name).into());
+        }
+
+        message.channel_id.send_default_reply(
+            &cx.http,
+            tr!(language, "alerts-subscribed", location = name),
+            EmbedType::Success,
+            &options,
+        ).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "unsubscribe",
+        description = "Unsubscribes the channel from weather alerts for the specified location",
+        category = Utility,
+        permissions = MANAGE_CHANNELS,
+    )]
+    async fn weather_alerts_unsubscribe(cx: &Context, message: &Message, location: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+        let channel_id = *message.channel_id.as_u64();
+
+        let location = alert_location(&location, language).await?;
+        let name = location.display_name();
+
+        let removed = ALERT_SUBSCRIPTIONS.update(|subscriptions| {
+            let count = subscriptions.len();
+
+            subscriptions.retain(|s| !is_subscribed(s, channel_id, &location));
+
+            subscriptions.len() != count
+        }).await?;
+
+        if !removed {
+            return Err(tr!(language, "alerts-not-subscribed", location = name).into());
+        }
+
+        message.channel_id.send_default_reply(
+            &cx.http,
+            tr!(language, "alerts-unsubscribed", location = name),
+            EmbedType::Success,
+            &options,
+        ).await?;
+
+        Ok(())
+    }
+
+    /// Checks the name of a scripted command or a tag that is about to be added or edited
+    fn custom_name(name: &str, language: Language) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let name = name.to_lowercase();
+
+        if !scripting::is_valid_name(&name) {
+            return Err(tr!(language, "custom-invalid-name", name = name).into());
+        }
+
+        // Built-in commands are looked up first, so such a script or tag could never run
+        if COMMANDS.find(&name).is_some() {
+            return Err(tr!(language, "custom-builtin-name", name = name).into());
+        }
+
+        Ok(name)
+    }
+
+    /// Reads a scripted command's name, description and source from what follows `script add` or `script edit`
+    fn script_definition(definition: &str, language: Language) -> Result<(String, String, String), Box<dyn Error + Send + Sync>> {
+        let text = definition.trim();
+        let (name, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+
+        let name = custom_name(name, language)?;
+        let (description, source) = scripting::parse_definition(rest);
+
+        if source.is_empty() {
+            return Err(tr!(language, "script-no-source").into());
+        }
+
+        scripting::compile(&source).map_err(|error| tr!(language, "script-invalid", error = error))?;
+
+        Ok((name, description, source))
+    }
+
+    #[command(
+        name = "add",
+        description = "Adds a scripted command to the server",
+        category = Utility,
+        usages = [["name", "description (optional)", "code block"]],
+        permissions = MANAGE_GUILD,
+    )]
+    async fn script_add(cx: &Context, message: &Message, definition: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+
+        let (name, description, source) = script_definition(&definition, language)?;
+
+        if tags::find(guild_id, &name).is_some() {
+            return Err(tr!(language, "custom-name-taken", name = name).into());
+        }
+
+        SCRIPTS.update(|scripts| {
+            let scripts = scripts.entry(guild_id).or_default();
+
+            if scripts.iter().any(|s| s.name == name) {
+                return Err(tr!(language, "custom-name-taken", name = name.as_str()));
+            }
+
+            if scripts.len() >= config::SCRIPTS_PER_GUILD {
+                return Err(tr!(language, "script-limit", limit = config::SCRIPTS_PER_GUILD));
+            }
+
+            scripts.push(Script {
+                name: name.clone(),
+                description,
+                source,
+                author_id: *message.author.id.as_u64(),
+            });
+
+            Ok(())
+        }).await??;
+
+        message.channel_id.send_default_reply(
+            &cx.http,
+            tr!(language, "script-added", name = name),
+            EmbedType::Success,
+            &options,
+        ).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "edit",
+        description = "Replaces the description and the code of a scripted command",
+        category = Utility,
+        usages = [["name", "description (optional)", "code block"]],
+        permissions = MANAGE_GUILD,
+    )]
+    async fn script_edit(cx: &Context, message: &Message, definition: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+
+        let (name, description, source) = script_definition(&definition, language)?;
+
+        let edited = SCRIPTS.update(|scripts| {
+            let script = scripts.get_mut(&guild_id).and_then(|scripts| scripts.iter_mut().find(|s| s.name == name));
+
+            match script {
+                Some(script) => {
+                    script.description = description;
+                    script.source = source;
+
+                    true
+                }
+                None => false,
+            }
+        }).await?;
+
+        if !edited {
+            return Err(tr!(language, "script-not-found", name = name).into());
+        }
+
+        message.channel_id.send_default_reply(
+            &cx.http,
+            tr!(language, "script-edited", name = name),
+            EmbedType::Success,
+            &options,
+        ).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "remove",
+        description = "Removes a scripted command from the server",
+        category = Utility,
+        aliases = ["delete"],
+        permissions = MANAGE_GUILD,
+    )]
+    async fn script_remove(cx: &Context, message: &Message, name: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+
+        let name = name.to_lowercase();
+
+        let removed = SCRIPTS.update(|scripts| match scripts.get_mut(&guild_id) {
+            Some(scripts) => {
+                let count = scripts.len();
+
+                scripts.retain(|s| s.name != name);
+                scripts.len() < count
             }
+            None => false,
+        }).await?;
+
+        if !removed {
+            return Err(tr!(language, "script-not-found", name = name).into());
+        }
+
+        message.channel_id.send_default_reply(
+            &cx.http,
+            tr!(language, "script-removed", name = name),
+            EmbedType::Success,
+            &options,
+        ).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "list",
+        description = "Sends the server's scripted commands",
+        category = Utility,
+    )]
+    async fn script_list(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+
+        let scripts = scripting::all(guild_id).iter()
+            .map(|s| match s.description() {
+                "" => format!("`{}`", s.name()),
+                description => format!("`{}` \u{2013} {}", s.name(), description),
+            })
+            .collect::<Vec<String>>();
+
+        if scripts.is_empty() {
+            message.channel_id.send_default_reply(
+                &cx.http,
+                tr!(language, "script-list-empty"),
+                EmbedType::Failure,
+                &options,
+            ).await?;
+        } else {
+            message.channel_id.send_default_long_reply(
+                &cx.http,
+                tr!(language, "script-list", count = scripts.len(), scripts = scripts.join("\n")),
+                EmbedType::Info,
+                &options,
+            ).await?;
+        }
+
+        Ok(())
+    }
+
+    #[command(
+        name = "show",
+        description = "Sends the code of a scripted command",
+        category = Utility,
+        permissions = MANAGE_GUILD,
+    )]
+    async fn script_show(cx: &Context, message: &Message, name: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+
+        let name = name.to_lowercase();
+        let script = scripting::find(guild_id, &name)
+            .ok_or_else(|| tr!(language, "script-not-found", name = name.as_str()))?
+            .0;
+
+        message.channel_id.send_default_long_reply(
+            &cx.http,
+            tr!(language, "script-source", author = format!("<@{}>", script.author_id), source = script.source),
+            EmbedType::Info,
+            &options,
+        ).await?;
+
+        Ok(())
+    }
+
+    /// Reads a tag's name and content from what follows `tag add` or `tag edit`
+    fn tag_definition(definition: &str, language: Language) -> Result<(String, String), Box<dyn Error + Send + Sync>> {
+        let text = definition.trim();
+        let (name, content) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+
+        let name = custom_name(name, language)?;
+        let content = content.trim();
+
+        if content.is_empty() {
+            return Err(tr!(language, "tag-no-content").into());
+        }
+
+        if content.chars().count() > config::TAG_MAX_LENGTH {
+            return Err(tr!(language, "tag-too-long", limit = config::TAG_MAX_LENGTH).into());
+        }
+
+        Ok((name, content.to_owned()))
+    }
+
+    /// Looks up a tag by its name in any case
+    fn find_tag(guild_id: u64, name: &str, language: Language) -> Result<Tag, Box<dyn Error + Send + Sync>> {
+        let name = name.to_lowercase();
+
+        tags::find(guild_id, &name)
+            .map(|t| t.0)
+            .ok_or_else(|| tr!(language, "tag-not-found", name = name).into())
+    }
+
+    /// Tags can be edited and deleted by their owners and by whoever can manage the server
+    async fn can_manage_tag(cx: &Context, message: &Message, tag: &Tag) -> bool {
+        tag.owner_id == *message.author.id.as_u64() || has_permissions(cx, message, Permissions::MANAGE_GUILD).await
+    }
+
+    #[command(
+        name = "add",
+        description = "Adds a tag to the server",
+        category = Utility,
+        aliases = ["create"],
+        usages = [["name", "text"]],
+        permissions = MANAGE_MESSAGES,
+    )]
+    async fn tag_add(cx: &Context, message: &Message, definition: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+
+        let (name, content) = tag_definition(&definition, language)?;
+
+        // `<tag add` and the like would run the subcommand instead of such a tag
+        let is_subcommand = COMMANDS.find("tag")
+            .map_or(false, |tag| cmds::find(tag.subcommands(), &name).is_some());
+
+        if is_subcommand {
+            return Err(tr!(language, "tag-subcommand-name", name = name).into());
+        }
+
+        if scripting::find(guild_id, &name).is_some() {
+            return Err(tr!(language, "custom-name-taken", name = name).into());
+        }
+
+        TAGS.update(|tags| {
+            let tags = tags.entry(guild_id).or_default();
+
+            if tags.iter().any(|t| t.name == name) {
+                return Err(tr!(language, "custom-name-taken", name = name.as_str()));
+            }
+
+            if tags.len() >= config::TAGS_PER_GUILD {
+                return Err(tr!(language, "tag-limit", limit = config::TAGS_PER_GUILD));
+            }
+
+            tags.push(Tag {
+                name: name.clone(),
+                content,
+                owner_id: *message.author.id.as_u64(),
+                uses: 0,
+                created: Utc::now().timestamp(),
+            });
+
+            Ok(())
+        }).await??;
+
+        message.channel_id.send_default_reply(
+            &cx.http,
+            tr!(language, "tag-added", name = name),
+            EmbedType::Success,
+            &options,
+        ).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "edit",
+        description = "Replaces the text of a tag",
+        category = Utility,
+        usages = [["name", "text"]],
+    )]
+    async fn tag_edit(cx: &Context, message: &Message, definition: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+
+        let (name, content) = tag_definition(&definition, language)?;
+        let tag = find_tag(guild_id, &name, language)?;
+
+        if !can_manage_tag(cx, message, &tag).await {
+            return Err(tr!(language, "tag-not-owner", name = tag.name).into());
+        }
+
+        let edited = TAGS.update(|tags| {
+            let tag = tags.get_mut(&guild_id).and_then(|tags| tags.iter_mut().find(|t| t.name == name));
+
+            match tag {
+                Some(tag) => {
+                    tag.content = content;
+
+                    true
+                }
+                None => false,
+            }
+        }).await?;
+
+        if !edited {
+            return Err(tr!(language, "tag-not-found", name = name).into());
+        }
+
+        message.channel_id.send_default_reply(
+            &cx.http,
+            tr!(language, "tag-edited", name = name),
+            EmbedType::Success,
+            &options,
+        ).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "delete",
+        description = "Deletes a tag from the server",
+        category = Utility,
+        aliases = ["remove"],
+    )]
+    async fn tag_delete(cx: &Context, message: &Message, name: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+
+        let tag = find_tag(guild_id, &name, language)?;
+
+        if !can_manage_tag(cx, message, &tag).await {
+            return Err(tr!(language, "tag-not-owner", name = tag.name).into());
+        }
+
+        TAGS.update(|tags| {
+            if let Some(tags) = tags.get_mut(&guild_id) {
+                tags.retain(|t| t.name != tag.name);
+            }
+        }).await?;
+
+        message.channel_id.send_default_reply(
+            &cx.http,
+            tr!(language, "tag-deleted", name = tag.name),
+            EmbedType::Success,
+            &options,
+        ).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "list",
+        description = "Sends the server's tags",
+        category = Utility,
+    )]
+    async fn tag_list(cx: &Context, message: &Message) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+
+        let tags = TAGS.read()
+            .get(&guild_id)
+            .map(|tags| {
+                tags.iter()
+                    .sorted_by(|a, b| b.uses.cmp(&a.uses).then_with(|| a.name.cmp(&b.name)))
+                    .map(|t| format!("`{}` \u{2013} {}", t.name, tr!(language, "tag-uses", count = t.uses)))
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default();
+
+        if tags.is_empty() {
+            message.channel_id.send_default_reply(
+                &cx.http,
+                tr!(language, "tag-list-empty"),
+                EmbedType::Failure,
+                &options,
+            ).await?;
+        } else {
+            message.channel_id.send_default_long_reply(
+                &cx.http,
+                tr!(language, "tag-list", count = tags.len(), tags = tags.join("\n")),
+                EmbedType::Info,
+                &options,
+            ).await?;
+        }
+
+        Ok(())
+    }
+
+    #[command(
+        name = "info",
+        description = "Sends who owns a tag, when it was created and how often it has been used",
+        category = Utility,
+    )]
+    async fn tag_info(cx: &Context, message: &Message, name: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+
+        let tag = find_tag(guild_id, &name, language)?;
+
+        message.channel_id.send_default_reply(
+            &cx.http,
+            tr!(
+                language,
+                "tag-info",
+                name = tag.name,
+                owner = format!("<@{}>", tag.owner_id),
+                created = format!("<t:{}:f>", tag.created),
+                uses = tag.uses,
+            ),
+            EmbedType::Info,
+            &options,
+        ).await?;
+
+        Ok(())
+    }
+
+    #[command(
+        name = "show",
+        description = "Sends a tag",
+        category = Utility,
+    )]
+    async fn tag_show(
+        cx: &Context,
+        message: &Message,
+        name: String,
+        arguments: Option<String>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let language = ReplyOptions::for_message(message).language;
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+
+        let tag = find_tag(guild_id, &name, language)?;
+        let args = arguments.as_deref().map(|a| a.split(' ').collect::<Vec<_>>()).unwrap_or_default();
+
+        tags::TagCommand(tag).invoke(cx, message, &args).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::testing::Harness;
+
+    use serde_json::Value;
 
+    #[cfg(any(feature = "general-commands", feature = "utility-commands"))]
+    use crate::config;
+
+    #[cfg(any(feature = "general-commands", feature = "utility-commands"))]
+    use crate::scripting::{Script, SCRIPTS};
+
+    #[cfg(feature = "utility-commands")]
+    use crate::{
+        analytics::ANALYTICS,
+        metrics::Outcome,
+        tags::{Tag, TAGS},
+    };
+
+    #[cfg(any(feature = "general-commands", feature = "utility-commands"))]
+    use hyper::Method;
+
+    struct NamedCommand(&'static str, &'static [&'static str]);
+
+    #[async_trait]
+    impl Command for NamedCommand {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn description(&self) -> &'static str {
+            "N/A"
+        }
+
+        fn category(&self) -> CommandCategory {
+            CommandCategory::General
+        }
+
+        fn aliases(&self) -> Vec<&str> {
+            self.1.to_vec()
+        }
+
+        async fn invoke(
+            &self,
+            _cx: &Context,
+            _message: &Message,
+            _args: &[&str],
+        ) -> Result<(), Box<dyn Error + Send + Sync>> {
             Ok(())
         }
     }
-}
\ No newline at end of file
+
+    fn build(commands: Vec<NamedCommand>) -> Result<CommandRegistry, RegistrationError> {
+        let mut builder = CommandRegistry::builder();
+
+        for command in commands {
+            builder.register(command);
+        }
+
+        builder.build()
+    }
+
+    #[test]
+    fn commands_are_found_by_name_or_alias() {
+        let registry = build(vec![NamedCommand("ping", &["latency"]), NamedCommand("help", &[])]).unwrap();
+
+        assert_eq!(registry.find("ping").map(|c| c.name()), Some("ping"));
+        assert_eq!(registry.find("latency").map(|c| c.name()), Some("ping"));
+        assert_eq!(registry.find("help").map(|c| c.name()), Some("help"));
+        assert!(registry.find("pong").is_none());
+    }
+
+    #[test]
+    fn clashing_names_are_rejected() {
+        let collision = |name: &str, first: &str, second: &str| RegistrationError::Collision {
+            name: name.to_owned(),
+            first: first.to_owned(),
+            second: second.to_owned(),
+        };
+
+        assert_eq!(
+            build(vec![NamedCommand("ping", &[]), NamedCommand("ping", &[])]).err(),
+            Some(collision("ping", "ping", "ping")),
+        );
+        assert_eq!(
+            build(vec![NamedCommand("ping", &["latency"]), NamedCommand("latency", &[])]).err(),
+            Some(collision("latency", "ping", "latency")),
+        );
+    }
+
+    #[test]
+    fn invalid_names_are_rejected() {
+        let invalid = |name: &str| Some(RegistrationError::InvalidName(name.to_owned()));
+
+        assert_eq!(build(vec![NamedCommand("weather", &["for cast"])]).err(), invalid("for cast"));
+        assert_eq!(build(vec![NamedCommand("", &[])]).err(), invalid(""));
+        assert_eq!(build(vec![NamedCommand("Ping", &[])]).err(), invalid("Ping"));
+        assert_eq!(build(vec![NamedCommand("ping", &["PONG"])]).err(), invalid("PONG"));
+    }
+
+    fn group() -> CommandGroup {
+        CommandGroup::new("weather", "N/A", CommandCategory::Utility)
+            .subcommand(NamedCommand("current", &["now"]))
+            .subcommand(NamedCommand("forecast", &[]))
+            .subcommand(
+                CommandGroup::new("alerts", "N/A", CommandCategory::Utility)
+                    .subcommand(NamedCommand("list", &[]))
+                    .subcommand(NamedCommand("subscribe", &[])),
+            )
+            .default_subcommand("current")
+    }
+
+    fn names<'a>(path: &[&'a dyn Command]) -> Vec<&'a str> {
+        path.iter().map(|c| c.name()).collect()
+    }
+
+    #[test]
+    fn subcommands_are_resolved_from_the_arguments() {
+        let group = group();
+
+        let (path, args) = resolve(&group, &["forecast", "London"], true);
+        assert_eq!((names(&path), args), (vec!["weather", "forecast"], &["London"][..]));
+
+        let (path, args) = resolve(&group, &["ALERTS", "list"], true);
+        assert_eq!((names(&path), args), (vec!["weather", "alerts", "list"], &[][..]));
+
+        let (path, args) = resolve(&group, &["NOW", "Oslo"], true);
+        assert_eq!((names(&path), args), (vec!["weather", "current"], &["Oslo"][..]));
+
+        // Falls back to the default subcommand, which keeps the arguments
+        let (path, args) = resolve(&group, &["London"], true);
+        assert_eq!((names(&path), args), (vec!["weather", "current"], &["London"][..]));
+
+        let (path, args) = resolve(&group, &["London"], false);
+        assert_eq!((names(&path), args), (vec!["weather"], &["London"][..]));
+
+        // Groups without a default subcommand are left for the dispatcher to report
+        let (path, args) = resolve(&group, &["alerts"], true);
+        assert_eq!((names(&path), args), (vec!["weather", "alerts"], &[][..]));
+    }
+
+    #[test]
+    fn usages_cover_every_subcommand() {
+        let group = group();
+
+        assert_eq!(usage_lines(&[&group]), vec![
+            "<weather current",
+            "<weather forecast",
+            "<weather alerts list",
+            "<weather alerts subscribe",
+        ]);
+        assert_eq!(description_id(&[&group, group.subcommands()[2].as_ref()]), "help-description-weather-alerts");
+    }
+
+    #[test]
+    fn invalid_groups_are_rejected() {
+        let registry = |group: CommandGroup| {
+            let mut builder = CommandRegistry::builder();
+
+            builder.register(group);
+            builder.build().err()
+        };
+
+        assert_eq!(registry(group()), None);
+
+        assert_eq!(
+            registry(group().subcommand(NamedCommand("later", &["forecast"]))),
+            Some(RegistrationError::Collision {
+                name: String::from("weather forecast"),
+                first: String::from("weather forecast"),
+                second: String::from("weather later"),
+            }),
+        );
+        assert_eq!(
+            registry(group().default_subcommand("tomorrow")),
+            Some(RegistrationError::UnknownDefault { group: String::from("weather"), default: "tomorrow" }),
+        );
+
+        let too_deep = CommandGroup::new("a", "N/A", CommandCategory::Utility).subcommand(
+            CommandGroup::new("b", "N/A", CommandCategory::Utility).subcommand(
+                CommandGroup::new("c", "N/A", CommandCategory::Utility).subcommand(NamedCommand("d", &[])),
+            ),
+        );
+
+        assert_eq!(registry(too_deep), Some(RegistrationError::TooDeep(String::from("a b c"))));
+    }
+
+    #[test]
+    fn groups_become_slash_subcommand_groups() {
+        /// Every option below `options` as its path and kind, e.g. `("alerts list", 1)`
+        fn flatten(options: &Value, parent: &str, flattened: &mut Vec<(String, u64)>) {
+            for option in options.as_array().into_iter().flatten() {
+                let name = format!("{}{}", parent, option["name"].as_str().unwrap());
+
+                flattened.push((name.clone(), option["type"].as_u64().unwrap()));
+                flatten(&option["options"], &format!("{} ", name), flattened);
+            }
+        }
+
+        let mut builder = CommandRegistry::builder();
+
+        builder.register(group());
+
+        let mut commands = CreateApplicationCommands::default();
+
+        builder.build().unwrap().slash_commands(&mut commands);
+
+        assert_eq!(commands.0.len(), 1);
+        assert_eq!(commands.0[0]["name"], "weather");
+
+        let mut options = Vec::new();
+
+        flatten(&commands.0[0]["options"], "", &mut options);
+
+        let group = ApplicationCommandOptionType::SubCommandGroup as u64;
+        let subcommand = ApplicationCommandOptionType::SubCommand as u64;
+
+        assert_eq!(options, vec![
+            (String::from("current"), subcommand),
+            (String::from("forecast"), subcommand),
+            (String::from("alerts"), group),
+            (String::from("alerts list"), subcommand),
+            (String::from("alerts subscribe"), subcommand),
+        ]);
+    }
+
+    #[test]
+    fn builtin_commands_do_not_clash() {
+        lazy_static::initialize(&COMMANDS);
+    }
+
+    #[cfg(feature = "general-commands")]
+    #[test]
+    fn flags_are_described_as_flags() {
+        let ping = COMMANDS.find("ping").unwrap();
+
+        assert_eq!(ping.usages(), vec![vec!["edit (optional)"]]);
+        assert_eq!(ping.options(), vec![CommandOption {
+            name: "edit",
+            description: "flag",
+            required: false,
+            flag: true,
+        }]);
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[test]
+    fn slash_options_become_the_typed_words() {
+        let options = serde_json::from_value::<Vec<ApplicationCommandInteractionDataOption>>(serde_json::json!([{
+            "name": "color",
+            "type": 1,
+            "options": [
+                { "name": "color", "type": 3, "value": "#FF0000" },
+                { "name": "embed_type", "type": 3, "value": "success" },
+            ],
+        }])).unwrap();
+
+        assert_eq!(slash_words(COMMANDS.find("theme").unwrap(), &options), vec!["theme", "color", "success", "#FF0000"]);
+    }
+
+    #[cfg(feature = "general-commands")]
+    #[tokio::test]
+    async fn slash_commands_run_like_prefixed_ones() {
+        let harness = Harness::new().await;
+
+        harness.slash("ping", serde_json::json!([{ "name": "edit", "type": 5, "value": true }])).await;
+
+        let requests = harness.requests();
+
+        // The answer echoes the command, which is then run as if the author had typed it
+        assert!(requests[0].path.ends_with("/callback"));
+        assert_eq!(requests[0].body["data"]["content"], "<ping edit");
+
+        let edits = requests.iter()
+            .filter(|r| r.method == Method::PATCH && r.path.starts_with(&format!("/channels/{}/messages/", harness.channel_id)))
+            .count();
+
+        // Measuring the edit latency takes an edit of its own
+        assert_eq!(edits, 2);
+    }
+
+    #[cfg(all(feature = "developer-commands", feature = "general-commands", feature = "utility-commands"))]
+    #[tokio::test]
+    async fn help_lists_every_category() {
+        let harness = Harness::new().await;
+
+        harness.send("<help").await;
+
+        let embeds = harness.embeds();
+
+        assert_eq!(embeds.len(), 1);
+
+        let description = embeds[0]["description"].as_str().unwrap();
+
+        for category in ["Developer", "General", "Utility"] {
+            assert!(description.contains(&format!("**{} Commands**", category)), "{} is missing", category);
+        }
+
+        assert!(description.contains("help"));
+        assert_eq!(embeds[0]["author"]["name"], "Test Bot Help");
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[tokio::test]
+    async fn weather_requires_a_location() {
+        let harness = Harness::new().await;
+
+        harness.send("<weather").await;
+
+        let requests = harness.requests();
+        let embeds = harness.embeds();
+
+        // Nothing is looked up without a location
+        assert!(requests.iter().all(|r| r.method == Method::POST));
+        assert!(requests.iter().all(|r| r.path == format!("/channels/{}/messages", harness.channel_id)));
+        assert_eq!(embeds.len(), 1);
+        assert_eq!(embeds[0]["description"], "You have not provided the location!");
+        assert_eq!(embeds[0]["color"], config::FAILURE_COLOR.0);
+
+        let outcomes = ANALYTICS.read().records.iter()
+            .filter(|r| r.guild == Some(harness.guild_id))
+            .map(|r| (r.command.clone(), r.outcome))
+            .collect::<Vec<_>>();
+
+        assert_eq!(outcomes, [(String::from("weather current"), Outcome::Failure)]);
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[tokio::test]
+    async fn groups_without_a_subcommand_list_their_usages() {
+        let harness = Harness::new().await;
+
+        harness.send("<weather alerts").await;
+
+        let embeds = harness.embeds();
+
+        assert_eq!(embeds.len(), 1);
+        assert_eq!(
+            embeds[0]["description"],
+            "Specify one of the subcommands:\n\
+            <weather alerts list\n\
+            <weather alerts subscribe <location>\n\
+            <weather alerts unsubscribe <location>",
+        );
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[tokio::test]
+    async fn subcommands_check_their_permissions() {
+        let harness = Harness::new().await;
+
+        // The fake server knows no members, so the author has no permissions at all
+        harness.send("<weather alerts subscribe Oslo").await;
+
+        let embeds = harness.embeds();
+
+        assert_eq!(embeds.len(), 1);
+        assert_eq!(embeds[0]["description"], "You need the Manage Channels permissions to execute the command!");
+        assert_eq!(embeds[0]["color"], config::FAILURE_COLOR.0);
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[tokio::test]
+    async fn groups_default_to_their_unrestricted_subcommand() {
+        let harness = Harness::new().await;
+
+        harness.send("<theme").await;
+        harness.send("<style reset").await;
+        harness.send("<theme colour info #1abc9c").await;
+
+        let embeds = harness.embeds();
+        let missing_permissions = format!(
+            "You need the {} permissions to execute the command!",
+            Permissions::MANAGE_GUILD.get_permission_names().join(", "),
+        );
+
+        assert_eq!(embeds.len(), 3);
+        assert_eq!(embeds[0]["author"]["name"], "Server theme");
+        assert_eq!(embeds[1]["description"], missing_permissions);
+        assert_eq!(embeds[2]["description"], missing_permissions);
+    }
+
+    #[cfg(feature = "general-commands")]
+    #[tokio::test]
+    async fn scripted_commands_are_dispatched_and_listed() {
+        let harness = Harness::new().await;
+
+        SCRIPTS.update(|scripts| {
+            scripts.entry(harness.guild_id).or_default().push(Script {
+                name: String::from("greet"),
+                description: String::from("Greets whoever is named"),
+                source: String::from("reply(\"success\", `Hi, ${args[0]}!`);"),
+                author_id: harness.user_id,
+            });
+        }).await.unwrap();
+
+        harness.send("<greet World").await;
+        harness.send("<help").await;
+
+        let embeds = harness.embeds();
+
+        assert_eq!(embeds.len(), 2);
+        assert_eq!(embeds[0]["description"], "Hi, World!");
+        assert_eq!(embeds[0]["color"], config::SUCCESS_COLOR.0);
+        assert!(embeds[1]["description"].as_str().unwrap().contains("**Custom Commands**\ngreet"));
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[tokio::test]
+    async fn script_sources_are_shown_to_server_managers_only() {
+        let harness = Harness::new().await;
+
+        SCRIPTS.update(|scripts| {
+            scripts.entry(harness.guild_id).or_default().push(Script {
+                name: String::from("fetch"),
+                description: String::new(),
+                source: String::from("reply(http_get(\"https://example.com\"));"),
+                author_id: harness.user_id,
+            });
+        }).await.unwrap();
+
+        harness.send("<script show fetch").await;
+
+        let embeds = harness.embeds();
+
+        assert_eq!(embeds.len(), 1);
+        assert_eq!(
+            embeds[0]["description"],
+            format!(
+                "You need the {} permissions to execute the command!",
+                Permissions::MANAGE_GUILD.get_permission_names().join(", "),
+            ),
+        );
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[tokio::test]
+    async fn tags_are_sent_and_counted() {
+        let harness = Harness::new().await;
+
+        TAGS.update(|tags| {
+            tags.entry(harness.guild_id).or_default().push(Tag {
+                name: String::from("rules"),
+                content: String::from("{user}, read the rules in {channel}, rule {args.1} above all!"),
+                owner_id: harness.user_id,
+                uses: 0,
+                created: 0,
+            });
+        }).await.unwrap();
+
+        harness.send("<rules 1").await;
+        harness.send("<tag rules 2").await;
+
+        let embeds = harness.embeds();
+        let uses = TAGS.read()[&harness.guild_id].iter().find(|t| t.name == "rules").unwrap().uses;
+
+        assert_eq!(embeds.len(), 2);
+        assert_eq!(
+            embeds[0]["description"],
+            format!("<@{}>, read the rules in <#{}>, rule 1 above all!", harness.user_id, harness.channel_id),
+        );
+        assert_eq!(
+            embeds[1]["description"],
+            format!("<@{}>, read the rules in <#{}>, rule 2 above all!", harness.user_id, harness.channel_id),
+        );
+        assert_eq!(uses, 2);
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[tokio::test]
+    async fn tags_are_managed_by_their_owners() {
+        let harness = Harness::new().await;
+
+        TAGS.update(|tags| {
+            let tag = |name: &str, owner_id| Tag {
+                name: name.to_owned(),
+                content: String::from("Be nice!"),
+                owner_id,
+                uses: 0,
+                created: 0,
+            };
+
+            tags.entry(harness.guild_id).or_default().extend([tag("rules", harness.user_id), tag("faq", 1)]);
+        }).await.unwrap();
+
+        // The author has no permissions, which only matters for tags owned by someone else
+        harness.send("<tag edit rules Be kind!").await;
+        harness.send("<tag delete faq").await;
+
+        let embeds = harness.embeds();
+        let contents = TAGS.read()[&harness.guild_id].iter().map(|t| t.content.clone()).collect::<Vec<_>>();
+
+        assert_eq!(embeds.len(), 2);
+        assert_eq!(embeds[0]["description"], "The tag `rules` has been edited!");
+        assert_eq!(embeds[1]["description"], "Only the owner of `faq` and server managers can change it!");
+        assert_eq!(contents, ["Be kind!", "Be nice!"]);
+    }
+
+    #[tokio::test]
+    async fn unknown_commands_are_ignored() {
+        let harness = Harness::new().await;
+
+        harness.send("<definitely-not-a-command").await;
+        harness.send("help").await;
+
+        assert!(harness.requests().is_empty());
+    }
+}
@@ -6,19 +6,95 @@ use serenity::utils::Color;
 
 use std::{
     env::var,
+    path::PathBuf,
     sync::Mutex,
+    time::Duration,
 };
 
 pub const PREFIX: &str = "<";
 
+/// Longer replies are attached as a text file instead of being split into more embeds
+pub const LONG_REPLY_MAX_PAGES: usize = 3;
+
+pub const WEATHER_ALERTS_POLL_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+pub const SHARD_WATCH_INTERVAL: Duration = Duration::from_secs(5);
+/// A shard is not considered ready if its last heartbeat was acknowledged longer ago than this
+pub const HEARTBEAT_ACK_TIMEOUT: Duration = Duration::from_secs(2 * 60);
+
+/// How often the uptime history notes that the bot is still running, which bounds how much of a crash goes unnoticed
+pub const UPTIME_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(60);
+/// Uptime events older than this are dropped from the history
+pub const UPTIME_HISTORY_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Command usage records older than this are dropped from the analytics
+pub const ANALYTICS_RETENTION: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+/// User hashes are stripped from command usage records older than this
+pub const ANALYTICS_USER_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// Command usage is recorded in memory and written this often, which bounds how much of it a crash loses
+pub const ANALYTICS_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+pub const SCRIPTS_PER_GUILD: usize = 25;
+/// How long a scripted command may run, including the HTTP requests it sends
+pub const SCRIPT_TIMEOUT: Duration = Duration::from_secs(3);
+/// Bounds the work a scripted command does however fast the host is
+pub const SCRIPT_MAX_OPERATIONS: u64 = 1_000_000;
+/// Scripts have no memory limit as such, so their memory is bounded by how many variables they may hold
+/// and how large a single value may grow, counting the strings and items nested in arrays and maps
+pub const SCRIPT_MAX_VARIABLES: usize = 64;
+/// In bytes, which also bounds the HTTP responses a script can receive
+pub const SCRIPT_MAX_STRING_SIZE: usize = 64 * 1024;
+pub const SCRIPT_MAX_COLLECTION_SIZE: usize = 1024;
+pub const SCRIPT_MAX_REPLIES: usize = 5;
+/// Every run takes up a blocking thread, so one guild cannot starve the others
+pub const SCRIPT_MAX_CONCURRENT_PER_GUILD: usize = 2;
+
+/// Each location costs two API requests, so the `time` command lists no more than this many at once
+pub const TIME_MAX_ZONES: usize = 10;
+
+pub const TAGS_PER_GUILD: usize = 100;
+/// In characters, which is as long as a message can be
+pub const TAG_MAX_LENGTH: usize = 2000;
+/// Tag uses are counted in memory and written this often, which bounds how many of them a crash loses
+pub const TAG_USES_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
 lazy_static! {
     pub static ref DISCORD_TOKEN: String = var("DISCORD_TOKEN").unwrap();
     pub static ref WEATHER_API_KEY: String = var("WEATHER_API_KEY").unwrap();
 
+    pub static ref DATA_DIR: PathBuf = var("DATA_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("data"));
+
+    /// Filter directives such as `info` or `discord_test_rs_bot=debug,serenity=warn`
+    pub static ref LOG_LEVEL: String = var("LOG_LEVEL").unwrap_or_else(|_| String::from("info"));
+    /// Either `pretty` or `json`
+    pub static ref LOG_FORMAT: String = var("LOG_FORMAT").unwrap_or_else(|_| String::from("pretty"));
+    /// Logs are additionally written to daily rotated files in this directory if it is set
+    pub static ref LOG_DIR: Option<PathBuf> = var("LOG_DIR").ok().map(PathBuf::from);
+
+    /// Where the health checks and, if the bot is built with the `metrics` feature, the metrics are served.
+    /// An empty address or `off` disables the server.
+    pub static ref HTTP_ADDR: Option<String> = match var("HTTP_ADDR") {
+        Ok(addr) if addr.trim().is_empty() || addr.trim().eq_ignore_ascii_case("off") => None,
+        Ok(addr) => Some(addr),
+        Err(_) => Some(String::from("127.0.0.1:8787")),
+    };
+
+    /// Hosts scripted commands may send HTTP requests to, separated by commas, e.g. `api.github.com,xkcd.com`
+    pub static ref SCRIPT_HTTP_ALLOWLIST: Vec<String> = var("SCRIPT_HTTP_ALLOWLIST")
+        .map(|hosts| hosts.split(',').map(|h| h.trim().to_lowercase()).filter(|h| !h.is_empty()).collect())
+        .unwrap_or_default();
+
+    /// Slash commands are registered in this guild only if it is set, which unlike registering them globally
+    /// takes effect at once and so suits development builds
+    pub static ref SLASH_COMMANDS_GUILD: Option<u64> = var("SLASH_COMMANDS_GUILD").ok().and_then(|id| id.parse().ok());
+
     pub static ref DEVELOPERS: Mutex<Vec<u64>> = Mutex::new(Vec::new());
 }
 
 pub const SUCCESS_COLOR: Color = Color::from_rgb(0x70, 0x55, 0x44);
 pub const FAILURE_COLOR: Color = Color::from_rgb(0xef, 0x43, 0x3f);
 pub const CONFIRMATION_COLOR: Color = Color::from_rgb(0x78, 0xb4, 0x54);
-pub const WARNING_COLOR: Color = Color::from_rgb(0xff, 0xf2, 0x36);
\ No newline at end of file
+pub const WARNING_COLOR: Color = Color::from_rgb(0xff, 0xf2, 0x36);
+pub const INFO_COLOR: Color = Color::from_rgb(0x3b, 0x88, 0xc3);
\ No newline at end of file
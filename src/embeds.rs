@@ -0,0 +1,1219 @@
+//! Command embeds built by pure functions into a plain, serializable model,
+//! so that they can be snapshot-tested before they are turned into serenity's builders
+
+use crate::{
+    i18n::{tr, Language},
+    theme::Theme,
+    utils::discord::EmbedType,
+};
+
+use serde::{Deserialize, Serialize};
+
+use serenity::builder::CreateEmbed;
+
+#[cfg(any(feature = "developer-commands", feature = "general-commands"))]
+use crate::utils::time::DurationFormatter;
+
+#[cfg(any(feature = "developer-commands", feature = "general-commands"))]
+use itertools::Itertools;
+
+#[cfg(any(feature = "developer-commands", feature = "general-commands"))]
+use std::time::Duration;
+
+#[cfg(any(feature = "general-commands", feature = "utility-commands"))]
+use crate::i18n::translate;
+
+#[cfg(any(feature = "general-commands", feature = "utility-commands"))]
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+#[cfg(any(feature = "general-commands", feature = "utility-commands"))]
+use chrono_tz::Tz;
+
+#[cfg(feature = "developer-commands")]
+use crate::analytics::Summary;
+
+#[cfg(feature = "general-commands")]
+use crate::{
+    availability::UptimeHistory,
+    cmds::{description_id, qualified_name, usage_lines, Command, CommandCategory},
+    config,
+    utils::{
+        process,
+        time::{as_text, DurationStyle, TimeUnit},
+    },
+};
+
+#[cfg(feature = "utility-commands")]
+use crate::{
+    utils::{
+        misc::{
+            capitalize,
+            format_temperature,
+            get_aqi_category,
+            get_beaufort_scale,
+            get_dew_point,
+            get_feels_like,
+            get_uv_risk,
+            get_weather_icon_url,
+            get_wind_direction,
+        },
+        time::format_offset_difference,
+    },
+    weather::{AdditionalConditions, CurrentWeather},
+};
+
+#[cfg(feature = "general-commands")]
+use serenity::model::permissions::Permissions;
+
+#[cfg(feature = "utility-commands")]
+use chrono::{FixedOffset, Offset, TimeZone};
+
+#[cfg(feature = "utility-commands")]
+use chrono_tz::{OffsetComponents, OffsetName};
+
+#[cfg(feature = "utility-commands")]
+use num_format::{Locale, ToFormattedString};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmbedModel {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<EmbedAuthorModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<EmbedFieldModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer: Option<String>,
+    /// An RFC 3339 timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmbedAuthorModel {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct EmbedFieldModel {
+    pub name: String,
+    pub value: String,
+    pub inline: bool,
+}
+
+impl EmbedFieldModel {
+    pub fn new(name: impl ToString, value: impl ToString, inline: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.to_string(),
+            inline,
+        }
+    }
+}
+
+impl EmbedModel {
+    /// Fills in the embed, decorating the author with the theme's icon for the embed type.
+    /// The theme's color, footer and timestamp are expected to have been applied already, e.g. by `themed_embed`.
+    pub fn apply<'a>(&self, embed: &'a mut CreateEmbed, theme: &Theme, embed_type: EmbedType) -> &'a mut CreateEmbed {
+        if let Some(ref author) = self.author {
+            theme.author(embed, embed_type, &author.name, |a| {
+                if let Some(ref icon_url) = author.icon_url {
+                    a.icon_url(icon_url);
+                }
+
+                if let Some(ref url) = author.url {
+                    a.url(url);
+                }
+
+                a
+            });
+        }
+
+        if let Some(ref description) = self.description {
+            embed.description(description);
+        }
+
+        embed.fields(self.fields.iter().map(|field| (&field.name, &field.value, field.inline)));
+
+        if let Some(ref thumbnail) = self.thumbnail {
+            embed.thumbnail(thumbnail);
+        }
+
+        if let Some(ref footer) = self.footer {
+            embed.footer(|f| f.text(footer));
+        }
+
+        if let Some(ref timestamp) = self.timestamp {
+            embed.timestamp(timestamp.clone());
+        }
+
+        embed
+    }
+}
+
+/// The list of every command grouped by category, which is sent as a long reply
+#[cfg(feature = "general-commands")]
+pub fn help_overview(language: Language, commands: &[&dyn Command], bot_name: &str, bot_face: &str) -> EmbedModel {
+    let mut cmds_grouped = Vec::new();
+
+    for (key, group) in &commands.iter().group_by(|c| c.category()) {
+        cmds_grouped.push((key, group.collect::<Vec<_>>()));
+    }
+
+    cmds_grouped.sort_by_key(|(c, _)| c.to_string());
+
+    let text = cmds_grouped.into_iter()
+        .map(|(c, cmds)| {
+            let mut cmds = cmds
+                .into_iter()
+                .map(|cmd| cmd.name())
+                .collect::<Vec<&str>>();
+
+            cmds.sort();
+
+            let category = tr!(language, "help-category-commands", category = c.translate(language));
+
+            format!("**{}**\n{}", category, cmds.join(", "))
+        })
+        .join("\n\n");
+
+    EmbedModel {
+        author: Some(EmbedAuthorModel {
+            name: tr!(language, "help-title", bot = bot_name),
+            icon_url: Some(bot_face.to_owned()),
+            url: None,
+        }),
+        description: Some(text),
+        ..EmbedModel::default()
+    }
+}
+
+/// Help for the command at the end of `path`, which is longer than one command for subcommands
+#[cfg(feature = "general-commands")]
+pub fn help_command(language: Language, path: &[&dyn Command], bot_face: &str) -> EmbedModel {
+    let cmd = path[path.len() - 1];
+    let mut title = format!("{}{}", config::PREFIX, qualified_name(path));
+
+    if path.iter().any(|c| c.is_developer()) {
+        title = tr!(language, "help-developer-only", command = title);
+    }
+
+    let mut fields = vec![EmbedFieldModel::new(tr!(language, "help-category"), path[0].category().translate(language), false)];
+
+    if !cmd.aliases().is_empty() {
+        let mut aliases = cmd.aliases();
+
+        aliases.sort();
+
+        fields.push(EmbedFieldModel::new(tr!(language, "help-aliases"), aliases.join(", "), false));
+    }
+
+    let permissions = path.iter().fold(Permissions::empty(), |permissions, c| permissions | c.permissions());
+
+    if !permissions.is_empty() {
+        fields.push(EmbedFieldModel::new(
+            tr!(language, "help-permissions"),
+            permissions.get_permission_names().join(", "),
+            false,
+        ));
+    }
+
+    if !cmd.subcommands().is_empty() {
+        let subcommands = cmd.subcommands().iter()
+            .map(|subcommand| {
+                let mut subcommand_path = path.to_vec();
+
+                subcommand_path.push(subcommand.as_ref());
+
+                let mut line = format!(
+                    "`{}` \u{2013} {}",
+                    subcommand.name(),
+                    translate(language, &description_id(&subcommand_path), None),
+                );
+
+                if cmd.default_subcommand() == Some(subcommand.name()) {
+                    line = format!("{} {}", line, tr!(language, "help-default-subcommand"));
+                }
+
+                line
+            })
+            .join("\n");
+
+        fields.push(EmbedFieldModel::new(tr!(language, "help-subcommands"), subcommands, false));
+    }
+
+    if !cmd.usages().is_empty() || !cmd.subcommands().is_empty() {
+        fields.push(EmbedFieldModel::new(tr!(language, "help-usages"), usage_lines(path).join("\n"), false));
+    }
+
+    // Scripted commands are described by whoever has written them rather than by the catalog
+    let description = match path[0].category() {
+        CommandCategory::Custom if cmd.description().is_empty() => tr!(language, "help-no-description"),
+        CommandCategory::Custom => cmd.description().to_owned(),
+        _ => translate(language, &description_id(path), None),
+    };
+
+    EmbedModel {
+        author: Some(EmbedAuthorModel {
+            name: title,
+            icon_url: Some(bot_face.to_owned()),
+            url: None,
+        }),
+        description: Some(description),
+        fields,
+        ..EmbedModel::default()
+    }
+}
+
+/// `timezone` is the user's own time zone, in which the last reboot is additionally shown
+#[cfg(feature = "general-commands")]
+pub fn uptime(language: Language, start_time: DateTime<Utc>, now: DateTime<Utc>, timezone: Option<Tz>) -> EmbedModel {
+    let uptime = now.signed_duration_since(start_time).num_milliseconds().max(0);
+
+    let mut description = DurationFormatter::new()
+        .locale(language.duration_locale())
+        .format(uptime);
+
+    if let Some(tz) = timezone {
+        let local_start_time = start_time.with_timezone(&tz).format("%b %d, %Y, %r (%Z)");
+
+        description.push_str("\n\n");
+        description.push_str(&tr!(language, "uptime-last-reboot-local", time = local_start_time.to_string()));
+    }
+
+    EmbedModel {
+        author: Some(EmbedAuthorModel {
+            name: tr!(language, "uptime-title"),
+            ..EmbedAuthorModel::default()
+        }),
+        description: Some(description),
+        footer: Some(tr!(language, "uptime-last-reboot")),
+        timestamp: Some(start_time.to_rfc3339()),
+        ..EmbedModel::default()
+    }
+}
+
+#[cfg(feature = "utility-commands")]
+pub fn weather(
+    language: Language,
+    weather: &CurrentWeather,
+    additional: &AdditionalConditions,
+    now: DateTime<Utc>,
+    bot_face: &str,
+) -> EmbedModel {
+    let mut fields = Vec::new();
+
+    if let Some(ref condition) = weather.condition {
+        fields.push(EmbedFieldModel::new(tr!(language, "weather-condition"), capitalize(condition), true));
+    }
+
+    {
+        let temp_c = weather.temperature;
+        let feels_like = get_feels_like(temp_c, weather.humidity, weather.wind_speed);
+        let dew_point = get_dew_point(temp_c, weather.humidity);
+
+        let temp_output = format!(
+            "{}\n{}\n{}",
+            format_temperature(temp_c),
+            tr!(language, "weather-feels-like", temperature = format_temperature(feels_like)),
+            tr!(language, "weather-dew-point", temperature = format_temperature(dew_point)),
+        );
+
+        fields.push(EmbedFieldModel::new(tr!(language, "weather-temperature"), temp_output, true));
+    }
+
+    {
+        let mut wind_output = tr!(language, "weather-wind-speed", speed = weather.wind_speed.round() as usize);
+
+        if let Some(direction) = get_wind_direction(weather.wind_direction) {
+            let id = format!("compass-{}", direction.abbreviation.to_lowercase());
+            let name = translate(language, &id, None);
+
+            wind_output.push_str(format!(" {} {} ({})", direction.arrow, name, direction.abbreviation).as_str());
+        }
+
+        let (force, _) = get_beaufort_scale(weather.wind_speed);
+        let force_name = translate(language, &format!("beaufort-{}", force), None);
+
+        wind_output.push('\n');
+        wind_output.push_str(&tr!(language, "weather-beaufort", name = force_name, force = force));
+
+        if let Some(gust) = additional.wind_gust {
+            wind_output.push('\n');
+            wind_output.push_str(&tr!(language, "weather-gusts", speed = gust.round() as usize));
+        }
+
+        fields.push(EmbedFieldModel::new(tr!(language, "weather-wind"), wind_output, true));
+    }
+
+    fields.push(EmbedFieldModel::new(tr!(language, "weather-humidity"), format!("{}%", weather.humidity as usize), true));
+
+    if let Some(cloudiness) = weather.cloudiness {
+        fields.push(EmbedFieldModel::new(tr!(language, "weather-cloudiness"), format!("{}%", cloudiness), true));
+    }
+
+    {
+        let pressure = weather.pressure as usize;
+        let pressure = format!("{} mbar", pressure.to_formatted_string(&Locale::en));
+
+        fields.push(EmbedFieldModel::new(tr!(language, "weather-pressure"), pressure, true));
+    }
+
+    if let Some(ref air_quality) = additional.air_quality {
+        let mut aqi_output = air_quality.aqi.to_string();
+
+        if let Some(category) = get_aqi_category(air_quality.aqi) {
+            aqi_output.push_str(format!(" ({})", translate(language, &category, None)).as_str());
+        }
+
+        let air_quality_output = format!(
+            "{}\nPM2.5: {:.1} \u{b5}g/m\u{b3}\nPM10: {:.1} \u{b5}g/m\u{b3}\nO3: {:.1} \u{b5}g/m\u{b3}",
+            aqi_output,
+            air_quality.pm2_5,
+            air_quality.pm10,
+            air_quality.o3,
+        );
+
+        fields.push(EmbedFieldModel::new(tr!(language, "weather-air-quality"), air_quality_output, true));
+    }
+
+    if let Some(uv_index) = additional.uv_index {
+        let uv_output = format!("{:.1} ({})", uv_index, translate(language, get_uv_risk(uv_index), None));
+
+        fields.push(EmbedFieldModel::new(tr!(language, "weather-uv-index"), uv_output, true));
+    }
+
+    if let Some(timezone_offset_secs) = weather.timezone {
+        let offset = FixedOffset::east(timezone_offset_secs);
+        let local_time = |secs: i64| offset.from_utc_datetime(&NaiveDateTime::from_timestamp(secs, 0));
+
+        if let Some(sunrise) = weather.sunrise {
+            fields.push(EmbedFieldModel::new(tr!(language, "weather-sunrise"), local_time(sunrise).format("%I:%M %p"), true));
+        }
+
+        if let Some(sunset) = weather.sunset {
+            fields.push(EmbedFieldModel::new(tr!(language, "weather-sunset"), local_time(sunset).format("%I:%M %p"), true));
+        }
+
+        let local_date_time = offset.from_utc_datetime(&now.naive_utc());
+        let formatted = local_date_time.format("%b %d, %Y, %r (UTC%:z)");
+
+        fields.push(EmbedFieldModel::new(tr!(language, "weather-current-date"), formatted, false));
+    }
+
+    EmbedModel {
+        author: Some(EmbedAuthorModel {
+            name: weather.location.clone(),
+            icon_url: Some(bot_face.to_owned()),
+            url: Some(format!("https://openweathermap.org/city/{}", weather.city_id)),
+        }),
+        fields,
+        thumbnail: weather.icon.as_deref().map(get_weather_icon_url),
+        footer: Some(tr!(language, "weather-provided-by")),
+        ..EmbedModel::default()
+    }
+}
+
+/// `gateway` is missing until the shard's first heartbeat, and `edit` unless editing has been measured too
+#[cfg(feature = "general-commands")]
+pub fn ping(
+    language: Language,
+    rest: Duration,
+    gateway: Option<Duration>,
+    edit: Option<Duration>,
+    shard_id: u64,
+    stage: Option<&str>,
+) -> EmbedModel {
+    let milliseconds = |latency: Duration| tr!(language, "ping-milliseconds", ping = latency.as_millis() as u64);
+
+    let mut fields = vec![
+        EmbedFieldModel::new(tr!(language, "ping-rest"), milliseconds(rest), true),
+        EmbedFieldModel::new(
+            tr!(language, "ping-gateway"),
+            gateway.map(milliseconds).unwrap_or_else(|| tr!(language, "ping-no-heartbeat")),
+            true,
+        ),
+    ];
+
+    if let Some(edit) = edit {
+        fields.push(EmbedFieldModel::new(tr!(language, "ping-edit"), milliseconds(edit), true));
+    }
+
+    EmbedModel {
+        author: Some(EmbedAuthorModel {
+            name: tr!(language, "ping-title"),
+            ..EmbedAuthorModel::default()
+        }),
+        fields,
+        footer: Some(tr!(language, "ping-shard", shard = shard_id, stage = stage.unwrap_or("?"))),
+        ..EmbedModel::default()
+    }
+}
+
+/// What the `stats` command reports, the process statistics are missing where they cannot be read
+#[cfg(feature = "general-commands")]
+pub struct BotStats {
+    pub uptime: Duration,
+    /// In bytes
+    pub memory: Option<u64>,
+    pub cpu_time: Option<Duration>,
+    pub tasks: usize,
+    pub workers: usize,
+    pub guilds: usize,
+    pub channels: usize,
+    pub users: usize,
+    pub commands_run: usize,
+    pub reconnects: usize,
+    pub version: String,
+    pub rustc_version: String,
+}
+
+#[cfg(feature = "general-commands")]
+pub fn stats(language: Language, stats: &BotStats, bot_name: &str, bot_face: &str) -> EmbedModel {
+    let formatter = DurationFormatter::new().locale(language.duration_locale());
+
+    let memory = stats.memory
+        .map(process::format_bytes)
+        .unwrap_or_else(|| tr!(language, "stats-unavailable"));
+    let cpu_time = stats.cpu_time
+        .map(|time| {
+            formatter
+                .units(TimeUnit::Millisecond..=TimeUnit::Hour)
+                .style(DurationStyle::Short)
+                .max_units(2)
+                .format(time.as_millis() as i64)
+        })
+        .unwrap_or_else(|| tr!(language, "stats-unavailable"));
+
+    let tasks = tr!(language, "stats-tasks-value", tasks = stats.tasks, workers = stats.workers);
+    let cache = tr!(
+        language,
+        "stats-cache-value",
+        guilds = stats.guilds,
+        channels = stats.channels,
+        users = stats.users,
+    );
+
+    EmbedModel {
+        author: Some(EmbedAuthorModel {
+            name: tr!(language, "stats-title", bot = bot_name),
+            icon_url: Some(bot_face.to_owned()),
+            url: None,
+        }),
+        fields: vec![
+            EmbedFieldModel::new(tr!(language, "stats-uptime"), formatter.max_units(3).format(stats.uptime.as_millis() as i64), true),
+            EmbedFieldModel::new(tr!(language, "stats-memory"), memory, true),
+            EmbedFieldModel::new(tr!(language, "stats-cpu-time"), cpu_time, true),
+            EmbedFieldModel::new(tr!(language, "stats-tasks"), tasks, true),
+            EmbedFieldModel::new(tr!(language, "stats-cache"), cache, true),
+            EmbedFieldModel::new(tr!(language, "stats-commands-run"), stats.commands_run, true),
+            EmbedFieldModel::new(tr!(language, "stats-reconnects"), stats.reconnects, true),
+            EmbedFieldModel::new(tr!(language, "stats-version"), &stats.version, true),
+            EmbedFieldModel::new(tr!(language, "stats-rustc"), &stats.rustc_version, true),
+        ],
+        ..EmbedModel::default()
+    }
+}
+
+/// The availability over the last day, week and month, and the latest outages. `now` is Unix time in seconds.
+#[cfg(feature = "general-commands")]
+pub fn uptime_history(language: Language, history: &UptimeHistory, now: i64) -> EmbedModel {
+    const PERIODS: [(&str, i64); 3] = [
+        ("uptime-history-day", 24 * 60 * 60),
+        ("uptime-history-week", 7 * 24 * 60 * 60),
+        ("uptime-history-month", 30 * 24 * 60 * 60),
+    ];
+    const MAX_OUTAGES: usize = 10;
+
+    let mut fields = PERIODS.iter()
+        .map(|&(id, period)| {
+            let availability = history.availability(period, now)
+                .map(|percentage| tr!(language, "uptime-history-availability", percentage = format!("{:.2}", percentage)))
+                .unwrap_or_else(|| tr!(language, "uptime-history-no-data"));
+
+            EmbedFieldModel::new(translate(language, id, None), availability, true)
+        })
+        .collect::<Vec<_>>();
+
+    let outages = history.outages().iter()
+        .rev()
+        .take(MAX_OUTAGES)
+        .map(|outage| {
+            let start = outage.start.to_string();
+            let duration = as_text(outage.duration(now) * 1000);
+
+            match outage.end {
+                Some(_) => tr!(language, "uptime-history-outage", start = start, duration = duration),
+                None => tr!(language, "uptime-history-ongoing-outage", start = start, duration = duration),
+            }
+        })
+        .join("\n");
+
+    let outages = if outages.is_empty() {
+        tr!(language, "uptime-history-no-outages")
+    } else {
+        outages
+    };
+
+    fields.push(EmbedFieldModel::new(tr!(language, "uptime-history-outages"), outages, false));
+
+    let tracked_since = history.tracked_since()
+        .map(|since| DateTime::<Utc>::from_utc(NaiveDateTime::from_timestamp(since, 0), Utc));
+
+    EmbedModel {
+        author: Some(EmbedAuthorModel {
+            name: tr!(language, "uptime-history-title"),
+            ..EmbedAuthorModel::default()
+        }),
+        fields,
+        footer: tracked_since.map(|_| tr!(language, "uptime-history-tracked-since")),
+        timestamp: tracked_since.map(|since| since.to_rfc3339()),
+        ..EmbedModel::default()
+    }
+}
+
+/// The most used commands, their error rates and latencies, and the daily active users over `period`
+#[cfg(feature = "developer-commands")]
+pub fn analytics(language: Language, summary: &Summary, period: chrono::Duration) -> EmbedModel {
+    const MAX_COMMANDS: usize = 10;
+
+    let percentage = |part: usize, total: usize| format!("{:.1}", 100.0 * part as f64 / total as f64);
+
+    let commands = summary.commands.iter()
+        .take(MAX_COMMANDS)
+        .enumerate()
+        .map(|(i, command)| tr!(
+            language,
+            "analytics-command",
+            rank = i + 1,
+            command = command.name.as_str(),
+            uses = command.uses,
+            errors = percentage(command.failures, command.uses),
+            p50 = command.p50.as_millis() as u64,
+            p95 = command.p95.as_millis() as u64,
+        ))
+        .join("\n");
+
+    let overview = tr!(
+        language,
+        "analytics-overview-value",
+        total = summary.total,
+        commands = summary.commands.len(),
+        errors = percentage(summary.failures, summary.total),
+    );
+
+    let daily_active_users = summary.daily_active_users.iter()
+        .map(|&(_, users)| users)
+        .collect::<Vec<usize>>();
+
+    let daily_active_users = match daily_active_users.iter().max() {
+        Some(peak) => tr!(
+            language,
+            "analytics-daily-users-value",
+            average = format!("{:.1}", daily_active_users.iter().sum::<usize>() as f64 / daily_active_users.len() as f64),
+            peak = *peak,
+            days = daily_active_users.len(),
+        ),
+        None => tr!(language, "analytics-no-users"),
+    };
+
+    let title = tr!(
+        language,
+        "analytics-title",
+        period = DurationFormatter::new()
+            .locale(language.duration_locale())
+            .format(period.num_milliseconds()),
+    );
+
+    EmbedModel {
+        author: Some(EmbedAuthorModel {
+            name: title,
+            ..EmbedAuthorModel::default()
+        }),
+        description: Some(commands),
+        fields: vec![
+            EmbedFieldModel::new(tr!(language, "analytics-overview"), overview, true),
+            EmbedFieldModel::new(tr!(language, "analytics-daily-users"), daily_active_users, true),
+        ],
+        ..EmbedModel::default()
+    }
+}
+
+#[cfg(feature = "developer-commands")]
+pub struct ShardSummary {
+    pub id: u64,
+    pub stage: String,
+    pub latency: Option<Duration>,
+    pub guilds: usize,
+}
+
+/// The list of shards, which goes into the description so it can be sent as a long reply
+#[cfg(feature = "developer-commands")]
+pub fn shards(language: Language, shards: &[ShardSummary], current_shard: u64, shard_count: u64) -> EmbedModel {
+    let text = shards.iter()
+        .map(|shard| {
+            let latency = match shard.latency {
+                Some(latency) => tr!(language, "ping-milliseconds", ping = latency.as_millis() as u64),
+                None => tr!(language, "ping-no-heartbeat"),
+            };
+
+            let mut entry = tr!(
+                language,
+                "shards-entry",
+                shard = shard.id,
+                stage = shard.stage.as_str(),
+                latency = latency,
+                guilds = shard.guilds,
+            );
+
+            if shard.id == current_shard {
+                entry.push_str(&tr!(language, "shards-current"));
+            }
+
+            entry
+        })
+        .join("\n");
+
+    EmbedModel {
+        author: Some(EmbedAuthorModel {
+            name: tr!(language, "shards-title", count = shard_count),
+            ..EmbedAuthorModel::default()
+        }),
+        description: Some(text),
+        ..EmbedModel::default()
+    }
+}
+
+/// The current time in every zone, with the differences to the user's own time zone,
+/// or to the first zone listed if the user has not saved theirs
+#[cfg(feature = "utility-commands")]
+pub fn world_clock(language: Language, zones: &[(String, Tz)], user_timezone: Option<Tz>, now: DateTime<Utc>) -> EmbedModel {
+    let offset_secs = |tz: &Tz| now.with_timezone(tz).offset().fix().local_minus_utc();
+
+    let reference = match user_timezone {
+        Some(tz) => Some((tr!(language, "time-reference-you"), offset_secs(&tz))),
+        None => zones.first().map(|(name, tz)| (name.clone(), offset_secs(tz))),
+    };
+
+    let fields = zones.iter()
+        .enumerate()
+        .map(|(i, (name, tz))| {
+            let local = now.with_timezone(tz);
+            let offset = local.offset();
+
+            let mut output = format!(
+                "**{}**\n{}\n{}, {} (UTC{})",
+                local.format("%I:%M %p"),
+                local.format("%a, %b %d, %Y"),
+                tz.name(),
+                offset.abbreviation(),
+                local.format("%:z"),
+            );
+
+            if offset.dst_offset().num_seconds() != 0 {
+                output.push('\n');
+                output.push_str(&tr!(language, "time-dst"));
+            }
+
+            if let Some((ref reference_name, reference_offset)) = reference {
+                if zones.len() > 1 && (user_timezone.is_some() || i > 0) {
+                    let difference = offset.fix().local_minus_utc() - reference_offset;
+
+                    let difference = if difference == 0 {
+                        tr!(language, "time-difference-same", reference = reference_name.as_str())
+                    } else {
+                        tr!(
+                            language,
+                            "time-difference",
+                            difference = format_offset_difference(difference),
+                            reference = reference_name.as_str(),
+                        )
+                    };
+
+                    output.push('\n');
+                    output.push_str(&difference);
+                }
+            }
+
+            EmbedFieldModel::new(name, output, true)
+        })
+        .collect();
+
+    EmbedModel {
+        author: Some(EmbedAuthorModel {
+            name: tr!(language, "time-title"),
+            ..EmbedAuthorModel::default()
+        }),
+        fields,
+        ..EmbedModel::default()
+    }
+}
+
+/// A guild's theme, with the color and icon of every embed type
+#[cfg(feature = "utility-commands")]
+pub fn theme(language: Language, theme: &Theme) -> EmbedModel {
+    let switch_name = |value: bool| if value {
+        tr!(language, "theme-on")
+    } else {
+        tr!(language, "theme-off")
+    };
+
+    let fields = EmbedType::ALL.iter()
+        .map(|embed_type| {
+            let mut value = format!("`#{:06X}`", theme.color(*embed_type).0);
+
+            if let Some(icon) = theme.icon(*embed_type) {
+                value.push('\n');
+                value.push_str(icon);
+            }
+
+            EmbedFieldModel::new(embed_type.get_title(language), value, true)
+        })
+        .collect();
+
+    let no_footer = tr!(language, "theme-no-footer");
+    let description = tr!(
+        language,
+        "theme-current",
+        footer = theme.footer.as_deref().unwrap_or(&no_footer),
+        timestamp = switch_name(theme.timestamp),
+        reply = switch_name(theme.reply),
+    );
+
+    EmbedModel {
+        author: Some(EmbedAuthorModel {
+            name: tr!(language, "theme-title"),
+            ..EmbedAuthorModel::default()
+        }),
+        description: Some(description),
+        fields,
+        ..EmbedModel::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{DateTime, TimeZone, Utc};
+
+    use std::{env, fs, path::Path};
+
+    #[cfg(feature = "general-commands")]
+    use crate::cmds::{CommandGroup, CommandUsages};
+
+    #[cfg(feature = "general-commands")]
+    use serenity::{async_trait, client::Context, model::channel::Message};
+
+    #[cfg(feature = "general-commands")]
+    use std::error::Error;
+
+    #[cfg(feature = "utility-commands")]
+    use crate::weather::AirQuality;
+
+    #[cfg(any(feature = "general-commands", feature = "utility-commands"))]
+    const BOT_FACE: &str = "https://cdn.discordapp.com/embed/avatars/0.png";
+
+    /// Compares the embed with `tests/snapshots/<name>.json`, or rewrites the file if `UPDATE_SNAPSHOTS` is set
+    fn assert_snapshot(name: &str, embed: &EmbedModel) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("snapshots")
+            .join(format!("{}.json", name));
+
+        let actual = serde_json::to_string_pretty(embed).unwrap() + "\n";
+
+        if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, actual).unwrap();
+
+            return;
+        }
+
+        let expected = fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("{} has no snapshot yet, run the tests with UPDATE_SNAPSHOTS=1", name));
+
+        assert_eq!(actual, expected, "{} does not match its snapshot, run the tests with UPDATE_SNAPSHOTS=1 if the change is intended", name);
+    }
+
+    /// Commands of their own so that the snapshots do not change whenever a command is added
+    #[cfg(feature = "general-commands")]
+    struct TestCommand {
+        name: &'static str,
+        category: CommandCategory,
+        aliases: Vec<&'static str>,
+        usages: CommandUsages<'static>,
+        permissions: Permissions,
+    }
+
+    #[cfg(feature = "general-commands")]
+    #[async_trait]
+    impl Command for TestCommand {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn description(&self) -> &'static str {
+            "N/A"
+        }
+
+        fn category(&self) -> CommandCategory {
+            self.category
+        }
+
+        fn aliases(&self) -> Vec<&str> {
+            self.aliases.clone()
+        }
+
+        fn usages(&self) -> CommandUsages {
+            self.usages.clone()
+        }
+
+        fn permissions(&self) -> Permissions {
+            self.permissions
+        }
+
+        async fn invoke(&self, _: &Context, _: &Message, _: &[&str]) -> Result<(), Box<dyn Error + Send + Sync>> {
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "general-commands")]
+    fn test_commands() -> Vec<Box<dyn Command>> {
+        vec![
+            Box::new(TestCommand {
+                name: "shutdown",
+                category: CommandCategory::Developer,
+                aliases: Vec::new(),
+                usages: Vec::new(),
+                permissions: Permissions::empty(),
+            }),
+            Box::new(TestCommand {
+                name: "ping",
+                category: CommandCategory::General,
+                aliases: vec!["latency"],
+                usages: vec![vec!["edit (optional)"]],
+                permissions: Permissions::empty(),
+            }),
+            Box::new(TestCommand {
+                name: "help",
+                category: CommandCategory::General,
+                aliases: Vec::new(),
+                usages: vec![vec!["command name (optional)"]],
+                permissions: Permissions::empty(),
+            }),
+            Box::new(TestCommand {
+                name: "weather",
+                category: CommandCategory::Utility,
+                aliases: Vec::new(),
+                usages: vec![vec!["location"], vec!["forecast", "location"]],
+                permissions: Permissions::empty(),
+            }),
+            Box::new(TestCommand {
+                name: "greet",
+                category: CommandCategory::Custom,
+                aliases: Vec::new(),
+                usages: Vec::new(),
+                permissions: Permissions::empty(),
+            }),
+        ]
+    }
+
+    #[cfg(feature = "general-commands")]
+    fn test_group() -> CommandGroup {
+        let subcommand = |name, usages, permissions| TestCommand {
+            name,
+            category: CommandCategory::Utility,
+            aliases: Vec::new(),
+            usages,
+            permissions,
+        };
+
+        CommandGroup::new("weather", "N/A", CommandCategory::Utility)
+            .subcommand(subcommand("current", vec![vec!["location"]], Permissions::empty()))
+            .subcommand(subcommand("forecast", vec![vec!["location"]], Permissions::empty()))
+            .subcommand(
+                CommandGroup::new("alerts", "N/A", CommandCategory::Utility)
+                    .subcommand(subcommand("list", Vec::new(), Permissions::empty()))
+                    .subcommand(subcommand("subscribe", vec![vec!["location"]], Permissions::MANAGE_CHANNELS)),
+            )
+            .default_subcommand("current")
+    }
+
+    #[cfg(feature = "utility-commands")]
+    fn test_weather() -> CurrentWeather {
+        CurrentWeather {
+            city_id: 2643743,
+            location: String::from("London, GB"),
+            condition: Some(String::from("light rain")),
+            icon: Some(String::from("10d")),
+            temperature: 12.5,
+            humidity: 81.0,
+            pressure: 1012.0,
+            wind_speed: 5.7,
+            wind_direction: 230.0,
+            cloudiness: Some(75),
+            timezone: Some(3600),
+            sunrise: Some(1_625_026_000),
+            sunset: Some(1_625_085_700),
+        }
+    }
+
+    fn test_time(hour: u32) -> DateTime<Utc> {
+        Utc.ymd(2021, 6, 30).and_hms(hour, 15, 30)
+    }
+
+    #[cfg(feature = "general-commands")]
+    #[test]
+    fn help_overview_snapshot() {
+        let commands = test_commands();
+        let commands = commands.iter().map(|c| c.as_ref()).collect::<Vec<_>>();
+
+        assert_snapshot("help_overview", &help_overview(Language::English, &commands, "Test Bot", BOT_FACE));
+    }
+
+    #[cfg(feature = "general-commands")]
+    #[test]
+    fn help_command_snapshots() {
+        let commands = test_commands();
+
+        assert_snapshot("help_command_ping", &help_command(Language::English, &[commands[1].as_ref()], BOT_FACE));
+        assert_snapshot("help_command_weather_de", &help_command(Language::German, &[commands[3].as_ref()], BOT_FACE));
+        assert_snapshot("help_command_developer", &help_command(Language::English, &[commands[0].as_ref()], BOT_FACE));
+        assert_snapshot("help_command_custom", &help_command(Language::English, &[commands[4].as_ref()], BOT_FACE));
+    }
+
+    #[cfg(feature = "general-commands")]
+    #[test]
+    fn help_group_snapshots() {
+        let group = test_group();
+        let alerts = group.subcommands()[2].as_ref();
+
+        assert_snapshot("help_group", &help_command(Language::English, &[&group], BOT_FACE));
+        assert_snapshot("help_group_nested_ru", &help_command(Language::Russian, &[&group, alerts], BOT_FACE));
+        assert_snapshot(
+            "help_subcommand",
+            &help_command(Language::English, &[&group, alerts, alerts.subcommands()[1].as_ref()], BOT_FACE),
+        );
+    }
+
+    #[cfg(feature = "general-commands")]
+    #[test]
+    fn uptime_snapshots() {
+        assert_snapshot("uptime", &uptime(Language::English, test_time(8), test_time(20), None));
+        assert_snapshot("uptime_local_ru", &uptime(Language::Russian, test_time(8), test_time(20), Some(Tz::Europe__Moscow)));
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[test]
+    fn weather_snapshots() {
+        let additional = AdditionalConditions {
+            air_quality: Some(AirQuality {
+                aqi: 2,
+                pm2_5: 8.31,
+                pm10: 12.04,
+                o3: 61.5,
+            }),
+            uv_index: Some(4.2),
+            wind_gust: Some(9.8),
+        };
+
+        assert_snapshot("weather", &weather(Language::English, &test_weather(), &additional, test_time(12), BOT_FACE));
+
+        let minimal = CurrentWeather {
+            condition: None,
+            icon: None,
+            cloudiness: None,
+            timezone: None,
+            ..test_weather()
+        };
+
+        assert_snapshot("weather_minimal_de", &weather(Language::German, &minimal, &AdditionalConditions::default(), test_time(12), BOT_FACE));
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[test]
+    fn sunrise_and_sunset_are_labeled_apart() {
+        let embed = weather(Language::English, &test_weather(), &AdditionalConditions::default(), test_time(12), BOT_FACE);
+        let names = embed.fields.iter().map(|field| field.name.as_str()).collect::<Vec<_>>();
+
+        assert!(names.contains(&"Sunrise"));
+        assert!(names.contains(&"Sunset"));
+    }
+
+    #[cfg(feature = "general-commands")]
+    #[test]
+    fn ping_snapshots() {
+        assert_snapshot(
+            "ping",
+            &ping(
+                Language::English,
+                Duration::from_millis(120),
+                Some(Duration::from_millis(45)),
+                Some(Duration::from_millis(210)),
+                0,
+                Some("Connected"),
+            ),
+        );
+        assert_snapshot("ping_no_heartbeat_de", &ping(Language::German, Duration::from_millis(80), None, None, 1, None));
+    }
+
+    #[cfg(feature = "general-commands")]
+    fn test_stats() -> BotStats {
+        BotStats {
+            uptime: Duration::from_secs(3 * 24 * 60 * 60 + 5 * 60 * 60 + 42 * 60),
+            memory: Some(48 * 1024 * 1024),
+            cpu_time: Some(Duration::from_millis(83_250)),
+            tasks: 14,
+            workers: 4,
+            guilds: 12,
+            channels: 340,
+            users: 5_120,
+            commands_run: 1_234,
+            reconnects: 2,
+            version: String::from("1.2.3"),
+            rustc_version: String::from("1.50.0"),
+        }
+    }
+
+    #[cfg(feature = "general-commands")]
+    #[test]
+    fn stats_snapshots() {
+        assert_snapshot("stats", &stats(Language::English, &test_stats(), "Test Bot", BOT_FACE));
+
+        let unavailable = BotStats {
+            memory: None,
+            cpu_time: None,
+            ..test_stats()
+        };
+
+        assert_snapshot("stats_unavailable_ru", &stats(Language::Russian, &unavailable, "Test Bot", BOT_FACE));
+    }
+
+    #[cfg(feature = "general-commands")]
+    #[test]
+    fn uptime_history_snapshots() {
+        use crate::availability::{EventKind, UptimeEvent};
+
+        let now = test_time(20).timestamp();
+        let event = |kind, hours_ago: i64| UptimeEvent { kind, time: now - hours_ago * 60 * 60 };
+
+        let history = UptimeHistory {
+            events: vec![
+                event(EventKind::Start, 10 * 24),
+                event(EventKind::Connect, 10 * 24),
+                event(EventKind::Disconnect, 3 * 24),
+                event(EventKind::Connect, 3 * 24 - 2),
+                event(EventKind::Crash, 5),
+                event(EventKind::Start, 4),
+                event(EventKind::Connect, 4),
+                event(EventKind::Disconnect, 1),
+            ],
+            last_seen: Some(now),
+        };
+
+        assert_snapshot("uptime_history", &uptime_history(Language::English, &history, now));
+        assert_snapshot("uptime_history_empty_de", &uptime_history(Language::German, &UptimeHistory::default(), now));
+    }
+
+    #[cfg(feature = "developer-commands")]
+    #[test]
+    fn analytics_snapshots() {
+        use crate::analytics::CommandSummary;
+
+        let command = |name: &str, uses, failures, p50, p95| CommandSummary {
+            name: name.to_owned(),
+            uses,
+            failures,
+            p50: Duration::from_millis(p50),
+            p95: Duration::from_millis(p95),
+        };
+
+        let summary = Summary {
+            total: 180,
+            failures: 9,
+            commands: vec![
+                command("weather", 120, 6, 340, 910),
+                command("ping", 45, 0, 95, 180),
+                command("time", 15, 3, 12, 40),
+            ],
+            daily_active_users: vec![
+                (test_time(0).date().naive_utc().pred(), 7),
+                (test_time(0).date().naive_utc(), 11),
+            ],
+        };
+
+        assert_snapshot("analytics", &analytics(Language::English, &summary, chrono::Duration::days(7)));
+
+        let empty = Summary {
+            total: 0,
+            failures: 0,
+            commands: Vec::new(),
+            daily_active_users: Vec::new(),
+        };
+
+        assert_snapshot("analytics_empty_ru", &analytics(Language::Russian, &empty, chrono::Duration::days(1)));
+    }
+
+    #[cfg(feature = "developer-commands")]
+    #[test]
+    fn shards_snapshot() {
+        let shards_list = [
+            ShardSummary {
+                id: 0,
+                stage: String::from("Connected"),
+                latency: Some(Duration::from_millis(42)),
+                guilds: 7,
+            },
+            ShardSummary {
+                id: 1,
+                stage: String::from("Resuming"),
+                latency: None,
+                guilds: 5,
+            },
+        ];
+
+        assert_snapshot("shards", &shards(Language::English, &shards_list, 1, 2));
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[test]
+    fn world_clock_snapshots() {
+        let zones = [
+            (String::from("Berlin"), Tz::Europe__Berlin),
+            (String::from("Tokyo"), Tz::Asia__Tokyo),
+            (String::from("UTC"), Tz::UTC),
+        ];
+
+        assert_snapshot("world_clock", &world_clock(Language::English, &zones, None, test_time(12)));
+        assert_snapshot(
+            "world_clock_user_timezone_de",
+            &world_clock(Language::German, &zones, Some(Tz::America__New_York), test_time(12)),
+        );
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[test]
+    fn theme_snapshots() {
+        assert_snapshot("theme_default", &theme(Language::English, &Theme::default()));
+
+        let mut custom = Theme {
+            footer: Some(String::from("Powered by coffee")),
+            timestamp: true,
+            ..Theme::default()
+        };
+
+        custom.colors.insert(EmbedType::Info, 0x00ff7f);
+        custom.icons.insert(EmbedType::Failure, String::from("⛔"));
+
+        assert_snapshot("theme_custom_ru", &theme(Language::Russian, &custom));
+    }
+}
@@ -0,0 +1,200 @@
+//! Liveness and readiness reports, served at `/healthz` and `/readyz`
+
+use crate::{config, START_TIME};
+
+use lazy_static::lazy_static;
+
+use serde::Serialize;
+
+use serenity::{
+    client::bridge::gateway::ShardManager,
+    gateway::ConnectionStage,
+    prelude::Mutex,
+};
+
+use std::{
+    collections::HashMap,
+    fs,
+    sync::{Arc, Mutex as StdMutex},
+    time::{Duration, Instant, SystemTime},
+};
+
+lazy_static! {
+    static ref SHARDS: StdMutex<HashMap<u64, ShardState>> = StdMutex::new(HashMap::new());
+}
+
+#[derive(Default)]
+struct ShardState {
+    stage: Option<ConnectionStage>,
+    latency: Option<Duration>,
+    /// When a new heartbeat latency was last seen, which is as close to the last heartbeat acknowledgement
+    /// as the shard manager lets us get
+    last_heartbeat_ack: Option<Instant>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    /// Only checked for readiness reports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ready: Option<bool>,
+    pub uptime_secs: u64,
+    pub shards: Vec<ShardReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<DependencyReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShardReport {
+    pub id: u64,
+    pub stage: String,
+    pub connected: bool,
+    pub latency_ms: Option<u64>,
+    pub last_heartbeat_ack_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DependencyReport {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ShardReport {
+    fn is_healthy(&self) -> bool {
+        let timeout = config::HEARTBEAT_ACK_TIMEOUT.as_secs();
+
+        self.connected && matches!(self.last_heartbeat_ack_secs, Some(secs) if secs <= timeout)
+    }
+}
+
+/// Keeps track of the shards' connection stages and heartbeats
+pub async fn watch_shards(manager: Arc<Mutex<ShardManager>>) {
+    let mut interval = tokio::time::interval(config::SHARD_WATCH_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let runners = manager.lock().await.runners.clone();
+        let runners = runners.lock().await;
+        let mut shards = SHARDS.lock().unwrap();
+
+        shards.retain(|id, _| runners.keys().any(|shard_id| shard_id.0 == *id));
+
+        for (shard_id, runner) in runners.iter() {
+            let shard = shards.entry(shard_id.0).or_default();
+
+            if runner.latency.is_some() && runner.latency != shard.latency {
+                shard.last_heartbeat_ack = Some(Instant::now());
+            }
+
+            shard.stage = Some(runner.stage);
+            shard.latency = runner.latency;
+        }
+    }
+}
+
+/// Makes sure the data directory can be written to
+fn check_storage() -> DependencyReport {
+    let path = config::DATA_DIR.join(".healthcheck");
+
+    let result = fs::create_dir_all(&*config::DATA_DIR)
+        .and_then(|_| fs::write(&path, b"ok"))
+        .and_then(|_| fs::remove_file(&path));
+
+    DependencyReport {
+        ok: result.is_ok(),
+        error: result.err().map(|e| format!("{} is not writable: {}", config::DATA_DIR.display(), e)),
+    }
+}
+
+fn shards() -> Vec<ShardReport> {
+    let mut shards = SHARDS.lock().unwrap()
+        .iter()
+        .map(|(id, shard)| ShardReport {
+            id: *id,
+            stage: shard.stage.map_or_else(|| String::from("unknown"), |stage| stage.to_string()),
+            connected: shard.stage == Some(ConnectionStage::Connected),
+            latency_ms: shard.latency.map(|latency| latency.as_millis() as u64),
+            last_heartbeat_ack_secs: shard.last_heartbeat_ack.map(|ack| ack.elapsed().as_secs()),
+        })
+        .collect::<Vec<ShardReport>>();
+
+    shards.sort_by_key(|shard| shard.id);
+
+    shards
+}
+
+fn uptime_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(*START_TIME)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Reports the shards without touching the disk, as liveness probes are frequent and must not fail on storage issues
+pub fn liveness() -> HealthReport {
+    HealthReport {
+        ready: None,
+        uptime_secs: uptime_secs(),
+        shards: shards(),
+        storage: None,
+    }
+}
+
+/// Reports the shards and writes a file to check the storage, which is done on a blocking thread
+pub async fn readiness() -> HealthReport {
+    let storage = tokio::task::spawn_blocking(check_storage)
+        .await
+        .unwrap_or_else(|e| DependencyReport { ok: false, error: Some(e.to_string()) });
+
+    let shards = shards();
+
+    HealthReport {
+        ready: Some(is_ready(&shards, &storage)),
+        uptime_secs: uptime_secs(),
+        shards,
+        storage: Some(storage),
+    }
+}
+
+/// The bot is ready once every shard is connected and heartbeating and its storage is writable
+fn is_ready(shards: &[ShardReport], storage: &DependencyReport) -> bool {
+    !shards.is_empty() && shards.iter().all(ShardReport::is_healthy) && storage.ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shard(connected: bool, last_heartbeat_ack_secs: Option<u64>) -> ShardReport {
+        ShardReport {
+            id: 0,
+            stage: String::new(),
+            connected,
+            latency_ms: None,
+            last_heartbeat_ack_secs,
+        }
+    }
+
+    #[test]
+    fn liveness_does_not_check_the_storage() {
+        let report = liveness();
+
+        assert!(report.ready.is_none());
+        assert!(report.storage.is_none());
+    }
+
+    #[test]
+    fn readiness_requires_healthy_shards_and_storage() {
+        let ok = DependencyReport { ok: true, error: None };
+        let failed = DependencyReport { ok: false, error: Some(String::from("read-only")) };
+
+        assert!(is_ready(&[shard(true, Some(10))], &ok));
+
+        assert!(!is_ready(&[], &ok));
+        assert!(!is_ready(&[shard(true, Some(10)), shard(false, Some(10))], &ok));
+        assert!(!is_ready(&[shard(true, None)], &ok));
+        assert!(!is_ready(&[shard(true, Some(60 * 60))], &ok));
+        assert!(!is_ready(&[shard(true, Some(10))], &failed));
+    }
+}
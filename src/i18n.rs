@@ -0,0 +1,257 @@
+use crate::storage::JsonStore;
+
+use fluent_bundle::{
+    concurrent::FluentBundle,
+    FluentArgs,
+    FluentResource,
+};
+
+use lazy_static::lazy_static;
+
+use serenity::model::channel::Message;
+
+use std::collections::HashMap;
+
+use unic_langid::LanguageIdentifier;
+
+#[cfg(any(feature = "developer-commands", feature = "general-commands"))]
+use crate::utils::time::{DurationLocale, ENGLISH, GERMAN, RUSSIAN};
+
+lazy_static! {
+    static ref BUNDLES: HashMap<Language, FluentBundle<FluentResource>> = Language::ALL.iter()
+        .map(|language| (*language, language.bundle()))
+        .collect();
+
+    pub static ref GUILD_LANGUAGES: JsonStore<HashMap<u64, String>> = JsonStore::open("guild_languages.json");
+    pub static ref USER_LANGUAGES: JsonStore<HashMap<u64, String>> = JsonStore::open("user_languages.json");
+}
+
+/// Formats a message from the catalog, e.g. `tr!(language, "time-set", zone = tz.name())`.
+/// IDs missing from the catalog fail to compile.
+macro_rules! tr {
+    ($language:expr, $id:literal) => {
+        $crate::i18n::translate($language, $crate::i18n::message_id!($id), None)
+    };
+    ($language:expr, $id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+
+        $(args.set(stringify!($name), $value);)+
+
+        $crate::i18n::translate($language, $crate::i18n::message_id!($id), Some(&args))
+    }};
+}
+
+pub(crate) use discord_test_rs_bot_macros::message_id;
+pub(crate) use tr;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    Russian,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::English, Language::German, Language::Russian];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en-US",
+            Language::German => "de",
+            Language::Russian => "ru",
+        }
+    }
+
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+            Language::Russian => "Русский",
+        }
+    }
+
+    #[cfg(any(feature = "developer-commands", feature = "general-commands"))]
+    pub fn duration_locale(self) -> &'static DurationLocale {
+        match self {
+            Language::English => &ENGLISH,
+            Language::German => &GERMAN,
+            Language::Russian => &RUSSIAN,
+        }
+    }
+
+    /// Accepts language codes as well as English and native language names
+    pub fn parse(query: &str) -> Option<Language> {
+        let query = query.trim().to_lowercase();
+
+        Language::ALL.iter().copied().find(|language| {
+            let code = language.code().to_lowercase();
+            let english_name = format!("{:?}", language).to_lowercase();
+
+            query == code
+                || code.starts_with(&format!("{}-", query))
+                || query == english_name
+                || query == language.native_name().to_lowercase()
+        })
+    }
+
+    fn catalog(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../locales/en-US/main.ftl"),
+            Language::German => include_str!("../locales/de/main.ftl"),
+            Language::Russian => include_str!("../locales/ru/main.ftl"),
+        }
+    }
+
+    fn bundle(self) -> FluentBundle<FluentResource> {
+        let language_id: LanguageIdentifier = self.code().parse().unwrap();
+        let resource = FluentResource::try_new(self.catalog().to_owned())
+            .unwrap_or_else(|(_, errors)| panic!("The {} catalog is invalid: {:?}", self.code(), errors));
+
+        let mut bundle = FluentBundle::new_concurrent(vec![language_id]);
+
+        // Discord renders the Unicode isolation marks around placeables as visible characters
+        bundle.set_use_isolating(false);
+        bundle.add_resource(resource)
+            .unwrap_or_else(|errors| panic!("The {} catalog is invalid: {:?}", self.code(), errors));
+
+        bundle
+    }
+}
+
+/// Formats a message, falling back to English and then to the message ID when it is missing
+pub fn translate(language: Language, id: &str, args: Option<&FluentArgs>) -> String {
+    [language, Language::English].iter()
+        .find_map(|language| {
+            let bundle = &BUNDLES[language];
+            let pattern = bundle.get_message(id)?.value()?;
+            let mut errors = Vec::new();
+
+            Some(bundle.format_pattern(pattern, args, &mut errors).into_owned())
+        })
+        .unwrap_or_else(|| id.to_owned())
+}
+
+pub fn get_guild_language(guild_id: Option<u64>) -> Language {
+    guild_id
+        .and_then(|id| GUILD_LANGUAGES.read().get(&id).cloned())
+        .and_then(|code| Language::parse(&code))
+        .unwrap_or_default()
+}
+
+/// A user's own language takes precedence over the language of the guild the message is from
+pub fn get_language(message: &Message) -> Language {
+    USER_LANGUAGES.read()
+        .get(message.author.id.as_u64())
+        .and_then(|code| Language::parse(code))
+        .unwrap_or_else(|| get_guild_language(message.guild_id.map(|id| *id.as_u64())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        cmds::{description_id, CommandCategory, COMMANDS},
+        utils::{
+            discord::EmbedType,
+            misc::{get_aqi_category, get_uv_risk, get_wind_direction},
+        },
+    };
+
+    use fluent_syntax::{ast, parser};
+
+    use std::collections::BTreeSet;
+
+    fn message_ids(language: Language) -> BTreeSet<String> {
+        let resource = parser::parse(language.catalog()).expect("The catalog must parse without errors");
+
+        resource.body.iter()
+            .filter_map(|entry| match entry {
+                ast::Entry::Message(message) => Some(message.id.name.to_owned()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Message IDs that are built at runtime, as those passed to `tr!` are checked when compiling
+    fn dynamic_ids() -> BTreeSet<String> {
+        let mut ids = BTreeSet::new();
+
+        ids.extend(EmbedType::ALL.iter().map(|t| format!("embed-{}", t.to_string().to_lowercase())));
+        ids.extend(COMMANDS.paths().iter().map(|path| description_id(path)));
+        ids.extend(CommandCategory::ALL.iter().map(|c| format!("category-{}", c.to_string().to_lowercase())));
+        ids.extend((0..=12).map(|force| format!("beaufort-{}", force)));
+        ids.extend((0..=6).filter_map(get_aqi_category));
+        ids.extend([0.0, 3.0, 6.0, 8.0, 11.0].iter().map(|uv| get_uv_risk(*uv).to_owned()));
+        ids.extend((0..16).filter_map(|i| get_wind_direction(i as f32 * 22.5)).map(|d| {
+            format!("compass-{}", d.abbreviation.to_lowercase())
+        }));
+
+        ids
+    }
+
+    #[test]
+    fn catalogs_have_the_same_messages() {
+        let english = message_ids(Language::English);
+
+        for language in &Language::ALL[1..] {
+            let ids = message_ids(*language);
+
+            let missing = english.difference(&ids).collect::<Vec<_>>();
+            let unknown = ids.difference(&english).collect::<Vec<_>>();
+
+            assert!(missing.is_empty(), "{} is missing {:?}", language.code(), missing);
+            assert!(unknown.is_empty(), "{} has unknown messages {:?}", language.code(), unknown);
+        }
+    }
+
+    #[test]
+    fn catalogs_cover_every_dynamic_message() {
+        let english = message_ids(Language::English);
+        let missing = dynamic_ids().into_iter()
+            .filter(|id| !english.contains(id))
+            .collect::<Vec<String>>();
+
+        assert!(missing.is_empty(), "The catalogs are missing {:?}", missing);
+    }
+
+    #[test]
+    fn bundles_load() {
+        for language in Language::ALL.iter() {
+            assert!(BUNDLES.contains_key(language));
+        }
+    }
+
+    #[test]
+    fn interpolation_and_plurals() {
+        assert_eq!(tr!(Language::English, "time-set", zone = "Europe/Berlin"), "Your time zone has been set to Europe/Berlin!");
+        assert_eq!(
+            tr!(Language::English, "alerts-list", count = 1, locations = "Oslo, NO"),
+            "The channel receives weather alerts for 1 location:\nOslo, NO",
+        );
+        assert_eq!(
+            tr!(Language::Russian, "alerts-list", count = 3, locations = "-"),
+            "Канал получает погодные предупреждения для 3 мест:\n-",
+        );
+        assert_eq!(
+            tr!(Language::Russian, "alerts-list", count = 21, locations = "-"),
+            "Канал получает погодные предупреждения для 21 места:\n-",
+        );
+    }
+
+    #[test]
+    fn fallback_to_message_id() {
+        assert_eq!(translate(Language::German, "no-such-message", None), "no-such-message");
+    }
+
+    #[test]
+    fn language_parsing() {
+        assert_eq!(Language::parse("en"), Some(Language::English));
+        assert_eq!(Language::parse("EN-us"), Some(Language::English));
+        assert_eq!(Language::parse("german"), Some(Language::German));
+        assert_eq!(Language::parse("Deutsch"), Some(Language::German));
+        assert_eq!(Language::parse("русский"), Some(Language::Russian));
+        assert_eq!(Language::parse("klingon"), None);
+    }
+}
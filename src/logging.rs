@@ -0,0 +1,91 @@
+use crate::config;
+
+use std::{error::Error, str::FromStr};
+
+use tracing_appender::{non_blocking::WorkerGuard, rolling};
+
+use tracing_subscriber::{
+    fmt::{self, MakeWriter},
+    prelude::*,
+    EnvFilter,
+    Layer,
+    Registry,
+};
+
+const LOG_FILE_PREFIX: &str = "bot.log";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LogFormat {
+    /// Human-readable lines
+    Pretty,
+    /// One JSON object per line, including the fields of the current span
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "pretty" | "text" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("Unknown log format \"{}\", expected \"pretty\" or \"json\"", s)),
+        }
+    }
+}
+
+/// Sets up logging to stdout and, if `LOG_DIR` is set, to a daily rotated file.
+/// `RUST_LOG` takes precedence over `LOG_LEVEL` when both are set.
+///
+/// The returned guard flushes the file output once dropped, so it has to be kept alive until the bot exits.
+pub fn init() -> Result<Option<WorkerGuard>, Box<dyn Error>> {
+    let filter = match EnvFilter::try_from_default_env() {
+        Ok(filter) => filter,
+        Err(_) => EnvFilter::try_new(config::LOG_LEVEL.as_str())?,
+    };
+
+    let format = config::LOG_FORMAT.parse::<LogFormat>()?;
+
+    let mut layers = vec![layer(format, std::io::stdout, true)];
+    let mut guard = None;
+
+    if let Some(ref dir) = *config::LOG_DIR {
+        let (writer, file_guard) = tracing_appender::non_blocking(rolling::daily(dir, LOG_FILE_PREFIX));
+
+        layers.push(layer(format, writer, false));
+        guard = Some(file_guard);
+    }
+
+    tracing_subscriber::registry()
+        .with(layers)
+        .with(filter)
+        .try_init()?;
+
+    Ok(guard)
+}
+
+fn layer<W>(format: LogFormat, writer: W, ansi: bool) -> Box<dyn Layer<Registry> + Send + Sync>
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = fmt::layer()
+        .with_writer(writer)
+        .with_ansi(ansi);
+
+    match format {
+        LogFormat::Pretty => layer.boxed(),
+        LogFormat::Json => layer.json().with_current_span(true).boxed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_formats_are_parsed() {
+        assert_eq!("pretty".parse(), Ok(LogFormat::Pretty));
+        assert_eq!(" JSON ".parse(), Ok(LogFormat::Json));
+        assert!("xml".parse::<LogFormat>().is_err());
+    }
+}
@@ -1,11 +1,33 @@
+mod analytics;
+mod availability;
+#[cfg(feature = "utility-commands")]
+mod chart;
 mod cmds;
 mod config;
+#[cfg(any(feature = "developer-commands", feature = "general-commands", feature = "utility-commands"))]
+mod embeds;
+mod health;
+mod i18n;
+mod logging;
+mod metrics;
+mod scripting;
+mod server;
+mod storage;
+mod tags;
+#[cfg(test)]
+mod testing;
+mod theme;
 mod utils;
+#[cfg(feature = "utility-commands")]
+mod weather;
 
 use crate::{
-    cmds::{Command, COMMANDS},
+    availability::EventKind,
+    cmds::{Command, CommandCategory, COMMANDS},
+    i18n::tr,
+    metrics::Outcome,
     utils::{
-        discord::{DefaultEmbedReplies, EmbedType},
+        discord::{has_permissions, DefaultEmbedReplies, EmbedType, ReplyOptions, ShardManagerContainer, MESSAGE_LIMIT},
         string::strip_str,
     },
 };
@@ -13,10 +35,18 @@ use crate::{
 use lazy_static::lazy_static;
 
 use serenity::{
-    client::Context,
+    client::{bridge::gateway::event::ShardStageUpdateEvent, Context},
+    gateway::ConnectionStage,
     model::{
         channel::{Message, MessageType},
         gateway::{Activity, Ready},
+        id::GuildId,
+        interactions::{
+            application_command::{ApplicationCommand, ApplicationCommandInteraction},
+            Interaction,
+            InteractionResponseType,
+        },
+        permissions::Permissions,
         prelude::OnlineStatus,
     },
     prelude::EventHandler,
@@ -27,20 +57,20 @@ use serenity::{
 use std::{
     error::Error,
     sync::atomic::{AtomicUsize, Ordering},
-    time::SystemTime,
+    time::{Duration, Instant, SystemTime},
 };
 
-use tracing::info;
+use tracing::{error, field, info, info_span, warn, Instrument, Span};
 
 lazy_static! {
     static ref START_TIME: SystemTime = SystemTime::now();
 }
 
 static CONNECT_COUNT: AtomicUsize = AtomicUsize::new(0);
+static COMMAND_COUNT: AtomicUsize = AtomicUsize::new(0);
 
 struct Handler;
 
-#[allow(unused_must_use)]
 #[async_trait]
 impl EventHandler for Handler {
     async fn message(&self, cx: Context, message: Message) {
@@ -50,73 +80,258 @@ impl EventHandler for Handler {
         let is_regular = message.kind == MessageType::Regular;
 
         if starts_with_prefix && author_is_not_bot && is_from_guild && is_regular {
-            let mut args: Vec<&str> = message.content.split(" ").collect();
-
-            if let Some(cmd_name) = args.first() {
-                if let Some(cmd_name) = cmd_name.to_lowercase().strip_prefix(config::PREFIX) {
-                    let commands: Vec<&Box<dyn Command>> = (*COMMANDS).iter()
-                        .filter(|c| c.name() == cmd_name || c.aliases().contains(&cmd_name))
-                        .collect();
-
-                    if let Some(cmd) = commands.first() {
-                        if cmd.is_developer()
-                            && !(*config::DEVELOPERS).lock().unwrap().contains(message.author.id.as_u64())
-                        {
-                            message.channel_id.send_default_reply(
-                                &cx.http,
-                                "You do not have permissions to execute the command!",
-                                EmbedType::Failure,
-                            ).await;
-
-                            return;
-                        }
-
-                        args.remove(0);
-
-                        if let Err(e) = cmd.invoke(&cx, &message, &args).await {
-                            if let Some(limited_message) =
-                                strip_str(e.to_string().as_str(), 2000, true)
-                            {
-                                message.channel_id.send_default_reply(
-                                    &cx.http,
-                                    &limited_message,
-                                    EmbedType::Failure,
-                                ).await;
-                            }
-                        }
-                    }
+            run(&cx, &message).await;
+        }
+    }
+
+    async fn interaction_create(&self, cx: Context, interaction: Interaction) {
+        // Like the prefixed commands, slash commands only run in guilds
+        if let Interaction::ApplicationCommand(interaction) = interaction {
+            if interaction.guild_id.is_some() {
+                if let Err(e) = run_slash_command(&cx, &interaction).await {
+                    error!(error = %e, "Failed to run a slash command");
                 }
             }
         }
     }
 
-    async fn ready(&self, cx: Context, _data_about_bot: Ready) {
+    async fn ready(&self, cx: Context, data_about_bot: Ready) {
         cx.set_presence(
             Some(Activity::watching(format!("{}help", config::PREFIX))),
             OnlineStatus::DoNotDisturb,
         ).await;
 
+        metrics::record_connect();
+        availability::record(EventKind::Connect).await;
+
         if CONNECT_COUNT.fetch_add(1, Ordering::SeqCst) == 0 {
-            if let Ok(application_info) = cx.http.get_current_application_info().await {
-                let mut devs = config::DEVELOPERS.lock().unwrap();
+            #[cfg(feature = "utility-commands")]
+            tokio::spawn(weather::poll_alerts(cx.http.clone()));
+
+            match cx.http.get_current_application_info().await {
+                Ok(application_info) => {
+                    let mut devs = config::DEVELOPERS.lock().unwrap();
 
-                devs.push(*application_info.owner.id.as_u64());
+                    devs.push(*application_info.owner.id.as_u64());
+                }
+                Err(e) => error!("Failed to fetch the application's owner: {}", e),
+            }
+
+            let registered = match *config::SLASH_COMMANDS_GUILD {
+                Some(guild_id) => GuildId(guild_id)
+                    .set_application_commands(&cx.http, |commands| COMMANDS.slash_commands(commands))
+                    .await,
+                None => ApplicationCommand::set_global_application_commands(&cx.http, |commands| {
+                    COMMANDS.slash_commands(commands)
+                }).await,
+            };
+
+            if let Err(e) = registered {
+                error!("Failed to register the slash commands: {}", e);
             }
         }
 
-        info!("Connected to Discord!");
+        info!(
+            user = %data_about_bot.user.tag(),
+            guilds = data_about_bot.guilds.len(),
+            shard = ?data_about_bot.shard,
+            "Connected to Discord!",
+        );
+    }
+
+    async fn shard_stage_update(&self, _cx: Context, event: ShardStageUpdateEvent) {
+        // Resumed sessions never fire `ready`, so they are only noticed here
+        if event.new == ConnectionStage::Connected {
+            availability::record(EventKind::Connect).await;
+        } else if event.old == ConnectionStage::Connected {
+            warn!(shard = event.shard_id.0, stage = %event.new, "Lost the connection to Discord");
+
+            availability::record(EventKind::Disconnect).await;
+        }
     }
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
+/// Looks up the command the message starts with, following it into its subcommands, and dispatches it
+async fn run(cx: &Context, message: &Message) {
+    let mut args: Vec<&str> = message.content.split(" ").collect();
+
+    if let Some(cmd_name) = args.first() {
+        if let Some(cmd_name) = cmd_name.to_lowercase().strip_prefix(config::PREFIX) {
+            let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+
+            // Built-in commands come first, so a script or tag that a newer built-in command is named like
+            // stops running instead of shadowing it. Scripts and tags cannot share a name.
+            let (mut scripted, mut tag) = (None, None);
+            let cmd = COMMANDS.find(cmd_name)
+                .or_else(|| {
+                    scripted = scripting::find(guild_id, cmd_name);
+                    scripted.as_ref().map(|s| s as &dyn Command)
+                })
+                .or_else(|| {
+                    tag = tags::find(guild_id, cmd_name);
+                    tag.as_ref().map(|t| t as &dyn Command)
+                });
+
+            if let Some(cmd) = cmd {
+                args.remove(0);
+
+                let (path, args) = cmds::resolve(cmd, &args, true);
+                let name = cmds::qualified_name(&path);
+
+                let span = info_span!(
+                    "command",
+                    command = name.as_str(),
+                    guild = guild_id,
+                    channel = *message.channel_id.as_u64(),
+                    user = *message.author.id.as_u64(),
+                    latency_ms = field::Empty,
+                );
+
+                dispatch(cx, message, &path, args).instrument(span).await;
+            }
+        }
+    }
+}
+
+/// Answers the interaction with the command as it would have been typed and runs that answer as the author's
+/// message, so a slash command is checked, recorded and replied to exactly like its prefixed counterpart
+async fn run_slash_command(
+    cx: &Context,
+    interaction: &ApplicationCommandInteraction,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let cmd = match COMMANDS.find(&interaction.data.name) {
+        Some(cmd) => cmd,
+        None => return Ok(()),
+    };
+
+    let content = format!("{}{}", config::PREFIX, cmds::slash_words(cmd, &interaction.data.options).join(" "));
+    let echo = strip_str(&content, MESSAGE_LIMIT, true).unwrap_or_default();
+
+    interaction.create_interaction_response(&cx.http, |response| {
+        response
+            .kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|data| data.content(echo).allowed_mentions(|mentions| mentions.empty_parse()))
+    }).await?;
+
+    let mut message = interaction.get_interaction_response(&cx.http).await?;
+
+    message.author = interaction.user.clone();
+    message.guild_id = interaction.guild_id;
+    message.channel_id = interaction.channel_id;
+    message.content = content;
+
+    run(cx, &message).await;
+
+    Ok(())
+}
+
+/// Tells the author why the command has not been run
+async fn deny(cx: &Context, message: &Message, reason: String, options: &ReplyOptions) {
+    let reply = message.channel_id.send_default_reply(&cx.http, reason, EmbedType::Failure, options).await;
+
+    if let Err(e) = reply {
+        error!(error = %e, "Failed to send a reply");
+    }
+}
+
+/// Runs the command at the end of `path` within its span, recording how long it took and logging its outcome
+async fn dispatch(cx: &Context, message: &Message, path: &[&dyn Command], args: &[&str]) {
+    let options = ReplyOptions::for_message(message);
+    let cmd = path[path.len() - 1];
+    let name = cmds::qualified_name(path);
+
+    // Every guild names its scripts and tags differently, so they share a label instead of growing the metrics
+    let label = if cmd.category() == CommandCategory::Custom { "custom" } else { name.as_str() };
+
+    let record = |outcome, latency| {
+        metrics::record_command(label, outcome, latency);
+        analytics::record(
+            &name,
+            message.guild_id.map(|id| *id.as_u64()),
+            *message.author.id.as_u64(),
+            outcome,
+            latency,
+        );
+    };
+
+    if path.iter().any(|c| c.is_developer())
+        && !(*config::DEVELOPERS).lock().unwrap().contains(message.author.id.as_u64())
     {
-        std::env::set_var("RUST_LOG", "INFO");
+        warn!("A non-developer tried to run a developer command");
+
+        record(Outcome::Denied, Duration::ZERO);
+        deny(cx, message, tr!(options.language, "no-permissions"), &options).await;
+
+        return;
+    }
+
+    let permissions = path.iter().fold(Permissions::empty(), |permissions, c| permissions | c.permissions());
+
+    if !permissions.is_empty() && !has_permissions(cx, message, permissions).await {
+        warn!(?permissions, "A member without the required permissions tried to run a command");
+
+        record(Outcome::Denied, Duration::ZERO);
+
+        let reason = tr!(
+            options.language,
+            "missing-permissions",
+            permissions = permissions.get_permission_names().join(", "),
+        );
+
+        deny(cx, message, reason, &options).await;
+
+        return;
+    }
+
+    COMMAND_COUNT.fetch_add(1, Ordering::SeqCst);
+
+    let start = Instant::now();
+
+    // A group only gets here if neither a subcommand nor a default one has been found
+    let result = if cmd.subcommands().is_empty() {
+        cmd.invoke(cx, message, args).await
+    } else {
+        Err(tr!(options.language, "subcommand-missing", usages = cmds::usage_lines(path).join("\n")).into())
+    };
+    let latency = start.elapsed();
+
+    Span::current().record("latency_ms", latency.as_millis() as u64);
+
+    match result {
+        Ok(_) => {
+            info!("Command succeeded");
 
-        tracing_subscriber::fmt::init();
+            record(Outcome::Success, latency);
+        }
+        Err(e) => {
+            error!(error = %e, "Command failed");
+
+            record(Outcome::Failure, latency);
+
+            let reply = message.channel_id.send_default_long_reply(
+                &cx.http,
+                e.to_string(),
+                EmbedType::Failure,
+                &options,
+            ).await;
+
+            if let Err(e) = reply {
+                error!(error = %e, "Failed to send the error reply");
+            }
+        }
+    }
+}
 
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    // Kept alive until the bot exits so buffered file logs are flushed
+    let _log_guard = logging::init()?;
+
+    {
         lazy_static::initialize(&config::DISCORD_TOKEN);
         lazy_static::initialize(&START_TIME);
+        // Fails on clashing command names before connecting rather than on the first message
+        lazy_static::initialize(&COMMANDS);
 
         info!("Starting!");
     }
@@ -131,7 +346,28 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .event_handler(Handler)
         .await?;
 
-    client.start().await?;
+    client.data.write().await.insert::<ShardManagerContainer>(client.shard_manager.clone());
+
+    // Started before connecting so readiness probes fail until the shards are up
+    tokio::spawn(server::serve(client.cache_and_http.cache.clone()));
+    tokio::spawn(health::watch_shards(client.shard_manager.clone()));
+
+    availability::record_start().await;
+    tokio::spawn(availability::keep_alive());
+    tokio::spawn(analytics::flush_periodically());
+    tokio::spawn(tags::flush_periodically());
+
+    let result = client.start().await;
+
+    availability::record(EventKind::Stop).await;
+    analytics::flush().await;
+    tags::flush().await;
+
+    if let Err(e) = result {
+        error!("The client has stopped: {}", e);
+
+        return Err(e.into());
+    }
 
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,167 @@
+//! Prometheus metrics, served at `/metrics` on `HTTP_ADDR` when the bot is built with the `metrics` feature.
+//! Without the feature, recording is a no-op.
+//!
+//! To scrape them locally: `cargo run --features metrics` and `curl http://127.0.0.1:8787/metrics`.
+
+use serde::{Deserialize, Serialize};
+
+use std::{future::Future, time::{Duration, Instant}};
+
+#[cfg(feature = "metrics")]
+pub use exporter::{export, CONTENT_TYPE};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Success,
+    Failure,
+    /// The command was refused before it ran, e.g. because of missing permissions
+    Denied,
+}
+
+impl Outcome {
+    #[cfg(feature = "metrics")]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Success => "success",
+            Outcome::Failure => "failure",
+            Outcome::Denied => "denied",
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub fn record_command(command: &str, outcome: Outcome, latency: Duration) {
+    #[cfg(feature = "metrics")]
+    exporter::COMMANDS
+        .with_label_values(&[command, outcome.as_str()])
+        .observe(latency.as_secs_f64());
+}
+
+/// Called on every `ready` event, i.e. on the first connection to the gateway and on every reconnection
+pub fn record_connect() {
+    #[cfg(feature = "metrics")]
+    exporter::CONNECTS.inc();
+}
+
+#[cfg(feature = "general-commands")]
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub fn record_rest_latency(latency: Duration) {
+    #[cfg(feature = "metrics")]
+    exporter::REST_LATENCY.observe(latency.as_secs_f64());
+}
+
+/// Times a call to an external API and records whether it succeeded
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub async fn track_api_call<T, E>(api: &str, call: impl Future<Output = Result<T, E>>) -> Result<T, E> {
+    let start = Instant::now();
+    let result = call.await;
+
+    #[cfg(feature = "metrics")]
+    {
+        let outcome = if result.is_ok() { Outcome::Success } else { Outcome::Failure };
+
+        exporter::API_CALLS
+            .with_label_values(&[api, outcome.as_str()])
+            .observe(start.elapsed().as_secs_f64());
+    }
+
+    result
+}
+
+#[cfg(feature = "metrics")]
+mod exporter {
+    use lazy_static::lazy_static;
+
+    use prometheus::{
+        Encoder,
+        Histogram,
+        HistogramOpts,
+        HistogramVec,
+        IntCounter,
+        IntGauge,
+        Registry,
+        TextEncoder,
+    };
+
+    use serenity::cache::Cache;
+
+    pub const CONTENT_TYPE: &str = prometheus::TEXT_FORMAT;
+
+    lazy_static! {
+        static ref REGISTRY: Registry = Registry::new();
+
+        /// The count of each histogram doubles as the number of invocations
+        pub static ref COMMANDS: HistogramVec = register(HistogramVec::new(
+            HistogramOpts::new("bot_command_duration_seconds", "Time taken to run commands"),
+            &["command", "outcome"],
+        ).unwrap());
+
+        pub static ref CONNECTS: IntCounter = register(IntCounter::new(
+            "bot_gateway_connects_total",
+            "Connections to the Discord gateway, including reconnections",
+        ).unwrap());
+
+        pub static ref REST_LATENCY: Histogram = register(Histogram::with_opts(
+            HistogramOpts::new("bot_rest_latency_seconds", "Discord REST latency as measured by the ping command"),
+        ).unwrap());
+
+        pub static ref API_CALLS: HistogramVec = register(HistogramVec::new(
+            HistogramOpts::new("bot_api_call_duration_seconds", "Time taken by calls to external APIs"),
+            &["api", "outcome"],
+        ).unwrap());
+
+        static ref GUILDS: IntGauge = register(IntGauge::new("bot_guilds", "Guilds in the cache").unwrap());
+        static ref USERS: IntGauge = register(IntGauge::new("bot_users", "Users in the cache").unwrap());
+    }
+
+    fn register<M: prometheus::core::Collector + Clone + 'static>(metric: M) -> M {
+        REGISTRY.register(Box::new(metric.clone())).unwrap();
+
+        metric
+    }
+
+    /// Renders every metric in the Prometheus text format
+    pub fn render() -> String {
+        // Metrics are registered on first use, but should be exported from the start
+        lazy_static::initialize(&COMMANDS);
+        lazy_static::initialize(&CONNECTS);
+        lazy_static::initialize(&REST_LATENCY);
+        lazy_static::initialize(&API_CALLS);
+        lazy_static::initialize(&GUILDS);
+        lazy_static::initialize(&USERS);
+
+        let mut buffer = Vec::new();
+
+        TextEncoder::new().encode(&REGISTRY.gather(), &mut buffer).unwrap();
+
+        String::from_utf8(buffer).unwrap()
+    }
+
+    /// Refreshes the cache sizes, which are only read when they are scraped, and renders every metric
+    pub async fn export(cache: &Cache) -> String {
+        GUILDS.set(cache.guild_count().await as i64);
+        USERS.set(cache.user_count().await as i64);
+
+        render()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use crate::metrics::{record_command, Outcome};
+
+        use std::time::Duration;
+
+        #[test]
+        fn commands_are_exported() {
+            record_command("ping", Outcome::Success, Duration::from_millis(20));
+
+            let output = render();
+
+            assert!(output.contains("bot_command_duration_seconds_count{command=\"ping\",outcome=\"success\"}"));
+            assert!(output.contains("# TYPE bot_gateway_connects_total counter"));
+        }
+    }
+}
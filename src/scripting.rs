@@ -0,0 +1,413 @@
+//! Commands that server admins write in Rhai. Scripts cannot import modules or touch the host:
+//! they read their arguments, reply with the bot's embeds and send requests to the hosts on `SCRIPT_HTTP_ALLOWLIST`.
+
+use crate::{
+    cmds::{Command, CommandCategory},
+    config,
+    i18n::tr,
+    metrics,
+    storage::JsonStore,
+    utils::discord::{DefaultEmbedReplies, EmbedType, ReplyOptions},
+};
+
+use lazy_static::lazy_static;
+
+use reqwest::{redirect::Policy, Url};
+
+use rhai::{module_resolvers::DummyModuleResolver, Array, Dynamic, Engine, EvalAltResult, ImmutableString, Scope};
+
+use serde::{Deserialize, Serialize};
+
+use serenity::{
+    client::Context,
+    model::channel::Message,
+    async_trait,
+};
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    error::Error,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    runtime::Handle,
+    sync::{OwnedSemaphorePermit, Semaphore},
+};
+
+use tracing::debug;
+
+/// The longest name Discord allows for a slash command
+#[cfg(feature = "utility-commands")]
+const MAX_NAME_LENGTH: usize = 32;
+
+lazy_static! {
+    pub static ref SCRIPTS: JsonStore<HashMap<u64, Vec<Script>>> = JsonStore::open("scripts.json");
+
+    /// Never follows a redirect to a host that is not on the allowlist
+    static ref CLIENT: reqwest::Client = reqwest::Client::builder()
+        .redirect(Policy::custom(|attempt| {
+            if is_allowed(attempt.url(), &config::SCRIPT_HTTP_ALLOWLIST) {
+                attempt.follow()
+            } else {
+                attempt.stop()
+            }
+        }))
+        .build()
+        .unwrap();
+
+    /// Bounds the scripts each guild runs at once
+    static ref RUNS: Mutex<HashMap<u64, Arc<Semaphore>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Script {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub source: String,
+    pub author_id: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ScriptReply {
+    pub embed_type: EmbedType,
+    pub text: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ScriptError {
+    /// The script has run out of time or operations
+    TimedOut,
+    /// Described by Rhai along with where in the script it has happened
+    Failed(String),
+}
+
+/// A guild's scripted command, which the dispatcher and the help treat like a built-in one
+pub struct ScriptedCommand(pub Script);
+
+#[async_trait]
+impl Command for ScriptedCommand {
+    fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    fn description(&self) -> &str {
+        &self.0.description
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Custom
+    }
+
+    async fn invoke(
+        &self,
+        cx: &Context,
+        message: &Message,
+        args: &[&str],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let language = options.language;
+
+        let _permit = acquire_run(message.guild_id.map(|id| *id.as_u64()).unwrap_or_default())
+            .ok_or_else(|| tr!(language, "script-busy", limit = config::SCRIPT_MAX_CONCURRENT_PER_GUILD))?;
+
+        let replies = run(&self.0.source, args, format!("<@{}>", message.author.id)).await.map_err(|e| match e {
+            ScriptError::TimedOut => tr!(language, "script-timed-out"),
+            ScriptError::Failed(error) => tr!(language, "script-failed", error = error),
+        })?;
+
+        for reply in replies {
+            message.channel_id.send_default_reply(&cx.http, reply.text, reply.embed_type, &options).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reserves one of the guild's concurrent runs, which is given back once the permit is dropped
+fn acquire_run(guild_id: u64) -> Option<OwnedSemaphorePermit> {
+    RUNS.lock()
+        .unwrap()
+        .entry(guild_id)
+        .or_insert_with(|| Arc::new(Semaphore::new(config::SCRIPT_MAX_CONCURRENT_PER_GUILD)))
+        .clone()
+        .try_acquire_owned()
+        .ok()
+}
+
+/// Looks a guild's scripted command up by its name, `name` has to be lowercase
+pub fn find(guild_id: u64, name: &str) -> Option<ScriptedCommand> {
+    SCRIPTS.read()
+        .get(&guild_id)?
+        .iter()
+        .find(|s| s.name == name)
+        .cloned()
+        .map(ScriptedCommand)
+}
+
+#[cfg(any(feature = "general-commands", feature = "utility-commands"))]
+pub fn all(guild_id: u64) -> Vec<ScriptedCommand> {
+    SCRIPTS.read()
+        .get(&guild_id)
+        .map(|scripts| scripts.iter().cloned().map(ScriptedCommand).collect())
+        .unwrap_or_default()
+}
+
+/// Whether `name` could be typed as a command, which is also the rule for slash command names
+#[cfg(feature = "utility-commands")]
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LENGTH
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/// Splits what follows a scripted command's name into its description and its source.
+/// The source is either put in a code block after the description or, without a description, given as it is.
+#[cfg(feature = "utility-commands")]
+pub fn parse_definition(text: &str) -> (String, String) {
+    let text = text.trim();
+
+    match text.find("```") {
+        Some(start) => {
+            let block = &text[start + 3..];
+            let block = block.find("```").map_or(block, |end| &block[..end]);
+
+            // The first line of a code block may name its language, e.g. ```rhai
+            let source = match block.split_once('\n') {
+                Some((language, source)) if language.chars().all(|c| c.is_ascii_alphanumeric()) => source,
+                _ => block,
+            };
+
+            (text[..start].trim().to_owned(), source.trim().to_owned())
+        }
+        None => (String::new(), text.to_owned()),
+    }
+}
+
+/// Only HTTPS requests to the default port of a host on the allowlist are allowed
+pub fn is_allowed(url: &Url, allowlist: &[String]) -> bool {
+    url.scheme() == "https"
+        && url.port().is_none()
+        && url.host_str().map_or(false, |host| allowlist.iter().any(|allowed| allowed == host))
+}
+
+/// An engine with the limits every script runs with, but without the API for replying and HTTP requests
+fn sandbox() -> Engine {
+    let mut engine = Engine::new();
+
+    engine
+        // Otherwise `import` would read scripts from the host's file system
+        .set_module_resolver(DummyModuleResolver::new())
+        .set_max_operations(config::SCRIPT_MAX_OPERATIONS)
+        .set_max_variables(config::SCRIPT_MAX_VARIABLES)
+        .set_max_string_size(config::SCRIPT_MAX_STRING_SIZE)
+        .set_max_array_size(config::SCRIPT_MAX_COLLECTION_SIZE)
+        .set_max_map_size(config::SCRIPT_MAX_COLLECTION_SIZE)
+        .set_max_call_levels(16)
+        .set_max_expr_depths(32, 16)
+        .on_print(|text| debug!(text, "A script has printed"))
+        .on_debug(|text, _, position| debug!(text, %position, "A script has printed"));
+
+    engine
+}
+
+/// Checks a script for syntax errors before it is saved
+#[cfg(feature = "utility-commands")]
+pub fn compile(source: &str) -> Result<(), String> {
+    sandbox().compile(source).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Runs a script on a blocking thread with `args` and the mention of its `author`,
+/// returning the replies it has made once it has finished
+pub async fn run(source: &str, args: &[&str], author: String) -> Result<Vec<ScriptReply>, ScriptError> {
+    let source = source.to_owned();
+    let args = args.iter().map(|a| a.to_string()).collect::<Vec<String>>();
+    let handle = Handle::current();
+
+    let run = move || -> Result<Vec<ScriptReply>, ScriptError> {
+        let deadline = Instant::now() + config::SCRIPT_TIMEOUT;
+        let replies = Rc::new(RefCell::new(Vec::new()));
+
+        let mut engine = sandbox();
+
+        engine.on_progress(move |_| (Instant::now() >= deadline).then_some(Dynamic::UNIT));
+
+        let sent = replies.clone();
+
+        engine.register_fn("reply", move |text: ImmutableString| add_reply(&sent, EmbedType::Info, text));
+
+        let sent = replies.clone();
+
+        engine.register_fn("reply", move |embed_type: ImmutableString, text: ImmutableString| -> Result<(), Box<EvalAltResult>> {
+            let embed_type = EmbedType::parse(&embed_type).ok_or_else(|| format!("{} is not an embed type", embed_type))?;
+
+            add_reply(&sent, embed_type, text)
+        });
+
+        engine.register_fn("http_get", move |url: ImmutableString| http_get(&url, deadline, &handle));
+
+        let mut scope = Scope::new();
+
+        scope.push_constant("args", args.into_iter().map(Dynamic::from).collect::<Array>());
+        scope.push_constant("author", author);
+
+        engine.run_with_scope(&mut scope, &source).map_err(|e| match *e {
+            EvalAltResult::ErrorTerminated(..) | EvalAltResult::ErrorTooManyOperations(..) => ScriptError::TimedOut,
+            e => ScriptError::Failed(e.to_string()),
+        })?;
+
+        Ok(replies.take())
+    };
+
+    tokio::task::spawn_blocking(run).await.unwrap_or_else(|e| Err(ScriptError::Failed(e.to_string())))
+}
+
+fn add_reply(replies: &RefCell<Vec<ScriptReply>>, embed_type: EmbedType, text: ImmutableString) -> Result<(), Box<EvalAltResult>> {
+    let mut replies = replies.borrow_mut();
+
+    if text.trim().is_empty() {
+        return Err("A reply cannot be empty".into());
+    }
+
+    if replies.len() >= config::SCRIPT_MAX_REPLIES {
+        return Err(format!("A script cannot reply more than {} times", config::SCRIPT_MAX_REPLIES).into());
+    }
+
+    replies.push(ScriptReply {
+        embed_type,
+        text: text.to_string(),
+    });
+
+    Ok(())
+}
+
+/// Sends a request for a script within the time it has left, which blocks the script until the response is read
+fn http_get(url: &str, deadline: Instant, handle: &Handle) -> Result<String, Box<EvalAltResult>> {
+    let url = Url::parse(url).map_err(|e| format!("{} is not a valid URL: {}", url, e))?;
+
+    if !is_allowed(&url, &config::SCRIPT_HTTP_ALLOWLIST) {
+        return Err(format!("{} is not on the HTTP allowlist", url.host_str().unwrap_or_default()).into());
+    }
+
+    let timeout = deadline.saturating_duration_since(Instant::now());
+
+    handle.block_on(metrics::track_api_call("script", fetch(url, timeout))).map_err(|e| e.to_string().into())
+}
+
+async fn fetch(url: Url, timeout: Duration) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let mut response = CLIENT.get(url).timeout(timeout).send().await?.error_for_status()?;
+    let mut body = Vec::new();
+
+    // Read in chunks so that an endless response is cut off as soon as no script could hold it
+    while let Some(chunk) = response.chunk().await? {
+        body.extend_from_slice(&chunk);
+
+        if body.len() > config::SCRIPT_MAX_STRING_SIZE {
+            return Err("The response is too large".into());
+        }
+    }
+
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_runs_are_limited_per_guild() {
+        let permits = (0..config::SCRIPT_MAX_CONCURRENT_PER_GUILD)
+            .map(|_| acquire_run(1).unwrap())
+            .collect::<Vec<_>>();
+        assert!(acquire_run(1).is_none());
+        assert!(acquire_run(2).is_some());
+
+        drop(permits);
+        assert!(acquire_run(1).is_some());
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[test]
+    fn definitions_are_split_into_descriptions_and_sources() {
+        assert_eq!(
+            parse_definition("Greets the author\n```rhai\nreply(`Hi, ${author}!`);\n```"),
+            (String::from("Greets the author"), String::from("reply(`Hi, ${author}!`);")),
+        );
+        assert_eq!(
+            parse_definition("```\nreply(\"a\");\nreply(\"b\");```"),
+            (String::new(), String::from("reply(\"a\");\nreply(\"b\");")),
+        );
+        assert_eq!(parse_definition("```reply(\"Hi\")```"), (String::new(), String::from("reply(\"Hi\")")));
+        assert_eq!(parse_definition(" reply(\"Hi\") "), (String::new(), String::from("reply(\"Hi\")")));
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[test]
+    fn names_are_validated() {
+        assert!(is_valid_name("greet"));
+        assert!(is_valid_name("dice_roll-2"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("Greet"));
+        assert!(!is_valid_name("<greet"));
+        assert!(!is_valid_name(&"a".repeat(MAX_NAME_LENGTH + 1)));
+    }
+
+    #[test]
+    fn only_allowlisted_hosts_can_be_requested() {
+        let allowlist = vec![String::from("api.github.com")];
+        let allowed = |url: &str| is_allowed(&Url::parse(url).unwrap(), &allowlist);
+
+        assert!(allowed("https://api.github.com/repos/rust-lang/rust"));
+        assert!(allowed("https://API.GitHub.com:443/"));
+        assert!(!allowed("http://api.github.com/"));
+        assert!(!allowed("https://api.github.com:8443/"));
+        assert!(!allowed("https://github.com/"));
+        assert!(!allowed("https://api.github.com.evil.example/"));
+        assert!(!allowed("https://127.0.0.1/"));
+    }
+
+    #[tokio::test]
+    async fn scripts_reply_with_their_arguments() {
+        let source = r#"
+            reply(`Hi, ${author}!`);
+            reply("warning", args.len().to_string() + " " + args[0]);
+        "#;
+
+        assert_eq!(
+            run(source, &["two", "args"], String::from("<@1>")).await,
+            Ok(vec![
+                ScriptReply {
+                    embed_type: EmbedType::Info,
+                    text: String::from("Hi, <@1>!"),
+                },
+                ScriptReply {
+                    embed_type: EmbedType::Warning,
+                    text: String::from("2 two"),
+                },
+            ]),
+        );
+    }
+
+    #[tokio::test]
+    async fn scripts_are_limited() {
+        let failed = |result| matches!(result, Err(ScriptError::Failed(_)));
+
+        assert_eq!(run("loop {}", &[], String::new()).await, Err(ScriptError::TimedOut));
+        assert!(failed(run("let s = \"a\"; loop { s += s; }", &[], String::new()).await));
+        assert!(failed(run("for i in 0..10 { reply(\"spam\"); }", &[], String::new()).await));
+        assert!(failed(run("reply(\"rainbow\", \"Hi\");", &[], String::new()).await));
+        assert!(failed(run("import \"secrets\" as s;", &[], String::new()).await));
+        assert!(failed(run("http_get(\"https://example.com/\");", &[], String::new()).await));
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[test]
+    fn syntax_errors_are_caught_before_saving() {
+        assert!(compile("reply(\"Hi\");").is_ok());
+        assert!(compile("reply(\"Hi\"").is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! A small HTTP server for orchestration and monitoring:
+//!
+//! - `/healthz` always answers with 200 as long as the bot is running
+//! - `/readyz` answers with 503 until every shard is connected and the storage is writable
+//! - `/metrics` serves Prometheus metrics if the bot is built with the `metrics` feature
+//!
+//! Both health checks report shard states, heartbeats and uptime as JSON, and `/readyz` also reports the storage
+//! status. Setting `HTTP_ADDR` to `off` disables the server.
+
+use crate::{config, health};
+
+#[cfg(feature = "metrics")]
+use crate::metrics;
+
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body,
+    Method,
+    Request,
+    Response,
+    Server,
+    StatusCode,
+};
+
+use serenity::cache::Cache;
+
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+use tracing::{error, info};
+
+fn response(status: StatusCode, content_type: &str, body: impl Into<Body>) -> Response<Body> {
+    let mut response = Response::new(body.into());
+
+    *response.status_mut() = status;
+    response.headers_mut().insert(CONTENT_TYPE, content_type.parse().unwrap());
+
+    response
+}
+
+fn health_response(status: StatusCode, report: &health::HealthReport) -> Response<Body> {
+    match serde_json::to_vec(report) {
+        Ok(json) => response(status, "application/json", json),
+        Err(e) => response(StatusCode::INTERNAL_SERVER_ERROR, "text/plain", e.to_string()),
+    }
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+async fn handle(request: Request<Body>, cache: Arc<Cache>) -> Result<Response<Body>, Infallible> {
+    if request.method() != Method::GET {
+        return Ok(response(StatusCode::METHOD_NOT_ALLOWED, "text/plain", "Method Not Allowed"));
+    }
+
+    let response = match request.uri().path() {
+        "/healthz" => health_response(StatusCode::OK, &health::liveness()),
+        "/readyz" => {
+            let report = health::readiness().await;
+            let status = if report.ready == Some(true) { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+
+            health_response(status, &report)
+        }
+        #[cfg(feature = "metrics")]
+        "/metrics" => response(StatusCode::OK, metrics::CONTENT_TYPE, metrics::export(&cache).await),
+        _ => response(StatusCode::NOT_FOUND, "text/plain", "Not Found"),
+    };
+
+    Ok(response)
+}
+
+pub async fn serve(cache: Arc<Cache>) {
+    let addr = match config::HTTP_ADDR.as_deref() {
+        Some(addr) => addr,
+        None => {
+            info!("The HTTP server is disabled");
+
+            return;
+        }
+    };
+
+    let addr: SocketAddr = match addr.parse() {
+        Ok(addr) => addr,
+        Err(e) => {
+            error!("Invalid HTTP address {}: {}", addr, e);
+
+            return;
+        }
+    };
+
+    let make_service = make_service_fn(move |_| {
+        let cache = cache.clone();
+
+        async move { Ok::<_, Infallible>(service_fn(move |request| handle(request, cache.clone()))) }
+    });
+
+    let server = match Server::try_bind(&addr) {
+        Ok(builder) => builder.serve(make_service),
+        Err(e) => {
+            error!("Failed to bind the HTTP server to {}: {}", addr, e);
+
+            return;
+        }
+    };
+
+    info!("Serving health checks at http://{}", addr);
+
+    if let Err(e) = server.await {
+        error!("The HTTP server has stopped: {}", e);
+    }
+}
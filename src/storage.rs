@@ -0,0 +1,254 @@
+#[cfg(not(test))]
+use crate::config;
+
+use chrono::Utc;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    io,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+};
+
+use tracing::error;
+
+/// A JSON file in the bot's data directory whose content is kept in memory
+/// and written back on every update, or on the next flush for frequent changes
+pub struct JsonStore<T> {
+    /// Only missing in tests, whose stores are kept in memory
+    path: Option<PathBuf>,
+    data: Mutex<T>,
+    /// A hash of what the file holds, so that updates changing nothing are not written.
+    /// Writers also hold it while writing, so an older state can never overwrite a newer one.
+    written: Mutex<Option<u64>>,
+    /// Set if the file exists but could neither be read nor moved aside, in which case it is never overwritten
+    read_only: bool,
+}
+
+impl<T: Serialize + DeserializeOwned + Default + Send + 'static> JsonStore<T> {
+    #[cfg(not(test))]
+    pub fn open(file_name: &str) -> Self {
+        Self::at(config::DATA_DIR.join(file_name))
+    }
+
+    /// Tests keep their stores in memory, as they run in parallel and must neither share files nor leave them behind
+    #[cfg(test)]
+    pub fn open(_file_name: &str) -> Self {
+        Self {
+            path: None,
+            data: Mutex::new(T::default()),
+            written: Mutex::new(None),
+            read_only: false,
+        }
+    }
+
+    fn at(path: PathBuf) -> Self {
+        let mut written = None;
+        let mut read_only = false;
+
+        let data = match fs::read(&path) {
+            Ok(bytes) => match serde_json::from_slice(&bytes) {
+                Ok(data) => {
+                    written = Some(hash(&bytes));
+
+                    data
+                }
+                Err(e) => {
+                    // Starting over in place would lose the data for good with the next update
+                    let backup = path.with_extension(format!("json.{}.bak", Utc::now().timestamp()));
+
+                    match fs::rename(&path, &backup) {
+                        Ok(_) => error!(
+                            error = %e,
+                            path = %path.display(),
+                            backup = %backup.display(),
+                            "Failed to parse a store, so it has been moved aside and starts over empty",
+                        ),
+                        Err(rename_error) => {
+                            error!(
+                                error = %e,
+                                rename_error = %rename_error,
+                                path = %path.display(),
+                                "Failed to parse a store or to move it aside, so it will not be written",
+                            );
+
+                            read_only = true;
+                        }
+                    }
+
+                    T::default()
+                }
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => T::default(),
+            Err(e) => {
+                error!(error = %e, path = %path.display(), "Failed to read a store, so it will not be written");
+
+                read_only = true;
+
+                T::default()
+            }
+        };
+
+        Self {
+            path: Some(path),
+            data: Mutex::new(data),
+            written: Mutex::new(written),
+            read_only,
+        }
+    }
+
+    pub fn read(&self) -> MutexGuard<'_, T> {
+        self.data.lock().unwrap()
+    }
+
+    /// Changes the data and writes it back on a blocking thread
+    pub async fn update<R>(&'static self, f: impl FnOnce(&mut T) -> R) -> io::Result<R> {
+        let output = self.modify(f);
+
+        self.flush().await?;
+
+        Ok(output)
+    }
+
+    /// Changes the data in memory only, leaving it to the next flush to write it
+    pub fn modify<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.data.lock().unwrap())
+    }
+
+    /// Writes the data back on a blocking thread
+    pub async fn flush(&'static self) -> io::Result<()> {
+        tokio::task::spawn_blocking(move || self.write())
+            .await
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+    }
+
+    /// Writes the current data unless the file already holds it
+    fn write(&self) -> io::Result<()> {
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return Ok(()),
+        };
+
+        if self.read_only {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("{} could not be read, so it is not overwritten", path.display()),
+            ));
+        }
+
+        let mut written = self.written.lock().unwrap();
+        let bytes = serde_json::to_vec_pretty(&*self.read())?;
+        let bytes_hash = hash(&bytes);
+
+        if *written == Some(bytes_hash) {
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Writing to a temporary file first so a crash never leaves a half-written store behind
+        let temp_path = path.with_extension("json.tmp");
+
+        fs::write(&temp_path, &bytes)?;
+        fs::rename(&temp_path, path)?;
+
+        *written = Some(bytes_hash);
+
+        Ok(())
+    }
+}
+
+fn hash(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{collections::HashMap, env, process};
+
+    /// A directory of its own for each test, removed when the test ends
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = env::temp_dir().join(format!("discord-test-rs-bot-storage-{}-{}", process::id(), name));
+
+            fs::create_dir_all(&path).unwrap();
+
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn store(path: PathBuf) -> &'static JsonStore<HashMap<String, u32>> {
+        Box::leak(Box::new(JsonStore::at(path)))
+    }
+
+    #[tokio::test]
+    async fn unparsable_files_are_moved_aside() {
+        let dir = TempDir::new("unparsable");
+        let path = dir.0.join("store.json");
+
+        fs::write(&path, "{ not json").unwrap();
+
+        let store = store(path.clone());
+
+        assert!(store.read().is_empty());
+
+        store.update(|data| data.insert(String::from("a"), 1)).await.unwrap();
+
+        let backups = fs::read_dir(&dir.0).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|p| p.to_string_lossy().ends_with(".bak"))
+            .collect::<Vec<PathBuf>>();
+
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read_to_string(&backups[0]).unwrap(), "{ not json");
+        assert_eq!(JsonStore::<HashMap<String, u32>>::at(path).read()["a"], 1);
+    }
+
+    #[tokio::test]
+    async fn modifications_are_written_when_flushed() {
+        let dir = TempDir::new("modified");
+        let path = dir.0.join("store.json");
+        let store = store(path.clone());
+
+        store.modify(|data| data.insert(String::from("a"), 1));
+        assert!(!path.exists());
+
+        store.flush().await.unwrap();
+        assert_eq!(JsonStore::<HashMap<String, u32>>::at(path).read()["a"], 1);
+    }
+
+    #[tokio::test]
+    async fn unchanged_data_is_not_written() {
+        let dir = TempDir::new("unchanged");
+        let path = dir.0.join("store.json");
+        let store = store(path.clone());
+
+        store.update(|data| data.insert(String::from("a"), 1)).await.unwrap();
+        fs::remove_file(&path).unwrap();
+
+        store.update(|data| data.insert(String::from("a"), 1)).await.unwrap();
+        assert!(!path.exists());
+
+        store.update(|data| data.insert(String::from("a"), 2)).await.unwrap();
+        assert!(path.exists());
+    }
+}
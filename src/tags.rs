@@ -0,0 +1,209 @@
+//! Text commands that a guild's moderators create, e.g. `<tag add rules Be nice!` makes `<rules` reply with "Be nice!"
+
+use crate::{
+    cmds::{Command, CommandCategory},
+    config,
+    storage::JsonStore,
+    utils::discord::{DefaultEmbedReplies, EmbedType, ReplyOptions},
+};
+
+use lazy_static::lazy_static;
+
+use serde::{Deserialize, Serialize};
+
+use serenity::{
+    client::Context,
+    model::channel::Message,
+    async_trait,
+};
+
+use std::{collections::HashMap, error::Error};
+
+use tracing::error;
+
+lazy_static! {
+    pub static ref TAGS: JsonStore<HashMap<u64, Vec<Tag>>> = JsonStore::open("tags.json");
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Tag {
+    pub name: String,
+    pub content: String,
+    /// Whoever has created the tag, who can edit or delete it without the Manage Server permission
+    pub owner_id: u64,
+    #[serde(default)]
+    pub uses: u64,
+    /// Unix time in seconds
+    pub created: i64,
+}
+
+/// What the placeholders of a tag are replaced with
+pub struct Variables<'a> {
+    /// `{user}`, a mention of whoever has run the tag
+    pub user: String,
+    /// `{user.name}`
+    pub user_name: &'a str,
+    /// `{channel}`, a mention of the channel the tag has been run in
+    pub channel: String,
+    /// `{args}` for all the arguments, `{args.1}` for the first one and so on
+    pub args: &'a [&'a str],
+}
+
+/// A guild's tag, which the dispatcher runs like a built-in command
+pub struct TagCommand(pub Tag);
+
+#[async_trait]
+impl Command for TagCommand {
+    fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    fn description(&self) -> &str {
+        ""
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Custom
+    }
+
+    async fn invoke(
+        &self,
+        cx: &Context,
+        message: &Message,
+        args: &[&str],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let options = ReplyOptions::for_message(message);
+        let guild_id = message.guild_id.map(|id| *id.as_u64()).unwrap_or_default();
+
+        let variables = Variables {
+            user: format!("<@{}>", message.author.id),
+            user_name: &message.author.name,
+            channel: format!("<#{}>", message.channel_id),
+            args,
+        };
+
+        record_use(guild_id, &self.0.name);
+
+        message.channel_id.send_default_long_reply(
+            &cx.http,
+            render(&self.0.content, &variables),
+            EmbedType::Info,
+            &options,
+        ).await?;
+
+        Ok(())
+    }
+}
+
+/// Looks a guild's tag up by its name, `name` has to be lowercase
+pub fn find(guild_id: u64, name: &str) -> Option<TagCommand> {
+    TAGS.read()
+        .get(&guild_id)?
+        .iter()
+        .find(|t| t.name == name)
+        .cloned()
+        .map(TagCommand)
+}
+
+/// Counts the use in memory, it is written by `flush`
+fn record_use(guild_id: u64, name: &str) {
+    TAGS.modify(|tags| {
+        let tag = tags.get_mut(&guild_id).and_then(|tags| tags.iter_mut().find(|t| t.name == name));
+
+        if let Some(tag) = tag {
+            tag.uses += 1;
+        }
+    });
+}
+
+/// Writes the uses counted since the last flush
+pub async fn flush() {
+    if let Err(e) = TAGS.flush().await {
+        error!(error = %e, "Failed to save the tag uses");
+    }
+}
+
+pub async fn flush_periodically() {
+    let mut interval = tokio::time::interval(config::TAG_USES_FLUSH_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        flush().await;
+    }
+}
+
+/// Replaces the placeholders in a tag's content. Anything else in braces is kept as it is,
+/// while the arguments that have not been given are left empty.
+pub fn render(content: &str, variables: &Variables) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = match rest.find('}') {
+            Some(end) => end,
+            None => break,
+        };
+
+        let value = match &rest[1..end] {
+            "user" => Some(variables.user.clone()),
+            "user.name" => Some(variables.user_name.to_owned()),
+            "channel" => Some(variables.channel.clone()),
+            "args" => Some(variables.args.join(" ")),
+            placeholder => placeholder.strip_prefix("args.")
+                .and_then(|n| n.parse::<usize>().ok())
+                .map(|n| n.checked_sub(1).and_then(|i| variables.args.get(i)).unwrap_or(&"").to_string()),
+        };
+
+        match value {
+            Some(value) => {
+                output.push_str(&value);
+                rest = &rest[end + 1..];
+            }
+            // Not a placeholder, e.g. a brace in a code snippet
+            None => {
+                output.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables<'a>(args: &'a [&'a str]) -> Variables<'a> {
+        Variables {
+            user: String::from("<@300>"),
+            user_name: "Tester",
+            channel: String::from("<#200>"),
+            args,
+        }
+    }
+
+    #[test]
+    fn placeholders_are_replaced() {
+        let variables = variables(&["first", "second"]);
+
+        assert_eq!(render("Welcome to {channel}, {user}!", &variables), "Welcome to <#200>, <@300>!");
+        assert_eq!(render("{user.name} says: {args}", &variables), "Tester says: first second");
+        assert_eq!(render("{args.2}, {args.1}, {args.3}{args.0}", &variables), "second, first, ");
+        assert_eq!(render("{unknown} {\"json\": {args.1}}", &variables), "{unknown} {\"json\": first}");
+    }
+
+    #[test]
+    fn other_braces_are_kept() {
+        let variables = variables(&[]);
+
+        assert_eq!(render("fn main() { println!(\"{}\"); }", &variables), "fn main() { println!(\"{}\"); }");
+        assert_eq!(render("{user", &variables), "{user");
+        assert_eq!(render("}{ {user}", &variables), "}{ <@300>");
+    }
+}
@@ -0,0 +1,267 @@
+//! An offline harness for running commands in tests. Every REST request goes to a local fake Discord server,
+//! which records it and answers with just enough for the commands to carry on, and every command is run
+//! from a simulated message or interaction event in a guild, exactly as `Handler` would receive it.
+//!
+//! Tests run in parallel and share the stores, so every harness has a guild, channel and user of its own.
+
+// Builds without some of the command categories leave out the tests that use the rest of the helpers
+#![cfg_attr(not(all(feature = "general-commands", feature = "utility-commands")), allow(dead_code))]
+
+use crate::Handler;
+
+use futures::channel::mpsc::{self, UnboundedReceiver};
+
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body,
+    Method,
+    Request,
+    Response,
+    Server,
+    StatusCode,
+};
+
+use serde_json::{json, Value};
+
+use serenity::{
+    cache::Cache,
+    client::{bridge::gateway::ShardMessenger, Context},
+    gateway::InterMessage,
+    http::HttpBuilder,
+    model::{channel::Message, interactions::Interaction},
+    prelude::{EventHandler, RwLock, TypeMap},
+};
+
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+        Mutex,
+    },
+};
+
+pub const BOT_ID: u64 = 400;
+
+/// Hands out the IDs of messages as well as the guilds, channels and users of the harnesses
+static NEXT_ID: AtomicU64 = AtomicU64::new(1000);
+
+fn next_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::SeqCst)
+}
+
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    pub method: Method,
+    /// The path without the API version, e.g. `/channels/200/messages`
+    pub path: String,
+    /// `Null` for empty and multipart bodies
+    pub body: Value,
+}
+
+impl RecordedRequest {
+    /// The embeds of a sent or edited message
+    pub fn embeds(&self) -> Vec<Value> {
+        match (&self.body["embeds"], &self.body["embed"]) {
+            (Value::Array(embeds), _) => embeds.clone(),
+            (_, embed @ Value::Object(_)) => vec![embed.clone()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+pub struct Harness {
+    pub cx: Context,
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub user_id: u64,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    /// Keeps the shard's channel open so that commands can still talk to the "gateway", e.g. to await reactions
+    _shard: UnboundedReceiver<InterMessage>,
+}
+
+impl Harness {
+    pub async fn new() -> Self {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let addr = serve(requests.clone());
+
+        let http = HttpBuilder::new("Bot test")
+            .proxy(format!("http://{}", addr))
+            .expect("The fake server's address is not a valid proxy")
+            .ratelimiter_disabled(true)
+            .application_id(BOT_ID)
+            .build();
+
+        let (sender, receiver) = mpsc::unbounded();
+
+        let cx = Context {
+            data: Arc::new(RwLock::new(TypeMap::new())),
+            shard: ShardMessenger::new(sender),
+            shard_id: 0,
+            http: Arc::new(http),
+            cache: Arc::new(Cache::new()),
+        };
+
+        Self {
+            cx,
+            guild_id: next_id(),
+            channel_id: next_id(),
+            user_id: next_id(),
+            requests,
+            _shard: receiver,
+        }
+    }
+
+    /// Sends a message from the test user to the guild channel and waits until it has been handled
+    pub async fn send(&self, content: &str) {
+        let author = user_json(self.user_id, "Tester", false);
+        let message = serde_json::from_value::<Message>(message_json(self.channel_id, Some(self.guild_id), content, &author))
+            .expect("The simulated message is invalid");
+
+        Handler.message(self.cx.clone(), message).await;
+    }
+
+    /// Runs a slash command from the test user in the guild channel and waits until it has been handled,
+    /// `options` being the command's options as Discord sends them
+    pub async fn slash(&self, name: &str, options: Value) {
+        let interaction = json!({
+            "id": next_id().to_string(),
+            "application_id": BOT_ID.to_string(),
+            "type": 2,
+            "data": { "id": next_id().to_string(), "name": name, "type": 1, "options": options },
+            "guild_id": self.guild_id.to_string(),
+            "channel_id": self.channel_id.to_string(),
+            "user": user_json(self.user_id, "Tester", false),
+            "token": "token",
+            "version": 1,
+            "locale": "en-US",
+        });
+        let interaction = serde_json::from_value::<Interaction>(interaction).expect("The simulated interaction is invalid");
+
+        Handler.interaction_create(self.cx.clone(), interaction).await;
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Every embed sent or edited so far, in order
+    pub fn embeds(&self) -> Vec<Value> {
+        self.requests().iter().flat_map(RecordedRequest::embeds).collect()
+    }
+}
+
+fn user_json(id: u64, name: &str, bot: bool) -> Value {
+    json!({
+        "id": id.to_string(),
+        "username": name,
+        "discriminator": "0001",
+        "avatar": null,
+        "bot": bot,
+        "public_flags": 0,
+    })
+}
+
+fn current_user_json() -> Value {
+    let mut user = user_json(BOT_ID, "Test Bot", true);
+
+    user["mfa_enabled"] = json!(false);
+    user["verified"] = json!(true);
+    user["email"] = json!(null);
+
+    user
+}
+
+fn message_json(channel_id: u64, guild_id: Option<u64>, content: &str, author: &Value) -> Value {
+    json!({
+        "id": next_id().to_string(),
+        "channel_id": channel_id.to_string(),
+        "guild_id": guild_id.map(|id| id.to_string()),
+        "author": author,
+        "content": content,
+        "timestamp": "2021-01-01T00:00:00+00:00",
+        "edited_timestamp": null,
+        "tts": false,
+        "mention_everyone": false,
+        "mentions": [],
+        "mention_roles": [],
+        "attachments": [],
+        "embeds": [],
+        "reactions": [],
+        "pinned": false,
+        "type": 0,
+    })
+}
+
+fn json_response(status: StatusCode, body: Value) -> Response<Body> {
+    let mut response = Response::new(Body::from(body.to_string()));
+
+    *response.status_mut() = status;
+    response.headers_mut().insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+    response
+}
+
+async fn handle(request: Request<Body>, requests: Arc<Mutex<Vec<RecordedRequest>>>) -> Result<Response<Body>, Infallible> {
+    let method = request.method().clone();
+
+    // Serenity requests paths such as `/api/v8/channels/200/messages`
+    let path = request.uri().path()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .skip(2)
+        .fold(String::new(), |path, segment| format!("{}/{}", path, segment));
+
+    let bytes = hyper::body::to_bytes(request.into_body()).await.unwrap_or_default();
+    let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+
+    requests.lock().unwrap().push(RecordedRequest {
+        method: method.clone(),
+        path: path.clone(),
+        body: body.clone(),
+    });
+
+    let segments = path.split('/').skip(1).collect::<Vec<&str>>();
+
+    let response = match (&method, segments.as_slice()) {
+        (&Method::GET, ["users", "@me"]) => json_response(StatusCode::OK, current_user_json()),
+        (&Method::POST, ["channels", channel_id, "messages"]) | (&Method::PATCH, ["channels", channel_id, "messages", _]) => {
+            let channel_id = channel_id.parse().unwrap_or_default();
+            let content = body["content"].as_str().unwrap_or_default();
+
+            json_response(StatusCode::OK, message_json(channel_id, None, content, &current_user_json()))
+        }
+        // The answer to an interaction, which slash commands fetch to run as a message
+        (&Method::GET, ["webhooks", _, _, "messages", "@original"]) => {
+            json_response(StatusCode::OK, message_json(next_id(), None, "", &current_user_json()))
+        }
+        (&Method::PUT, _) | (&Method::DELETE, _) | (&Method::POST, ["interactions", _, _, "callback"]) => {
+            let mut response = Response::new(Body::empty());
+
+            *response.status_mut() = StatusCode::NO_CONTENT;
+
+            response
+        }
+        _ => json_response(StatusCode::NOT_FOUND, json!({ "code": 0, "message": "404: Not Found" })),
+    };
+
+    Ok(response)
+}
+
+/// Starts the fake Discord server on a random port
+fn serve(requests: Arc<Mutex<Vec<RecordedRequest>>>) -> SocketAddr {
+    let make_service = make_service_fn(move |_| {
+        let requests = requests.clone();
+
+        async move { Ok::<_, Infallible>(service_fn(move |request| handle(request, requests.clone()))) }
+    });
+
+    let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+    let addr = server.local_addr();
+
+    tokio::spawn(server);
+
+    addr
+}
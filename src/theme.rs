@@ -0,0 +1,193 @@
+use crate::{
+    i18n::Language,
+    storage::JsonStore,
+    utils::discord::EmbedType,
+};
+
+use chrono::Utc;
+
+use lazy_static::lazy_static;
+
+#[cfg(feature = "utility-commands")]
+use reqwest::Url;
+
+use serde::{Deserialize, Serialize};
+
+use serenity::{
+    builder::{CreateEmbed, CreateEmbedAuthor},
+    utils::Color,
+};
+
+use std::collections::HashMap;
+
+lazy_static! {
+    pub static ref GUILD_THEMES: JsonStore<HashMap<u64, Theme>> = JsonStore::open("guild_themes.json");
+}
+
+/// How a guild wants the bot's embeds to look. Anything left unset falls back to the bot's defaults.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub colors: HashMap<EmbedType, u32>,
+    /// Either an emoji put in front of the embed title or an image URL shown next to it
+    pub icons: HashMap<EmbedType, String>,
+    pub footer: Option<String>,
+    pub timestamp: bool,
+    /// Whether replies reference the message that invoked the command
+    pub reply: bool,
+}
+
+impl Theme {
+    pub fn color(&self, embed_type: EmbedType) -> Color {
+        self.colors.get(&embed_type)
+            .map(|color| Color::new(*color))
+            .unwrap_or_else(|| embed_type.get_color())
+    }
+
+    pub fn icon(&self, embed_type: EmbedType) -> Option<&str> {
+        self.icons.get(&embed_type).map(String::as_str)
+    }
+
+    /// Applies the color, footer and timestamp of the embed type.
+    /// The footer may still be replaced afterwards, e.g. by page numbers.
+    pub fn apply<'a>(&self, embed: &'a mut CreateEmbed, embed_type: EmbedType) -> &'a mut CreateEmbed {
+        embed.color(self.color(embed_type));
+
+        if let Some(ref footer) = self.footer {
+            embed.footer(|f| f.text(footer));
+        }
+
+        if self.timestamp {
+            embed.timestamp(&Utc::now());
+        }
+
+        embed
+    }
+
+    /// Puts the localized title of the embed type into the author field along with its icon
+    pub fn apply_author<'a>(
+        &self,
+        embed: &'a mut CreateEmbed,
+        embed_type: EmbedType,
+        language: Language,
+    ) -> &'a mut CreateEmbed {
+        self.author(embed, embed_type, embed_type.get_title(language), |a| a)
+    }
+
+    /// Sets the author field, decorated with the embed type's icon.
+    /// An icon URL set by the theme takes precedence over the one set by `f`.
+    pub fn author<'a, F>(
+        &self,
+        embed: &'a mut CreateEmbed,
+        embed_type: EmbedType,
+        name: impl ToString,
+        f: F,
+    ) -> &'a mut CreateEmbed
+    where
+        F: FnOnce(&mut CreateEmbedAuthor) -> &mut CreateEmbedAuthor,
+    {
+        let name = match self.icon(embed_type) {
+            Some(emoji) if !is_url(emoji) => format!("{} {}", emoji, name.to_string()),
+            _ => name.to_string(),
+        };
+
+        embed.author(|a| {
+            f(a.name(name));
+
+            match self.icon(embed_type) {
+                Some(url) if is_url(url) => a.icon_url(url),
+                _ => a,
+            }
+        })
+    }
+}
+
+pub fn is_url(icon: &str) -> bool {
+    icon.starts_with("https://")
+}
+
+pub fn get_theme(guild_id: Option<u64>) -> Theme {
+    guild_id
+        .and_then(|id| GUILD_THEMES.read().get(&id).cloned())
+        .unwrap_or_default()
+}
+
+/// Anything that looks like a URL has to be a well-formed https one, as Discord rejects the whole embed otherwise,
+/// while everything else is shown as an emoji
+#[cfg(feature = "utility-commands")]
+pub fn is_valid_icon(icon: &str) -> bool {
+    if !icon.contains("://") {
+        return !icon.is_empty();
+    }
+
+    is_url(icon)
+        && !icon.contains(char::is_whitespace)
+        && matches!(Url::parse(icon), Ok(url) if url.host_str().is_some())
+}
+
+/// Accepts colors like `#1ABC9C`, `1abc9c` or `0x1abc9c`
+#[cfg(feature = "utility-commands")]
+pub fn parse_color(query: &str) -> Option<u32> {
+    let query = query.trim();
+    let hex = query.strip_prefix('#')
+        .or_else(|| query.strip_prefix("0x"))
+        .unwrap_or(query);
+
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    u32::from_str_radix(hex, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "utility-commands")]
+    #[test]
+    fn colors_are_parsed() {
+        assert_eq!(parse_color("#1ABC9C"), Some(0x1abc9c));
+        assert_eq!(parse_color("1abc9c"), Some(0x1abc9c));
+        assert_eq!(parse_color("0xffffff"), Some(0xffffff));
+        assert_eq!(parse_color("#fff"), None);
+        assert_eq!(parse_color("#+12345"), None);
+        assert_eq!(parse_color("teal"), None);
+    }
+
+    #[cfg(feature = "utility-commands")]
+    #[test]
+    fn icons_are_validated() {
+        assert!(is_valid_icon("⚠️"));
+        assert!(is_valid_icon("https://example.com/icon.png"));
+        assert!(!is_valid_icon("http://example.com/icon.png"));
+        assert!(!is_valid_icon("https://"));
+        assert!(!is_valid_icon("https://example.com/my icon.png"));
+        assert!(!is_valid_icon("ftp://example.com/icon.png"));
+    }
+
+    #[test]
+    fn unset_colors_fall_back_to_defaults() {
+        let mut theme = Theme::default();
+
+        theme.colors.insert(EmbedType::Failure, 0x123456);
+
+        assert_eq!(theme.color(EmbedType::Failure), Color::new(0x123456));
+        assert_eq!(theme.color(EmbedType::Success), EmbedType::Success.get_color());
+    }
+
+    #[test]
+    fn themes_survive_a_round_trip() {
+        let mut theme = Theme::default();
+
+        theme.colors.insert(EmbedType::Info, 0xabcdef);
+        theme.icons.insert(EmbedType::Warning, "⚠️".to_owned());
+        theme.footer = Some("Powered by Rust".to_owned());
+        theme.reply = true;
+
+        let json = serde_json::to_string(&theme).unwrap();
+
+        assert_eq!(serde_json::from_str::<Theme>(&json).unwrap(), theme);
+        assert_eq!(serde_json::from_str::<Theme>("{}").unwrap(), Theme::default());
+    }
+}
@@ -1,138 +1,1145 @@
 #![allow(dead_code)]
 
 pub mod time {
-    use chrono::Duration;
+    use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
 
-    use crate::utils::string::singular_or_plural;
+    use chrono_tz::{Tz, TZ_VARIANTS};
+
+    use crate::storage::JsonStore;
+
+    use lazy_static::lazy_static;
+
+    use std::{
+        collections::HashMap,
+        error::Error,
+        fmt::{self, Display, Formatter},
+        ops::RangeInclusive,
+    };
+
+    lazy_static! {
+        pub static ref USER_TIMEZONES: JsonStore<HashMap<u64, String>> =
+            JsonStore::open("user_timezones.json");
+    }
+
+    /// Looks up an IANA time zone name, ignoring case
+    pub fn parse_timezone(name: &str) -> Option<Tz> {
+        let name = name.trim();
+
+        name.parse::<Tz>().ok().or_else(|| {
+            TZ_VARIANTS.iter()
+                .find(|tz| tz.name().eq_ignore_ascii_case(name))
+                .copied()
+        })
+    }
+
+    /// Returns the time zone a user has saved with the `time` command
+    pub fn get_user_timezone(user_id: u64) -> Option<Tz> {
+        USER_TIMEZONES.read()
+            .get(&user_id)
+            .and_then(|name| parse_timezone(name))
+    }
+
+    /// Splits a comma-separated list of locations or time zones, dropping empty and repeated ones regardless of case
+    pub fn split_time_queries(input: &str) -> Vec<&str> {
+        let mut queries: Vec<&str> = Vec::new();
+
+        for query in input.split(',').map(str::trim).filter(|q| !q.is_empty()) {
+            if !queries.iter().any(|q| q.eq_ignore_ascii_case(query)) {
+                queries.push(query);
+            }
+        }
+
+        queries
+    }
+
+    /// Formats an offset difference as `+5:30` or `-8:00`, leaving the wording to the message catalog
+    pub fn format_offset_difference(secs: i32) -> String {
+        let sign = if secs < 0 { '-' } else { '+' };
+        let hours = secs.abs() / 3600;
+        let minutes = secs.abs() % 3600 / 60;
+
+        format!("{}{}:{:02}", sign, hours, minutes)
+    }
 
     // should have been `usize`, but `chrono::Duration` only accepts `i64`-values
     pub fn as_text(millis: i64) -> String {
-        let mut output = String::new();
+        DurationFormatter::new().format(millis)
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum TimeUnit {
+        Millisecond,
+        Second,
+        Minute,
+        Hour,
+        Day,
+        Week,
+        Year,
+    }
+
+    impl TimeUnit {
+        const ALL: [TimeUnit; 7] = [
+            TimeUnit::Year,
+            TimeUnit::Week,
+            TimeUnit::Day,
+            TimeUnit::Hour,
+            TimeUnit::Minute,
+            TimeUnit::Second,
+            TimeUnit::Millisecond,
+        ];
 
-        let duration = Duration::milliseconds(millis);
+        pub fn as_millis(self) -> i64 {
+            match self {
+                TimeUnit::Millisecond => 1,
+                TimeUnit::Second => 1000,
+                TimeUnit::Minute => 60 * 1000,
+                TimeUnit::Hour => 60 * 60 * 1000,
+                TimeUnit::Day => 24 * 60 * 60 * 1000,
+                TimeUnit::Week => 7 * 24 * 60 * 60 * 1000,
+                TimeUnit::Year => 365 * 24 * 60 * 60 * 1000,
+            }
+        }
+    }
 
-        let days = duration.num_days();
-        let hours = duration.num_hours() % 24;
-        let minutes = duration.num_minutes() % 60;
-        let seconds = duration.num_seconds() % 60;
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum DurationStyle {
+        /// "1 day, 2 hours and 3 seconds"
+        Long,
+        /// "1d 2h 3s"
+        Short,
+    }
+
+    pub struct UnitNames {
+        /// Plural forms in the order the locale's `plural_form` function indexes them
+        pub long: &'static [&'static str],
+        pub short: &'static str,
+    }
 
-        let mut already_present = false;
+    pub struct DurationLocale {
+        /// Names ordered from milliseconds to years
+        pub units: [UnitNames; 7],
+        pub separator: &'static str,
+        pub conjunction: &'static str,
+        pub plural_form: fn(i64) -> usize,
+    }
 
-        if days > 0 {
-            output.push_str(format!("{} {}", days, singular_or_plural("day", days)).as_str());
+    impl DurationLocale {
+        fn unit_name(&self, unit: TimeUnit, n: i64, style: DurationStyle) -> &'static str {
+            let names = &self.units[unit as usize];
 
-            already_present = true;
+            match style {
+                DurationStyle::Long => names.long[(self.plural_form)(n).min(names.long.len() - 1)],
+                DurationStyle::Short => names.short,
+            }
         }
+    }
+
+    pub static ENGLISH: DurationLocale = DurationLocale {
+        units: [
+            UnitNames { long: &["millisecond", "milliseconds"], short: "ms" },
+            UnitNames { long: &["second", "seconds"], short: "s" },
+            UnitNames { long: &["minute", "minutes"], short: "m" },
+            UnitNames { long: &["hour", "hours"], short: "h" },
+            UnitNames { long: &["day", "days"], short: "d" },
+            UnitNames { long: &["week", "weeks"], short: "w" },
+            UnitNames { long: &["year", "years"], short: "y" },
+        ],
+        separator: ", ",
+        conjunction: " and ",
+        plural_form: |n| (n != 1) as usize,
+    };
+
+    pub static GERMAN: DurationLocale = DurationLocale {
+        units: [
+            UnitNames { long: &["Millisekunde", "Millisekunden"], short: "ms" },
+            UnitNames { long: &["Sekunde", "Sekunden"], short: "s" },
+            UnitNames { long: &["Minute", "Minuten"], short: "min" },
+            UnitNames { long: &["Stunde", "Stunden"], short: "h" },
+            UnitNames { long: &["Tag", "Tage"], short: "T" },
+            UnitNames { long: &["Woche", "Wochen"], short: "W" },
+            UnitNames { long: &["Jahr", "Jahre"], short: "J" },
+        ],
+        separator: ", ",
+        conjunction: " und ",
+        plural_form: |n| (n != 1) as usize,
+    };
+
+    pub static RUSSIAN: DurationLocale = DurationLocale {
+        units: [
+            UnitNames { long: &["миллисекунда", "миллисекунды", "миллисекунд"], short: "мс" },
+            UnitNames { long: &["секунда", "секунды", "секунд"], short: "с" },
+            UnitNames { long: &["минута", "минуты", "минут"], short: "мин" },
+            UnitNames { long: &["час", "часа", "часов"], short: "ч" },
+            UnitNames { long: &["день", "дня", "дней"], short: "д" },
+            UnitNames { long: &["неделя", "недели", "недель"], short: "нед" },
+            UnitNames { long: &["год", "года", "лет"], short: "г" },
+        ],
+        separator: ", ",
+        conjunction: " и ",
+        plural_form: |n| match (n % 10, n % 100) {
+            (1, m) if m != 11 => 0,
+            (2..=4, m) if !(12..=14).contains(&m) => 1,
+            _ => 2,
+        },
+    };
+
+    /// Formats durations with a configurable unit range, precision, style, and locale
+    ///
+    /// The default formatter matches [`as_text`]: English long-style days through seconds
+    #[derive(Clone, Copy)]
+    pub struct DurationFormatter {
+        largest_unit: TimeUnit,
+        smallest_unit: TimeUnit,
+        max_units: Option<usize>,
+        style: DurationStyle,
+        locale: &'static DurationLocale,
+    }
 
-        if hours > 0 {
-            if already_present && (minutes != 0 || seconds != 0) {
-                output.push_str(", ");
+    impl Default for DurationFormatter {
+        fn default() -> Self {
+            Self {
+                largest_unit: TimeUnit::Day,
+                smallest_unit: TimeUnit::Second,
+                max_units: None,
+                style: DurationStyle::Long,
+                locale: &ENGLISH,
             }
+        }
+    }
+
+    impl DurationFormatter {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn units(mut self, units: RangeInclusive<TimeUnit>) -> Self {
+            self.smallest_unit = *units.start().min(units.end());
+            self.largest_unit = *units.start().max(units.end());
+            self
+        }
+
+        /// Limits the output to the given number of the largest non-zero units, truncating the rest
+        pub fn max_units(mut self, max_units: usize) -> Self {
+            self.max_units = Some(max_units.max(1));
+            self
+        }
+
+        pub fn style(mut self, style: DurationStyle) -> Self {
+            self.style = style;
+            self
+        }
+
+        pub fn locale(mut self, locale: &'static DurationLocale) -> Self {
+            self.locale = locale;
+            self
+        }
+
+        pub fn format(&self, millis: i64) -> String {
+            let mut remaining = millis.unsigned_abs();
+            let mut parts = Vec::new();
 
-            if !output.is_empty() && (minutes == 0 || seconds == 0) {
-                if output.contains(", ") {
-                    output.push_str(",");
+            let units = TimeUnit::ALL.iter()
+                .filter(|u| (self.smallest_unit..=self.largest_unit).contains(u));
+
+            for unit in units {
+                let unit_millis = unit.as_millis() as u64;
+                let amount = remaining / unit_millis;
+
+                remaining %= unit_millis;
+
+                if amount > 0 {
+                    parts.push(self.format_part(amount as i64, *unit));
                 }
+            }
 
-                output.push_str(" and ");
+            if let Some(max_units) = self.max_units {
+                parts.truncate(max_units);
             }
 
-            output.push_str(format!("{} {}", hours, singular_or_plural("hour", hours)).as_str());
+            if parts.is_empty() {
+                return self.format_part(0, self.smallest_unit);
+            }
+
+            let output = match self.style {
+                DurationStyle::Long => match parts.split_last() {
+                    Some((last, rest)) if !rest.is_empty() => {
+                        format!("{}{}{}", rest.join(self.locale.separator), self.locale.conjunction, last)
+                    }
+                    _ => parts.concat(),
+                },
+                DurationStyle::Short => parts.join(" "),
+            };
 
-            already_present = true;
+            if millis < 0 {
+                format!("-{}", output)
+            } else {
+                output
+            }
         }
 
-        if minutes > 0 {
-            if already_present && seconds != 0 {
-                output.push_str(", ");
+        fn format_part(&self, amount: i64, unit: TimeUnit) -> String {
+            let name = self.locale.unit_name(unit, amount, self.style);
+
+            match self.style {
+                DurationStyle::Long => format!("{} {}", amount, name),
+                DurationStyle::Short => format!("{}{}", amount, name),
             }
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum DurationParseError {
+        Empty,
+        InvalidNumber(String),
+        MissingUnit(String),
+        UnknownUnit(String),
+        InvalidTimeOfDay(String),
+        TimeInPast,
+        Overflow,
+    }
 
-            if !output.is_empty() && seconds == 0 {
-                if output.contains(", ") {
-                    output.push_str(",");
+    impl Display for DurationParseError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            match self {
+                DurationParseError::Empty => write!(f, "No duration has been provided!"),
+                DurationParseError::InvalidNumber(n) => write!(f, "\"{}\" is not a valid number!", n),
+                DurationParseError::MissingUnit(n) => {
+                    write!(f, "\"{}\" has no time unit! Try something like \"{}m\" or \"{} hours\".", n, n, n)
                 }
+                DurationParseError::UnknownUnit(u) => write!(
+                    f,
+                    "\"{}\" is not a known time unit! Use milliseconds, seconds, minutes, hours, days, weeks, or years.",
+                    u,
+                ),
+                DurationParseError::InvalidTimeOfDay(t) => {
+                    write!(f, "\"{}\" is not a valid time of day! Try something like \"5pm\" or \"17:30\".", t)
+                }
+                DurationParseError::TimeInPast => write!(f, "The specified time has already passed!"),
+                DurationParseError::Overflow => write!(f, "The duration is too long!"),
+            }
+        }
+    }
+
+    impl Error for DurationParseError {}
+
+    fn unit_millis(unit: &str) -> Option<i64> {
+        let millis = match unit {
+            "ms" | "msec" | "msecs" | "millisecond" | "milliseconds" => 1,
+            "s" | "sec" | "secs" | "second" | "seconds" => 1000,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60 * 1000,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 60 * 60 * 1000,
+            "d" | "day" | "days" => 24 * 60 * 60 * 1000,
+            "w" | "wk" | "wks" | "week" | "weeks" => 7 * 24 * 60 * 60 * 1000,
+            "y" | "yr" | "yrs" | "year" | "years" => 365 * 24 * 60 * 60 * 1000,
+            _ => return None,
+        };
+
+        Some(millis)
+    }
+
+    /// Parses a duration relative to the current moment in UTC (see [`parse_duration_from`])
+    pub fn parse_duration(input: &str) -> Result<Duration, DurationParseError> {
+        parse_duration_from(input, Utc::now().with_timezone(&Tz::UTC))
+    }
 
-                output.push_str(" and ");
+    /// Parses either a span of time (`1d2h30m`, `90s`, `2 weeks 3 days`, `in 3 hours`,
+    /// or the output of [`as_text`]) or a moment (`tomorrow at 5pm`, `today at 17:30`, `at noon`)
+    /// into the duration between `now` and that moment
+    pub fn parse_duration_from(input: &str, now: DateTime<Tz>) -> Result<Duration, DurationParseError> {
+        let input = input.trim().to_lowercase();
+        let input = input.strip_suffix("from now").unwrap_or(&input).trim_end();
+        let input = match input.strip_prefix("in") {
+            Some(rest) if rest.is_empty() || rest.starts_with(char::is_whitespace) => rest.trim_start(),
+            _ => input,
+        };
+
+        if input.is_empty() {
+            return Err(DurationParseError::Empty);
+        }
+
+        let day_offset = if let Some(rest) = input.strip_prefix("tomorrow") {
+            Some((1, rest))
+        } else if let Some(rest) = input.strip_prefix("today") {
+            Some((0, rest))
+        } else {
+            input.strip_prefix("at ").map(|rest| (-1, rest))
+        };
+
+        match day_offset {
+            Some((days, rest)) => {
+                let rest = rest.trim();
+                let time = rest.strip_prefix("at").unwrap_or(rest).trim();
+
+                parse_moment(days, time, now)
+            }
+            None => parse_span(input),
+        }
+    }
+
+    fn parse_span(input: &str) -> Result<Duration, DurationParseError> {
+        let mut total: i64 = 0;
+        let mut chars = input.chars().peekable();
+
+        loop {
+            while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+
+            let mut word = String::new();
+
+            while let Some(c) = chars.next_if(|c| c.is_alphabetic()) {
+                word.push(c);
+            }
+
+            // Connectives such as in "1 day, 2 hours and 3 seconds"
+            if word == "and" {
+                continue;
             }
 
-            output.push_str(format!("{} {}", minutes, singular_or_plural("minute", minutes)).as_str());
+            if !word.is_empty() {
+                return Err(DurationParseError::InvalidNumber(word));
+            }
 
-            already_present = true;
+            let mut number = String::new();
+
+            while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                number.push(c);
+            }
+
+            if number.is_empty() {
+                match chars.next() {
+                    Some(c) => return Err(DurationParseError::InvalidNumber(c.to_string())),
+                    None => break,
+                }
+            }
+
+            let value: f64 = number.parse()
+                .map_err(|_| DurationParseError::InvalidNumber(number.clone()))?;
+
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+            let mut unit = String::new();
+
+            while let Some(c) = chars.next_if(|c| c.is_alphabetic()) {
+                unit.push(c);
+            }
+
+            if unit.is_empty() {
+                return Err(DurationParseError::MissingUnit(number));
+            }
+
+            let millis = unit_millis(&unit).ok_or(DurationParseError::UnknownUnit(unit))?;
+            let millis = value * millis as f64;
+
+            if !millis.is_finite() || millis >= i64::MAX as f64 {
+                return Err(DurationParseError::Overflow);
+            }
+
+            total = total.checked_add(millis.round() as i64).ok_or(DurationParseError::Overflow)?;
         }
 
-        if seconds > 0 {
-            if already_present {
-                if output.contains(", ") {
-                    output.push_str(",");
+        Ok(Duration::milliseconds(total))
+    }
+
+    fn parse_time_of_day(input: &str) -> Option<(u32, u32)> {
+        match input {
+            "noon" => return Some((12, 0)),
+            "midnight" => return Some((0, 0)),
+            _ => {}
+        }
+
+        let (clock, meridiem) = if let Some(clock) = input.strip_suffix("am") {
+            (clock.trim(), Some(false))
+        } else if let Some(clock) = input.strip_suffix("pm") {
+            (clock.trim(), Some(true))
+        } else {
+            (input, None)
+        };
+
+        let (hours, minutes) = match clock.split_once(':') {
+            Some((hours, minutes)) if minutes.len() == 2 => (hours.parse::<u32>().ok()?, minutes.parse::<u32>().ok()?),
+            Some(_) => return None,
+            None => (clock.parse::<u32>().ok()?, 0),
+        };
+
+        let hours = match meridiem {
+            Some(is_pm) if (1..=12).contains(&hours) => hours % 12 + if is_pm { 12 } else { 0 },
+            Some(_) => return None,
+            None if clock.contains(':') => hours,
+            // A bare number such as "at 5" is ambiguous
+            None => return None,
+        };
+
+        (hours < 24 && minutes < 60).then_some((hours, minutes))
+    }
+
+    /// `days` is the number of days after today, or -1 for the next occurrence of the time
+    fn parse_moment(days: i64, time: &str, now: DateTime<Tz>) -> Result<Duration, DurationParseError> {
+        let (hours, minutes) = parse_time_of_day(time)
+            .ok_or_else(|| DurationParseError::InvalidTimeOfDay(time.to_owned()))?;
+
+        let tz = now.timezone();
+        let today = now.naive_local().date();
+
+        let at = |date: NaiveDate| {
+            date.and_hms_opt(hours, minutes, 0)
+                .and_then(|naive| tz.from_local_datetime(&naive).earliest())
+                .ok_or_else(|| DurationParseError::InvalidTimeOfDay(time.to_owned()))
+        };
+
+        let target = match days {
+            -1 => {
+                let target = at(today)?;
+
+                if target > now {
+                    target
+                } else {
+                    at(today.succ_opt().ok_or(DurationParseError::Overflow)?)?
                 }
+            }
+            days => {
+                let date = today.checked_add_signed(Duration::days(days)).ok_or(DurationParseError::Overflow)?;
 
-                output.push_str(" and ");
+                at(date)?
             }
+        };
 
-            output.push_str(format!("{} {}", seconds, singular_or_plural("second", seconds)).as_str());
+        if target <= now {
+            return Err(DurationParseError::TimeInPast);
         }
 
-        if output.is_empty() && !already_present {
-            output.push_str("0 seconds");
+        Ok(target - now)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use proptest::prelude::*;
+
+        const SECOND: i64 = 1000;
+        const MINUTE: i64 = 60 * SECOND;
+        const HOUR: i64 = 60 * MINUTE;
+        const DAY: i64 = 24 * HOUR;
+        const WEEK: i64 = 7 * DAY;
+        const YEAR: i64 = 365 * DAY;
+
+        #[test]
+        fn as_text_single_units() {
+            assert_eq!(as_text(0), "0 seconds");
+            assert_eq!(as_text(999), "0 seconds");
+            assert_eq!(as_text(SECOND), "1 second");
+            assert_eq!(as_text(2 * SECOND), "2 seconds");
+            assert_eq!(as_text(MINUTE), "1 minute");
+            assert_eq!(as_text(HOUR), "1 hour");
+            assert_eq!(as_text(3 * DAY), "3 days");
+        }
+
+        #[test]
+        fn as_text_unit_combinations() {
+            assert_eq!(as_text(DAY + 2 * HOUR + 3 * SECOND), "1 day, 2 hours and 3 seconds");
+            assert_eq!(as_text(DAY + 2 * HOUR), "1 day and 2 hours");
+            assert_eq!(as_text(DAY + 5 * MINUTE), "1 day and 5 minutes");
+            assert_eq!(as_text(DAY + SECOND), "1 day and 1 second");
+            assert_eq!(as_text(HOUR + MINUTE), "1 hour and 1 minute");
+            assert_eq!(as_text(HOUR + SECOND), "1 hour and 1 second");
+            assert_eq!(as_text(MINUTE + SECOND), "1 minute and 1 second");
+            assert_eq!(as_text(DAY + HOUR + MINUTE), "1 day, 1 hour and 1 minute");
+            assert_eq!(as_text(HOUR + MINUTE + SECOND), "1 hour, 1 minute and 1 second");
+            assert_eq!(as_text(2 * DAY + 3 * HOUR + 4 * MINUTE + 5 * SECOND), "2 days, 3 hours, 4 minutes and 5 seconds");
+            assert_eq!(as_text(DAY + MINUTE + SECOND), "1 day, 1 minute and 1 second");
+        }
+
+        #[test]
+        fn as_text_does_not_use_weeks_or_years() {
+            assert_eq!(as_text(400 * DAY), "400 days");
+        }
+
+        #[test]
+        fn formatter_unit_range() {
+            let formatter = DurationFormatter::new().units(TimeUnit::Millisecond..=TimeUnit::Year);
+
+            assert_eq!(formatter.format(YEAR + 2 * WEEK + DAY + 250), "1 year, 2 weeks, 1 day and 250 milliseconds");
+            assert_eq!(formatter.format(0), "0 milliseconds");
+
+            let formatter = DurationFormatter::new().units(TimeUnit::Hour..=TimeUnit::Week);
+
+            assert_eq!(formatter.format(WEEK + DAY + 59 * MINUTE), "1 week and 1 day");
+            assert_eq!(formatter.format(59 * MINUTE), "0 hours");
+        }
+
+        #[test]
+        fn formatter_unit_range_is_order_independent() {
+            let formatter = DurationFormatter::new().units(TimeUnit::Day..=TimeUnit::Minute);
+
+            assert_eq!(formatter.format(DAY + MINUTE + SECOND), "1 day and 1 minute");
+        }
+
+        #[test]
+        fn formatter_max_units() {
+            let formatter = DurationFormatter::new().max_units(2);
+
+            assert_eq!(formatter.format(DAY + 2 * HOUR + 3 * MINUTE + 4 * SECOND), "1 day and 2 hours");
+            assert_eq!(formatter.format(DAY + 3 * MINUTE + 4 * SECOND), "1 day and 3 minutes");
+            assert_eq!(formatter.format(4 * SECOND), "4 seconds");
+            assert_eq!(DurationFormatter::new().max_units(1).format(DAY + HOUR), "1 day");
+            assert_eq!(DurationFormatter::new().max_units(0).format(DAY + HOUR), "1 day");
+        }
+
+        #[test]
+        fn formatter_short_style() {
+            let formatter = DurationFormatter::new().style(DurationStyle::Short);
+
+            assert_eq!(formatter.format(DAY + 2 * HOUR + 30 * MINUTE), "1d 2h 30m");
+            assert_eq!(formatter.format(90 * SECOND), "1m 30s");
+            assert_eq!(formatter.format(0), "0s");
+        }
+
+        #[test]
+        fn formatter_negative_durations() {
+            assert_eq!(as_text(-(HOUR + SECOND)), "-1 hour and 1 second");
+            assert_eq!(DurationFormatter::new().style(DurationStyle::Short).format(-90 * SECOND), "-1m 30s");
+            assert!(as_text(i64::MIN).starts_with("-"));
+        }
+
+        #[test]
+        fn formatter_locales() {
+            let german = DurationFormatter::new().locale(&GERMAN);
+
+            assert_eq!(german.format(DAY + 2 * HOUR + SECOND), "1 Tag, 2 Stunden und 1 Sekunde");
+            assert_eq!(german.format(0), "0 Sekunden");
+
+            let russian = DurationFormatter::new().locale(&RUSSIAN);
+
+            assert_eq!(russian.format(DAY + 2 * HOUR + 5 * SECOND), "1 день, 2 часа и 5 секунд");
+            assert_eq!(russian.format(11 * DAY + 21 * HOUR), "11 дней и 21 час");
+            assert_eq!(russian.format(22 * MINUTE + 14 * SECOND), "22 минуты и 14 секунд");
+            assert_eq!(
+                DurationFormatter::new().locale(&RUSSIAN).style(DurationStyle::Short).format(HOUR + 5 * MINUTE),
+                "1ч 5мин",
+            );
         }
 
-        output
+        #[test]
+        fn short_english_output_round_trips() {
+            let formatter = DurationFormatter::new()
+                .units(TimeUnit::Millisecond..=TimeUnit::Year)
+                .style(DurationStyle::Short);
+
+            let millis = YEAR + 3 * WEEK + 4 * DAY + 5 * HOUR + 6 * MINUTE + 7 * SECOND + 8;
+
+            assert_eq!(parse_duration(&formatter.format(millis)), Ok(Duration::milliseconds(millis)));
+        }
+
+        #[test]
+        fn timezone_parsing() {
+            assert_eq!(parse_timezone("Europe/London"), Some(Tz::Europe__London));
+            assert_eq!(parse_timezone(" america/new_york "), Some(Tz::America__New_York));
+            assert_eq!(parse_timezone("UTC"), Some(Tz::UTC));
+            assert_eq!(parse_timezone("Mars/Olympus_Mons"), None);
+        }
+
+        #[test]
+        fn time_queries_are_split_and_deduplicated() {
+            assert_eq!(split_time_queries("Berlin, Tokyo"), vec!["Berlin", "Tokyo"]);
+            assert_eq!(split_time_queries(" Berlin ,, berlin, UTC, , BERLIN"), vec!["Berlin", "UTC"]);
+            assert!(split_time_queries(" , ").is_empty());
+        }
+
+        #[test]
+        fn offset_differences() {
+            assert_eq!(format_offset_difference(0), "+0:00");
+            assert_eq!(format_offset_difference(3600), "+1:00");
+            assert_eq!(format_offset_difference(-8 * 3600), "-8:00");
+            assert_eq!(format_offset_difference(5 * 3600 + 1800), "+5:30");
+            assert_eq!(format_offset_difference(-(9 * 3600 + 2700)), "-9:45");
+        }
+
+        fn london(day: u32, hours: u32, minutes: u32) -> DateTime<Tz> {
+            let naive = NaiveDate::from_ymd_opt(2021, 10, day).unwrap().and_hms_opt(hours, minutes, 0).unwrap();
+
+            Tz::Europe__London.from_local_datetime(&naive).unwrap()
+        }
+
+        #[test]
+        fn compact_durations() {
+            assert_eq!(parse_duration("1d2h30m"), Ok(Duration::minutes(24 * 60 + 150)));
+            assert_eq!(parse_duration("90s"), Ok(Duration::seconds(90)));
+            assert_eq!(parse_duration("1h 30m"), Ok(Duration::minutes(90)));
+            assert_eq!(parse_duration("1.5h"), Ok(Duration::minutes(90)));
+            assert_eq!(parse_duration("250ms"), Ok(Duration::milliseconds(250)));
+            assert_eq!(parse_duration("2w"), Ok(Duration::weeks(2)));
+            assert_eq!(parse_duration("1y"), Ok(Duration::days(365)));
+        }
+
+        #[test]
+        fn verbose_durations() {
+            assert_eq!(parse_duration("2 weeks 3 days"), Ok(Duration::days(17)));
+            assert_eq!(parse_duration("in 3 hours"), Ok(Duration::hours(3)));
+            assert_eq!(parse_duration("In 1 Minute"), Ok(Duration::minutes(1)));
+            assert_eq!(parse_duration("5 minutes from now"), Ok(Duration::minutes(5)));
+            assert_eq!(
+                parse_duration("1 day, 2 hours and 3 seconds"),
+                Ok(Duration::days(1) + Duration::hours(2) + Duration::seconds(3)),
+            );
+        }
+
+        #[test]
+        fn moments() {
+            let now = london(18, 14, 0);
+
+            assert_eq!(parse_duration_from("tomorrow at 5pm", now), Ok(Duration::hours(27)));
+            assert_eq!(parse_duration_from("tomorrow 9:30am", now), Ok(Duration::minutes(19 * 60 + 30)));
+            assert_eq!(parse_duration_from("today at 17:30", now), Ok(Duration::minutes(210)));
+            assert_eq!(parse_duration_from("at noon", now), Ok(Duration::hours(22)));
+            assert_eq!(parse_duration_from("at midnight", now), Ok(Duration::hours(10)));
+            assert_eq!(parse_duration_from("at 3pm", now), Ok(Duration::hours(1)));
+            assert_eq!(parse_duration_from("today at 9am", now), Err(DurationParseError::TimeInPast));
+        }
+
+        #[test]
+        fn moments_across_daylight_saving_changes() {
+            // British Summer Time ends on October 31, 2021, so that day is 25 hours long
+            let now = london(30, 12, 0);
+
+            assert_eq!(parse_duration_from("tomorrow at noon", now), Ok(Duration::hours(25)));
+        }
+
+        #[test]
+        fn parse_errors() {
+            assert_eq!(parse_duration(""), Err(DurationParseError::Empty));
+            assert_eq!(parse_duration("in "), Err(DurationParseError::Empty));
+            assert_eq!(parse_duration("90"), Err(DurationParseError::MissingUnit("90".to_owned())));
+            assert_eq!(parse_duration("5 fortnights"), Err(DurationParseError::UnknownUnit("fortnights".to_owned())));
+            assert_eq!(parse_duration("soon"), Err(DurationParseError::InvalidNumber("soon".to_owned())));
+            assert_eq!(parse_duration("1..5h"), Err(DurationParseError::InvalidNumber("1..5".to_owned())));
+            assert_eq!(parse_duration("tomorrow at 25pm"), Err(DurationParseError::InvalidTimeOfDay("25pm".to_owned())));
+            assert_eq!(parse_duration("tomorrow at 5"), Err(DurationParseError::InvalidTimeOfDay("5".to_owned())));
+            assert_eq!(parse_duration("99999999999999999y"), Err(DurationParseError::Overflow));
+        }
+
+        #[test]
+        fn error_messages() {
+            assert_eq!(
+                parse_duration("90").unwrap_err().to_string(),
+                "\"90\" has no time unit! Try something like \"90m\" or \"90 hours\".",
+            );
+        }
+
+        proptest! {
+            #[test]
+            fn round_trips_through_as_text(secs in 0i64..10_000_000_000) {
+                let text = as_text(secs * 1000);
+
+                prop_assert_eq!(parse_duration(&text), Ok(Duration::seconds(secs)), "{}", text);
+            }
+
+            #[test]
+            fn compact_components_add_up(d in 0i64..1000, h in 0i64..24, m in 0i64..60, s in 0i64..60) {
+                let input = format!("{}d{}h{}m{}s", d, h, m, s);
+                let expected = Duration::days(d) + Duration::hours(h) + Duration::minutes(m) + Duration::seconds(s);
+
+                prop_assert_eq!(parse_duration(&input), Ok(expected));
+            }
+
+            #[test]
+            fn never_panics(input in "\\PC*") {
+                let _ = parse_duration(&input);
+            }
+        }
     }
 }
 
 pub mod string {
-    pub fn strip_str(s: &str, mut lim: usize, add_ellipsis: bool) -> Option<String> {
-        if add_ellipsis {
-            if lim < 4 {
-                return None;
+    use unicode_segmentation::UnicodeSegmentation;
+
+    const ELLIPSIS: &str = "...";
+
+    /// Discord measures text limits in characters rather than bytes
+    pub fn char_len(s: &str) -> usize {
+        s.chars().count()
+    }
+
+    /// Shortens the string to at most `lim` characters without splitting a grapheme cluster,
+    /// so neither multi-byte characters nor emoji sequences are ever cut in half
+    pub fn strip_str(s: &str, lim: usize, add_ellipsis: bool) -> Option<String> {
+        let ellipsis = if add_ellipsis { ELLIPSIS } else { "" };
+
+        if add_ellipsis && lim <= char_len(ellipsis) {
+            return None;
+        }
+
+        if char_len(s) <= lim {
+            return Some(s.to_owned());
+        }
+
+        let lim = lim - char_len(ellipsis);
+        let mut len = 0;
+        let mut end = 0;
+
+        for (i, grapheme) in s.grapheme_indices(true) {
+            len += char_len(grapheme);
+
+            if len > lim {
+                break;
             }
 
-            lim -= 3;
+            end = i + grapheme.len();
         }
 
-        let stripped = s
-            .get(..lim)
-            .map(|str| {
-                add_ellipsis
-                    .then(|| format!("{}...", str))
-                    .unwrap_or_else(|| str.to_owned())
-            })
-            .unwrap_or_else(|| s.to_owned());
+        Some(format!("{}{}", &s[..end], ellipsis))
+    }
+
+    /// Splits the string after at most `lim` characters, or after its first grapheme if even that does not fit
+    fn split_graphemes(s: &str, lim: usize) -> (&str, &str) {
+        let mut len = 0;
+        let mut end = 0;
+
+        for (i, grapheme) in s.grapheme_indices(true) {
+            len += char_len(grapheme);
 
-        Some(stripped)
+            if len > lim && end > 0 {
+                break;
+            }
+
+            end = i + grapheme.len();
+        }
+
+        s.split_at(end)
     }
 
-    pub fn singular_or_plural(s: &str, n: i64) -> String {
-        if n == 1 {
-            s.to_owned()
-        } else {
-            format!("{}s", s)
+    /// Splits text into chunks of at most `lim` characters, preferring line boundaries. A code block
+    /// that has to be split is closed at the end of a chunk and reopened with the same language
+    /// in the next one, and lines that are too long on their own are split wherever they have to be.
+    pub fn split_message(text: &str, lim: usize) -> Vec<String> {
+        const FENCE: &str = "```";
+
+        let mut chunks = Vec::new();
+        let mut chunk = String::new();
+        let mut has_content = false;
+
+        // The opening line of the code block the current line is in
+        let mut fence: Option<String> = None;
+
+        let flush = |chunks: &mut Vec<String>, chunk: &mut String, fence: &Option<String>| {
+            let opening = fence.as_ref().map(|fence| format!("{}\n", fence));
+
+            match opening {
+                // A code block that has only just been opened moves to the next chunk as a whole
+                Some(ref opening) if chunk.ends_with(opening.as_str()) => {
+                    chunk.truncate(chunk.len() - opening.len());
+                }
+                Some(_) => {
+                    if !chunk.ends_with('\n') {
+                        chunk.push('\n');
+                    }
+
+                    chunk.push_str(FENCE);
+                }
+                None => {}
+            }
+
+            // Blank lines are useless at the edges of a message and Discord would trim them anyway
+            if !chunk.trim_matches('\n').is_empty() {
+                chunks.push(chunk.trim_matches('\n').to_owned());
+            }
+
+            chunk.clear();
+
+            if let Some(opening) = opening {
+                chunk.push_str(&opening);
+            }
+        };
+
+        // Closing a code block takes a fence, preceded by a line break unless the chunk already ends with one
+        let closing_len = |fence: &Option<String>, end: &str| {
+            fence.as_ref().map_or(0, |_| FENCE.len() + usize::from(!end.ends_with('\n')))
+        };
+
+        for line in text.split_inclusive('\n') {
+            let next_fence = if line.matches(FENCE).count() % 2 == 1 {
+                match fence {
+                    Some(_) => None,
+                    None => Some(format!("{}{}", FENCE, line.rsplit(FENCE).next().unwrap_or_default().trim())),
+                }
+            } else {
+                fence.clone()
+            };
+
+            if has_content && char_len(&chunk) + char_len(line) + closing_len(&next_fence, line) > lim {
+                flush(&mut chunks, &mut chunk, &fence);
+
+                has_content = false;
+            }
+
+            let mut rest = line;
+
+            while char_len(&chunk) + char_len(rest) + closing_len(&next_fence, rest) > lim {
+                let room = lim.saturating_sub(char_len(&chunk) + closing_len(&fence, ""));
+                let (head, tail) = split_graphemes(rest, room);
+
+                chunk.push_str(head);
+                flush(&mut chunks, &mut chunk, &fence);
+
+                rest = tail;
+
+                if rest.is_empty() {
+                    break;
+                }
+            }
+
+            if !rest.is_empty() {
+                chunk.push_str(rest);
+                has_content = true;
+            }
+
+            fence = next_fence;
+        }
+
+        if has_content && !chunk.trim_matches('\n').is_empty() {
+            chunks.push(chunk.trim_matches('\n').to_owned());
         }
+
+        chunks
     }
 
-    pub fn singular_or_plural_u64(s: &str, n: u64) -> String {
-        if n == 1 {
-            s.to_owned()
-        } else {
-            format!("{}s", s)
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use proptest::prelude::*;
+
+        #[test]
+        fn short_strings_are_kept() {
+            assert_eq!(strip_str("Hello", 5, true).as_deref(), Some("Hello"));
+            assert_eq!(strip_str("Hello", 2000, true).as_deref(), Some("Hello"));
+            assert_eq!(strip_str("", 10, false).as_deref(), Some(""));
+        }
+
+        #[test]
+        fn long_strings_are_truncated() {
+            assert_eq!(strip_str("Hello, world!", 8, true).as_deref(), Some("Hello..."));
+            assert_eq!(strip_str("Hello, world!", 5, false).as_deref(), Some("Hello"));
+            assert_eq!(strip_str("Hello, world!", 3, true), None);
+        }
+
+        #[test]
+        fn multi_byte_characters_are_counted_once() {
+            assert_eq!(strip_str("Привет, мир!", 12, true).as_deref(), Some("Привет, мир!"));
+            assert_eq!(strip_str("Привет, мир!", 9, true).as_deref(), Some("Привет..."));
+            assert_eq!(strip_str("\u{b0}\u{b0}\u{b0}\u{b0}", 2, false).as_deref(), Some("\u{b0}\u{b0}"));
+        }
+
+        #[test]
+        fn grapheme_clusters_are_never_split() {
+            // A family emoji is 5 characters joined into a single grapheme
+            let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+
+            assert_eq!(strip_str(&format!("ab{}", family), 4, false).as_deref(), Some("ab"));
+            assert_eq!(strip_str(&format!("ab{}cd", family), 7, false).as_deref(), Some(format!("ab{}", family).as_str()));
+            assert_eq!(strip_str("e\u{301}e\u{301}", 3, false).as_deref(), Some("e\u{301}"));
+        }
+
+        #[test]
+        fn short_messages_are_not_split() {
+            assert_eq!(split_message("Hello,\nworld!", 2000), vec!["Hello,\nworld!"]);
+            assert!(split_message("", 2000).is_empty());
+        }
+
+        #[test]
+        fn messages_are_split_on_line_boundaries() {
+            assert_eq!(split_message("one\ntwo\nthree", 8), vec!["one\ntwo", "three"]);
+            assert_eq!(split_message("one\n\ntwo", 4), vec!["one", "two"]);
+        }
+
+        #[test]
+        fn code_blocks_are_reopened() {
+            let text = "Output:\n```rust\nlet a = 1;\nlet b = 2;\n```\nDone";
+
+            assert_eq!(
+                split_message(text, 30),
+                vec!["Output:\n```rust\nlet a = 1;\n```", "```rust\nlet b = 2;\n```\nDone"],
+            );
+        }
+
+        #[test]
+        fn long_lines_are_split_on_grapheme_boundaries() {
+            let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+            let chunks = split_message(&format!("abc{}def", family), 5);
+
+            assert_eq!(chunks, vec!["abc".to_owned(), family.to_owned(), "def".to_owned()]);
+        }
+
+        #[test]
+        fn long_lines_in_code_blocks_keep_their_fences() {
+            let chunks = split_message(&format!("```\n{}\n```", "x".repeat(20)), 16);
+
+            for chunk in &chunks {
+                assert!(chunk.starts_with("```\n") && chunk.ends_with("```"), "{:?}", chunk);
+                assert!(char_len(chunk) <= 16, "{:?}", chunk);
+            }
+
+            assert_eq!(chunks.iter().map(|c| c.matches('x').count()).sum::<usize>(), 20);
+        }
+
+        proptest! {
+            #[test]
+            fn chunks_fit_and_keep_the_content(text in "[a-z \n\u{e9}\u{301}]{0,500}", lim in 8usize..200) {
+                let chunks = split_message(&text, lim);
+
+                for chunk in &chunks {
+                    prop_assert!(char_len(chunk) <= lim, "{:?}", chunk);
+                }
+
+                prop_assert_eq!(chunks.concat().replace('\n', ""), text.replace('\n', ""));
+            }
+
+            #[test]
+            fn fenced_chunks_fit(text in "(```[a-z]{0,3}\n|[a-z ]{0,30}\n){0,40}", lim in 16usize..100) {
+                for chunk in split_message(&text, lim) {
+                    prop_assert!(char_len(&chunk) <= lim, "{:?}", chunk);
+                }
+            }
         }
     }
 }
 
 pub mod discord {
-    use crate::config;
+    use crate::{
+        config,
+        i18n::{get_language, tr, translate, Language},
+        theme::{get_theme, Theme},
+        utils::string::{char_len, split_message, strip_str},
+    };
+
+    use serde::{Deserialize, Serialize};
+
+    use serde_json::Value;
 
     use serenity::{
-        http::Http,
+        builder::{CreateEmbed, CreateMessage},
+        client::{bridge::gateway::ShardManager, Context},
+        gateway::ConnectionStage,
+        http::{AttachmentType, Http},
         model::{
             channel::Message,
-            id::ChannelId,
+            id::{ChannelId, MessageId},
+            permissions::Permissions,
         },
+        prelude::{Mutex, TypeMapKey},
         utils::Color,
         Result,
         async_trait,
     };
 
+    use std::{
+        borrow::Cow,
+        collections::HashMap,
+        sync::Arc,
+        time::Duration,
+    };
+
+    pub const MESSAGE_LIMIT: usize = 2000;
+    pub const EMBED_TITLE_LIMIT: usize = 256;
+    pub const EMBED_DESCRIPTION_LIMIT: usize = 4096;
+    pub const EMBED_FIELD_NAME_LIMIT: usize = 256;
+    pub const EMBED_FIELD_VALUE_LIMIT: usize = 1024;
+    pub const EMBED_FIELD_COUNT_LIMIT: usize = 25;
+    pub const EMBED_FOOTER_LIMIT: usize = 2048;
+    pub const EMBED_AUTHOR_LIMIT: usize = 256;
+    pub const EMBED_TOTAL_LIMIT: usize = 6000;
+
+    pub async fn has_permissions(cx: &Context, message: &Message, permissions: Permissions) -> bool {
+        match message.member(cx).await {
+            Ok(member) => member.permissions(cx).await
+                .map(|p| p.contains(permissions))
+                .unwrap_or(false),
+            Err(_) => false,
+        }
+    }
+
+    pub struct ShardManagerContainer;
+
+    impl TypeMapKey for ShardManagerContainer {
+        type Value = Arc<Mutex<ShardManager>>;
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    pub struct ShardInfo {
+        /// The latency of the last heartbeat, if one has been acknowledged yet
+        pub latency: Option<Duration>,
+        pub stage: ConnectionStage,
+    }
+
+    /// Looks every shard up in the shard manager stored in the client's data
+    pub async fn get_shards(cx: &Context) -> HashMap<u64, ShardInfo> {
+        let manager = match cx.data.read().await.get::<ShardManagerContainer>() {
+            Some(manager) => manager.clone(),
+            None => return HashMap::new(),
+        };
+
+        let runners = manager.lock().await.runners.clone();
+        let runners = runners.lock().await;
+
+        runners.iter()
+            .map(|(id, runner)| (id.0, ShardInfo { latency: runner.latency, stage: runner.stage }))
+            .collect()
+    }
+
+    /// How a reply is rendered and whether it references the message it answers
+    #[derive(Clone, Default)]
+    pub struct ReplyOptions {
+        pub language: Language,
+        pub theme: Theme,
+        pub reply_to: Option<(ChannelId, MessageId)>,
+    }
+
+    impl ReplyOptions {
+        pub fn new(language: Language, theme: Theme) -> Self {
+            Self {
+                language,
+                theme,
+                reply_to: None,
+            }
+        }
+
+        /// Picks the author's language and the theme of the guild the message is from,
+        /// replying to the message if the guild has chosen so
+        pub fn for_message(message: &Message) -> Self {
+            let theme = get_theme(message.guild_id.map(|id| *id.as_u64()));
+            let reply_to = theme.reply.then(|| (message.channel_id, message.id));
+
+            Self {
+                language: get_language(message),
+                theme,
+                reply_to,
+            }
+        }
+
+        pub fn reply_to(mut self, message: &Message) -> Self {
+            self.reply_to = Some((message.channel_id, message.id));
+            self
+        }
+    }
+
+    pub trait ThemedMessage {
+        /// Builds an embed on top of the theme's style for the type, replying if the options say so
+        fn themed_embed<F>(&mut self, options: &ReplyOptions, embed_type: EmbedType, f: F) -> &mut Self
+        where
+            F: FnOnce(&mut CreateEmbed) -> &mut CreateEmbed;
+    }
+
+    impl<'a> ThemedMessage for CreateMessage<'a> {
+        fn themed_embed<F>(&mut self, options: &ReplyOptions, embed_type: EmbedType, f: F) -> &mut Self
+        where
+            F: FnOnce(&mut CreateEmbed) -> &mut CreateEmbed,
+        {
+            if let Some(reference) = options.reply_to {
+                self.reference_message(reference);
+            }
+
+            self.embed(|embed| f(options.theme.apply(embed, embed_type)).limit())
+        }
+    }
+
     #[async_trait]
     pub trait DefaultEmbedReplies {
         async fn send_default_reply<'http, D: ToString + Send>(
@@ -140,7 +1147,17 @@ pub mod discord {
             http: impl AsRef<Http> + Send + Sync + 'http,
             description: D,
             embed_type: EmbedType,
+            options: &ReplyOptions,
         ) -> Result<Message>;
+
+        /// Sends text that may not fit into a single embed (see [`LongReplies::send_long_reply`])
+        async fn send_default_long_reply<'http>(
+            self,
+            http: impl AsRef<Http> + Send + Sync + 'http,
+            text: String,
+            embed_type: EmbedType,
+            options: &ReplyOptions,
+        ) -> Result<Vec<Message>>;
     }
 
     #[async_trait]
@@ -150,24 +1167,203 @@ pub mod discord {
             http: impl AsRef<Http> + Send + Sync + 'http,
             description: D,
             embed_type: EmbedType,
+            options: &ReplyOptions,
         ) -> Result<Message> {
             self.send_message(&http, |m| {
-                m.embed(|embed| {
-                    embed
-                        .author(|a| a.name(format!("{}!", embed_type.to_string())))
-                        .color(embed_type.get_color())
+                m.themed_embed(options, embed_type, |embed| {
+                    options.theme.apply_author(embed, embed_type, options.language)
                         .description(description)
                 })
             }).await
         }
+
+        async fn send_default_long_reply<'http>(
+            self,
+            http: impl AsRef<Http> + Send + Sync + 'http,
+            text: String,
+            embed_type: EmbedType,
+            options: &ReplyOptions,
+        ) -> Result<Vec<Message>> {
+            let file_name = format!("{}.txt", embed_type.to_string().to_lowercase());
+
+            self.send_long_reply(http, text, &file_name, embed_type, options, |embed| {
+                options.theme.apply_author(embed, embed_type, options.language)
+            }).await
+        }
+    }
+
+    #[async_trait]
+    pub trait LongReplies {
+        /// Splits the text on line and code block boundaries across as many embeds as it takes,
+        /// each of them decorated by `decorate`. Text that would take more than
+        /// `config::LONG_REPLY_MAX_PAGES` embeds is attached as a file instead.
+        async fn send_long_reply<'http, F>(
+            self,
+            http: impl AsRef<Http> + Send + Sync + 'http,
+            text: String,
+            file_name: &str,
+            embed_type: EmbedType,
+            options: &ReplyOptions,
+            decorate: F,
+        ) -> Result<Vec<Message>>
+        where
+            F: Fn(&mut CreateEmbed) -> &mut CreateEmbed + Send + Sync;
     }
 
-    #[derive(Debug)]
+    #[async_trait]
+    impl LongReplies for ChannelId {
+        async fn send_long_reply<'http, F>(
+            self,
+            http: impl AsRef<Http> + Send + Sync + 'http,
+            text: String,
+            file_name: &str,
+            embed_type: EmbedType,
+            options: &ReplyOptions,
+            decorate: F,
+        ) -> Result<Vec<Message>>
+        where
+            F: Fn(&mut CreateEmbed) -> &mut CreateEmbed + Send + Sync,
+        {
+            let language = options.language;
+            let pages = split_message(&text, EMBED_DESCRIPTION_LIMIT);
+
+            if pages.len() > config::LONG_REPLY_MAX_PAGES {
+                let message = self.send_message(&http, |m| {
+                    m.add_file(AttachmentType::Bytes {
+                        data: Cow::Owned(text.into_bytes()),
+                        filename: file_name.to_owned(),
+                    });
+
+                    m.themed_embed(options, embed_type, |embed| {
+                        decorate(embed).description(tr!(language, "reply-attached", file = file_name))
+                    })
+                }).await?;
+
+                return Ok(vec![message]);
+            }
+
+            let count = pages.len();
+            let mut messages = Vec::with_capacity(count);
+
+            for (i, page) in pages.into_iter().enumerate() {
+                // Only the first page replies to the original message
+                let options = if i == 0 {
+                    Cow::Borrowed(options)
+                } else {
+                    Cow::Owned(ReplyOptions { reply_to: None, ..options.clone() })
+                };
+
+                let message = self.send_message(&http, |m| {
+                    m.themed_embed(&options, embed_type, |embed| {
+                        decorate(embed).description(page);
+
+                        if count > 1 {
+                            embed.footer(|f| f.text(tr!(language, "reply-page", page = i + 1, count = count)));
+                        }
+
+                        embed
+                    })
+                }).await?;
+
+                messages.push(message);
+            }
+
+            Ok(messages)
+        }
+    }
+
+    /// Keeps an embed within Discord's limits, which would otherwise make the whole message fail
+    pub trait EmbedLimits {
+        /// Truncates every text of the embed to its own limit, drops the fields past the 25th one,
+        /// and then shortens the description and drops the last fields until the total limit is met
+        fn limit(&mut self) -> &mut Self;
+    }
+
+    impl EmbedLimits for CreateEmbed {
+        fn limit(&mut self) -> &mut Self {
+            limit_embed(&mut self.0);
+
+            self
+        }
+    }
+
+    fn limit_text(value: Option<&mut Value>, lim: usize) {
+        if let Some(Value::String(text)) = value {
+            if let Some(stripped) = strip_str(text, lim, true) {
+                *text = stripped;
+            }
+        }
+    }
+
+    fn text_len(value: Option<&Value>) -> usize {
+        value.and_then(Value::as_str).map_or(0, char_len)
+    }
+
+    fn total_len(embed: &HashMap<&'static str, Value>) -> usize {
+        let fields = embed.get("fields")
+            .and_then(Value::as_array)
+            .map_or(0, |fields| {
+                fields.iter()
+                    .map(|f| text_len(f.get("name")) + text_len(f.get("value")))
+                    .sum()
+            });
+
+        text_len(embed.get("title"))
+            + text_len(embed.get("description"))
+            + text_len(embed.get("author").and_then(|a| a.get("name")))
+            + text_len(embed.get("footer").and_then(|f| f.get("text")))
+            + fields
+    }
+
+    fn limit_embed(embed: &mut HashMap<&'static str, Value>) {
+        limit_text(embed.get_mut("title"), EMBED_TITLE_LIMIT);
+        limit_text(embed.get_mut("description"), EMBED_DESCRIPTION_LIMIT);
+        limit_text(embed.get_mut("author").and_then(|a| a.get_mut("name")), EMBED_AUTHOR_LIMIT);
+        limit_text(embed.get_mut("footer").and_then(|f| f.get_mut("text")), EMBED_FOOTER_LIMIT);
+
+        if let Some(Value::Array(fields)) = embed.get_mut("fields") {
+            fields.truncate(EMBED_FIELD_COUNT_LIMIT);
+
+            for field in fields.iter_mut() {
+                limit_text(field.get_mut("name"), EMBED_FIELD_NAME_LIMIT);
+                limit_text(field.get_mut("value"), EMBED_FIELD_VALUE_LIMIT);
+            }
+        }
+
+        let excess = total_len(embed).saturating_sub(EMBED_TOTAL_LIMIT);
+
+        // The description is usually the longest part, so it is shortened before any field is dropped
+        if excess > 0 {
+            let description = embed.get("description").and_then(Value::as_str).unwrap_or_default();
+            let lim = char_len(description).saturating_sub(excess);
+
+            match strip_str(description, lim, true) {
+                Some(description) => {
+                    embed.insert("description", Value::from(description));
+                }
+                None => {
+                    embed.remove("description");
+                }
+            }
+        }
+
+        while total_len(embed) > EMBED_TOTAL_LIMIT {
+            match embed.get_mut("fields") {
+                Some(Value::Array(fields)) if !fields.is_empty() => {
+                    fields.pop();
+                }
+                _ => break,
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
     pub enum EmbedType {
         Success,
         Failure,
         Confirmation,
         Warning,
+        Info,
     }
 
     impl ToString for EmbedType {
@@ -177,37 +1373,416 @@ pub mod discord {
     }
 
     impl EmbedType {
+        pub const ALL: [EmbedType; 5] = [
+            EmbedType::Success,
+            EmbedType::Failure,
+            EmbedType::Confirmation,
+            EmbedType::Warning,
+            EmbedType::Info,
+        ];
+
+        pub fn parse(query: &str) -> Option<EmbedType> {
+            EmbedType::ALL.iter()
+                .copied()
+                .find(|t| t.to_string().eq_ignore_ascii_case(query.trim()))
+        }
+
+        pub fn get_title(&self, language: Language) -> String {
+            translate(language, &format!("embed-{}", self.to_string().to_lowercase()), None)
+        }
+
+        /// The color used unless a guild's theme overrides it
         pub fn get_color(&self) -> Color {
             match self {
                 EmbedType::Success => config::SUCCESS_COLOR,
                 EmbedType::Failure => config::FAILURE_COLOR,
                 EmbedType::Confirmation => config::CONFIRMATION_COLOR,
                 EmbedType::Warning => config::WARNING_COLOR,
+                EmbedType::Info => config::INFO_COLOR,
             }
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn fields(embed: &CreateEmbed) -> &Vec<Value> {
+            embed.0["fields"].as_array().unwrap()
+        }
+
+        #[test]
+        fn embed_types_have_distinct_colors() {
+            let mut colors = EmbedType::ALL.iter().map(|t| t.get_color().0).collect::<Vec<u32>>();
+
+            colors.sort_unstable();
+            colors.dedup();
+
+            assert_eq!(colors.len(), EmbedType::ALL.len());
+        }
+
+        #[test]
+        fn texts_are_limited() {
+            let mut embed = CreateEmbed::default();
+
+            embed
+                .title("t".repeat(300))
+                .description("d".repeat(5000))
+                .author(|a| a.name("a".repeat(300)))
+                .footer(|f| f.text("f".repeat(3000)))
+                .limit();
+
+            assert_eq!(text_len(embed.0.get("title")), EMBED_TITLE_LIMIT);
+            assert_eq!(text_len(embed.0.get("author").and_then(|a| a.get("name"))), EMBED_AUTHOR_LIMIT);
+            assert_eq!(text_len(embed.0.get("footer").and_then(|f| f.get("text"))), EMBED_FOOTER_LIMIT);
+
+            // The description additionally gives way to the total limit
+            assert!(embed.0["description"].as_str().unwrap().ends_with("..."));
+            assert!(total_len(&embed.0) <= EMBED_TOTAL_LIMIT);
+        }
+
+        #[test]
+        fn fields_are_limited() {
+            let mut embed = CreateEmbed::default();
+
+            embed
+                .fields((0..30).map(|i| (format!("{}{}", i, "n".repeat(300)), "v".repeat(2000), true)))
+                .limit();
+
+            for field in fields(&embed) {
+                assert_eq!(text_len(field.get("name")), EMBED_FIELD_NAME_LIMIT);
+                assert_eq!(text_len(field.get("value")), EMBED_FIELD_VALUE_LIMIT);
+            }
+
+            // 25 fields of 1280 characters would exceed the total limit, so only the first 4 remain
+            assert_eq!(fields(&embed).len(), 4);
+            assert!(fields(&embed)[0]["name"].as_str().unwrap().starts_with('0'));
+        }
+
+        #[test]
+        fn description_is_shortened_before_fields_are_dropped() {
+            let mut embed = CreateEmbed::default();
+
+            embed
+                .description("d".repeat(4000))
+                .fields((0..3).map(|i| (i, "v".repeat(1000), false)))
+                .limit();
+
+            assert_eq!(fields(&embed).len(), 3);
+            assert_eq!(total_len(&embed.0), EMBED_TOTAL_LIMIT);
+        }
+
+        #[test]
+        fn small_embeds_are_untouched() {
+            let mut embed = CreateEmbed::default();
+
+            embed.title("Title").description("Description").field("Name", "Value", false);
+
+            let before = embed.0.clone();
+
+            embed.limit();
+
+            assert_eq!(embed.0, before);
+        }
+    }
 }
 
-pub mod misc {
-    pub(crate) fn get_wind_direction(deg: f32) -> Option<String> {
-        let deg = deg as i32;
-
-        if let 0..=360 = deg {
-            let name = match deg {
-                0..=25 | 336..=360 => "North",
-                26..=70 => "Northeast",
-                71..=110 => "East",
-                111..=155 => "Southeast",
-                156..=200 => "South",
-                201..=250 => "Southwest",
-                251..=290 => "West",
-                291..=335 => "Northwest",
-                _ => "",
-            };
+pub mod process {
+    //! Statistics of the bot's own process, read from `/proc` on Linux and unavailable elsewhere
+
+    use std::{fs, time::Duration};
+
+    /// `USER_HZ`, the unit of the CPU times in `/proc`, is 100 on every architecture Linux supports
+    const CLOCK_TICKS_PER_SECOND: u64 = 100;
+
+    /// The resident set size in bytes
+    pub fn memory_usage() -> Option<u64> {
+        parse_memory_usage(&fs::read_to_string("/proc/self/status").ok()?)
+    }
+
+    /// The time spent on the CPU in both user and kernel mode
+    pub fn cpu_time() -> Option<Duration> {
+        parse_cpu_time(&fs::read_to_string("/proc/self/stat").ok()?)
+    }
+
+    fn parse_memory_usage(status: &str) -> Option<u64> {
+        let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+        let kibibytes = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+
+        Some(kibibytes * 1024)
+    }
 
-            Some(name.to_owned())
+    fn parse_cpu_time(stat: &str) -> Option<Duration> {
+        // The process name may contain spaces and parentheses, so the fields are counted from its end
+        let fields = stat[stat.rfind(')')? + 1..].split_whitespace().collect::<Vec<&str>>();
+
+        // `utime` and `stime` are the 14th and 15th fields, counting the PID and the name
+        let user = fields.get(11)?.parse::<u64>().ok()?;
+        let system = fields.get(12)?.parse::<u64>().ok()?;
+
+        Some(Duration::from_millis((user + system) * 1000 / CLOCK_TICKS_PER_SECOND))
+    }
+
+    /// Formats a byte count with binary prefixes, e.g. "12.3 MiB"
+    pub fn format_bytes(bytes: u64) -> String {
+        const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+        let mut value = bytes as f64;
+        let mut unit = 0;
+
+        while value >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+
+        if unit == 0 {
+            format!("{} {}", bytes, UNITS[0])
         } else {
-            None
+            format!("{:.1} {}", value, UNITS[unit])
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn memory_usage_is_parsed() {
+            let status = "Name:\tbot\nVmPeak:\t  20000 kB\nVmRSS:\t   12345 kB\nThreads:\t8\n";
+
+            assert_eq!(parse_memory_usage(status), Some(12345 * 1024));
+            assert_eq!(parse_memory_usage("Name:\tbot\n"), None);
+        }
+
+        #[test]
+        fn cpu_time_is_parsed() {
+            let stat = "4242 (weird) name) S 1 4242 4242 0 -1 4194560 1000 0 0 0 250 130 0 0 20 0 8 0 100 0 0";
+
+            assert_eq!(parse_cpu_time(stat), Some(Duration::from_millis(3800)));
+            assert_eq!(parse_cpu_time("4242 (bot) S 1"), None);
+        }
+
+        #[test]
+        fn bytes_are_formatted() {
+            assert_eq!(format_bytes(512), "512 B");
+            assert_eq!(format_bytes(1536), "1.5 KiB");
+            assert_eq!(format_bytes(12 * 1024 * 1024 + 300 * 1024), "12.3 MiB");
+        }
+    }
+}
+
+pub mod misc {
+    pub(crate) struct WindDirection {
+        pub name: &'static str,
+        pub abbreviation: &'static str,
+        pub arrow: char,
+    }
+
+    const COMPASS_POINTS: [(&str, &str); 16] = [
+        ("North", "N"),
+        ("North-northeast", "NNE"),
+        ("Northeast", "NE"),
+        ("East-northeast", "ENE"),
+        ("East", "E"),
+        ("East-southeast", "ESE"),
+        ("Southeast", "SE"),
+        ("South-southeast", "SSE"),
+        ("South", "S"),
+        ("South-southwest", "SSW"),
+        ("Southwest", "SW"),
+        ("West-southwest", "WSW"),
+        ("West", "W"),
+        ("West-northwest", "WNW"),
+        ("Northwest", "NW"),
+        ("North-northwest", "NNW"),
+    ];
+
+    // The wind direction is where the wind blows from, so the arrows point the opposite way
+    const WIND_ARROWS: [char; 8] = [
+        '\u{2193}',
+        '\u{2199}',
+        '\u{2190}',
+        '\u{2196}',
+        '\u{2191}',
+        '\u{2197}',
+        '\u{2192}',
+        '\u{2198}',
+    ];
+
+    const BEAUFORT_SCALE: [(f32, &str); 12] = [
+        (0.5, "Calm"),
+        (1.6, "Light air"),
+        (3.4, "Light breeze"),
+        (5.5, "Gentle breeze"),
+        (8.0, "Moderate breeze"),
+        (10.8, "Fresh breeze"),
+        (13.9, "Strong breeze"),
+        (17.2, "Near gale"),
+        (20.8, "Gale"),
+        (24.5, "Strong gale"),
+        (28.5, "Storm"),
+        (32.7, "Violent storm"),
+    ];
+
+    pub(crate) fn get_wind_direction(deg: f32) -> Option<WindDirection> {
+        if !deg.is_finite() {
+            return None;
         }
+
+        let deg = deg.rem_euclid(360.0);
+
+        let (name, abbreviation) = COMPASS_POINTS[(deg / 22.5).round() as usize % 16];
+        let arrow = WIND_ARROWS[(deg / 45.0).round() as usize % 8];
+
+        Some(WindDirection { name, abbreviation, arrow })
     }
-}
\ No newline at end of file
+
+    /// Returns the Beaufort number and its name for the wind speed in m/s
+    pub(crate) fn get_beaufort_scale(speed: f32) -> (u8, &'static str) {
+        BEAUFORT_SCALE.iter()
+            .position(|(upper_bound, _)| speed < *upper_bound)
+            .map(|force| (force as u8, BEAUFORT_SCALE[force].1))
+            .unwrap_or((12, "Hurricane force"))
+    }
+
+    /// Calculates the dew point in °C using the Magnus formula
+    pub(crate) fn get_dew_point(temp_c: f32, humidity: f32) -> f32 {
+        const B: f32 = 17.62;
+        const C: f32 = 243.12;
+
+        let gamma = (humidity.max(1.0) / 100.0).ln() + B * temp_c / (C + temp_c);
+
+        C * gamma / (B - gamma)
+    }
+
+    /// Calculates the apparent temperature in °C as defined by the Australian Bureau of Meteorology
+    pub(crate) fn get_feels_like(temp_c: f32, humidity: f32, wind_speed: f32) -> f32 {
+        let vapour_pressure = humidity / 100.0 * 6.105 * (17.27 * temp_c / (237.7 + temp_c)).exp();
+
+        temp_c + 0.33 * vapour_pressure - 0.7 * wind_speed - 4.0
+    }
+
+    pub(crate) fn format_temperature(temp_c: f32) -> String {
+        let temp_f = temp_c * 1.8 + 32.0;
+
+        format!("{}\u{b0}C/{}\u{b0}F", temp_c.round() as i32, temp_f.round() as i32)
+    }
+
+    pub(crate) fn get_weather_icon_url(icon: &str) -> String {
+        format!("https://openweathermap.org/img/wn/{}@2x.png", icon)
+    }
+
+    pub(crate) fn capitalize(s: &str) -> String {
+        let mut chars = s.chars();
+
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    }
+
+    /// Returns the message ID of the category of an OpenWeather air quality index
+    pub(crate) fn get_aqi_category(aqi: u8) -> Option<String> {
+        (1..=5).contains(&aqi).then(|| format!("aqi-{}", aqi))
+    }
+
+    /// Returns the message ID of the WHO risk level of a UV index
+    pub(crate) fn get_uv_risk(uv_index: f64) -> &'static str {
+        match uv_index.round() as i64 {
+            i64::MIN..=2 => "uv-low",
+            3..=5 => "uv-moderate",
+            6..=7 => "uv-high",
+            8..=10 => "uv-very-high",
+            _ => "uv-extreme",
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn wind_direction_covers_all_compass_points() {
+            let abbreviations = (0..16)
+                .map(|i| get_wind_direction(i as f32 * 22.5).unwrap().abbreviation)
+                .collect::<Vec<&str>>();
+
+            assert_eq!(
+                abbreviations,
+                COMPASS_POINTS.iter().map(|(_, a)| *a).collect::<Vec<&str>>(),
+            );
+        }
+
+        #[test]
+        fn wind_direction_sector_boundaries() {
+            assert_eq!(get_wind_direction(11.24).unwrap().abbreviation, "N");
+            assert_eq!(get_wind_direction(11.26).unwrap().abbreviation, "NNE");
+            assert_eq!(get_wind_direction(348.76).unwrap().abbreviation, "N");
+            assert_eq!(get_wind_direction(348.74).unwrap().abbreviation, "NNW");
+        }
+
+        #[test]
+        fn wind_direction_normalizes_degrees() {
+            assert_eq!(get_wind_direction(360.0).unwrap().name, "North");
+            assert_eq!(get_wind_direction(360.5).unwrap().name, "North");
+            assert_eq!(get_wind_direction(450.0).unwrap().name, "East");
+            assert_eq!(get_wind_direction(-90.0).unwrap().name, "West");
+            assert!(get_wind_direction(f32::NAN).is_none());
+            assert!(get_wind_direction(f32::INFINITY).is_none());
+        }
+
+        #[test]
+        fn wind_direction_arrows_point_downwind() {
+            assert_eq!(get_wind_direction(0.0).unwrap().arrow, '\u{2193}');
+            assert_eq!(get_wind_direction(90.0).unwrap().arrow, '\u{2190}');
+            assert_eq!(get_wind_direction(180.0).unwrap().arrow, '\u{2191}');
+            assert_eq!(get_wind_direction(270.0).unwrap().arrow, '\u{2192}');
+            assert_eq!(get_wind_direction(225.0).unwrap().arrow, '\u{2197}');
+        }
+
+        #[test]
+        fn beaufort_scale() {
+            assert_eq!(get_beaufort_scale(0.0), (0, "Calm"));
+            assert_eq!(get_beaufort_scale(0.5), (1, "Light air"));
+            assert_eq!(get_beaufort_scale(5.0), (3, "Gentle breeze"));
+            assert_eq!(get_beaufort_scale(10.8), (6, "Strong breeze"));
+            assert_eq!(get_beaufort_scale(32.6), (11, "Violent storm"));
+            assert_eq!(get_beaufort_scale(32.7), (12, "Hurricane force"));
+            assert_eq!(get_beaufort_scale(60.0), (12, "Hurricane force"));
+        }
+
+        #[test]
+        fn dew_point() {
+            assert!((get_dew_point(20.0, 100.0) - 20.0).abs() < 0.01);
+            assert!((get_dew_point(25.0, 50.0) - 13.85).abs() < 0.1);
+            assert!((get_dew_point(0.0, 80.0) - -3.0).abs() < 0.1);
+            assert!(get_dew_point(30.0, 0.0).is_finite());
+        }
+
+        #[test]
+        fn feels_like() {
+            assert!((get_feels_like(30.0, 70.0, 0.0) - 35.7).abs() < 0.1);
+            assert!((get_feels_like(5.0, 50.0, 10.0) - -4.6).abs() < 0.1);
+            assert!(get_feels_like(20.0, 50.0, 5.0) < get_feels_like(20.0, 50.0, 0.0));
+        }
+
+        #[test]
+        fn temperature_formatting() {
+            assert_eq!(format_temperature(21.6), "22\u{b0}C/71\u{b0}F");
+            assert_eq!(format_temperature(-5.0), "-5\u{b0}C/23\u{b0}F");
+            assert_eq!(format_temperature(-20.0), "-20\u{b0}C/-4\u{b0}F");
+        }
+
+        #[test]
+        fn weather_icon_url() {
+            assert_eq!(get_weather_icon_url("04d"), "https://openweathermap.org/img/wn/04d@2x.png");
+        }
+
+        #[test]
+        fn capitalization() {
+            assert_eq!(capitalize("broken clouds"), "Broken clouds");
+            assert_eq!(capitalize("\u{e9}t\u{e9}"), "\u{c9}t\u{e9}");
+            assert_eq!(capitalize(""), "");
+        }
+    }
+}
@@ -0,0 +1,433 @@
+use crate::{
+    config,
+    i18n::{get_guild_language, tr, Language},
+    metrics,
+    storage::JsonStore,
+    theme::get_theme,
+    utils::{
+        discord::{DefaultEmbedReplies, EmbedType, ReplyOptions},
+        string::strip_str,
+    },
+};
+
+use lazy_static::lazy_static;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use serenity::{
+    http::Http,
+    model::id::ChannelId,
+};
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+};
+
+use tracing::{error, warn};
+
+const API_BASE: &str = "https://api.openweathermap.org";
+
+lazy_static! {
+    static ref CLIENT: reqwest::Client = reqwest::Client::new();
+
+    pub static ref ALERT_SUBSCRIPTIONS: JsonStore<Vec<AlertSubscription>> =
+        JsonStore::open("weather_alerts.json");
+}
+
+#[derive(Deserialize)]
+pub struct GeoLocation {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub country: String,
+    pub state: Option<String>,
+}
+
+impl GeoLocation {
+    pub fn display_name(&self) -> String {
+        match self.state {
+            Some(ref state) => format!("{}, {}, {}", self.name, state, self.country),
+            None => format!("{}, {}", self.name, self.country),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct OneCall {
+    pub timezone: String,
+    pub current: Option<CurrentConditions>,
+    #[serde(default)]
+    pub alerts: Vec<WeatherAlert>,
+}
+
+#[derive(Deserialize)]
+pub struct CurrentConditions {
+    pub uvi: f64,
+    pub wind_gust: Option<f64>,
+}
+
+#[derive(Deserialize)]
+pub struct WeatherAlert {
+    pub sender_name: String,
+    pub event: String,
+    pub start: i64,
+    pub end: i64,
+    pub description: String,
+}
+
+impl WeatherAlert {
+    /// The provider has no alert IDs, so an alert is identified by its issuer, event, and start
+    pub fn key(&self) -> String {
+        format!("{}|{}|{}", self.sender_name, self.event, self.start)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Forecast {
+    pub list: Vec<ForecastEntry>,
+    pub city: ForecastCity,
+}
+
+#[derive(Deserialize)]
+pub struct ForecastEntry {
+    pub dt: i64,
+    pub main: ForecastTemperature,
+    pub rain: Option<Precipitation>,
+    pub snow: Option<Precipitation>,
+}
+
+impl ForecastEntry {
+    pub fn precipitation(&self) -> f64 {
+        [&self.rain, &self.snow].iter()
+            .filter_map(|p| p.as_ref())
+            .map(|p| p.three_hours)
+            .sum()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ForecastTemperature {
+    pub temp: f64,
+}
+
+#[derive(Deserialize)]
+pub struct Precipitation {
+    #[serde(rename = "3h", default)]
+    pub three_hours: f64,
+}
+
+#[derive(Deserialize)]
+pub struct ForecastCity {
+    pub timezone: i32,
+}
+
+#[derive(Deserialize)]
+struct AirPollution {
+    list: Vec<AirPollutionEntry>,
+}
+
+#[derive(Deserialize)]
+struct AirPollutionEntry {
+    main: AirPollutionIndex,
+    components: AirPollutionComponents,
+}
+
+#[derive(Deserialize)]
+struct AirPollutionIndex {
+    aqi: u8,
+}
+
+#[derive(Deserialize)]
+struct AirPollutionComponents {
+    pm2_5: f64,
+    pm10: f64,
+    o3: f64,
+}
+
+pub struct AirQuality {
+    pub aqi: u8,
+    pub pm2_5: f64,
+    pub pm10: f64,
+    pub o3: f64,
+}
+
+/// Data that is not a part of the current weather response and is only shown when available
+#[derive(Default)]
+pub struct AdditionalConditions {
+    pub air_quality: Option<AirQuality>,
+    pub uv_index: Option<f64>,
+    pub wind_gust: Option<f64>,
+}
+
+/// The current weather as shown by the weather command, independent of the OpenWeather client's types
+#[derive(Clone, Debug, Default)]
+pub struct CurrentWeather {
+    pub city_id: u64,
+    pub location: String,
+    pub condition: Option<String>,
+    pub icon: Option<String>,
+    /// In degrees Celsius
+    pub temperature: f32,
+    /// In percent
+    pub humidity: f32,
+    /// In millibars
+    pub pressure: f32,
+    /// In meters per second
+    pub wind_speed: f32,
+    /// The direction the wind blows from, in degrees
+    pub wind_direction: f32,
+    /// In percent
+    pub cloudiness: Option<u32>,
+    /// The offset from UTC in seconds
+    pub timezone: Option<i32>,
+    /// Unix time in seconds
+    pub sunrise: Option<i64>,
+    /// Unix time in seconds
+    pub sunset: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AlertSubscription {
+    pub channel_id: u64,
+    /// Used to pick the language alerts are posted in
+    #[serde(default)]
+    pub guild_id: Option<u64>,
+    pub location: String,
+    pub lat: f64,
+    pub lon: f64,
+    #[serde(default)]
+    pub seen_alerts: HashMap<String, i64>,
+}
+
+/// Sends a request to the OpenWeather API, which is recorded in the metrics as `api`
+async fn request<T: DeserializeOwned>(api: &str, path: &str, query: &[(&str, &str)]) -> reqwest::Result<T> {
+    let call = async {
+        CLIENT
+            .get(format!("{}{}", API_BASE, path))
+            .query(query)
+            .query(&[("appid", config::WEATHER_API_KEY.as_str())])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    };
+
+    metrics::track_api_call(api, call).await
+}
+
+pub async fn geocode(query: &str) -> reqwest::Result<Option<GeoLocation>> {
+    let locations: Vec<GeoLocation> = request(
+        "openweather_geocoding",
+        "/geo/1.0/direct",
+        &[("q", query), ("limit", "1")],
+    ).await?;
+
+    Ok(locations.into_iter().next())
+}
+
+pub async fn one_call(lat: f64, lon: f64) -> reqwest::Result<OneCall> {
+    request(
+        "openweather_one_call",
+        "/data/3.0/onecall",
+        &[
+            ("lat", lat.to_string().as_str()),
+            ("lon", lon.to_string().as_str()),
+            ("exclude", "minutely,hourly,daily"),
+        ],
+    ).await
+}
+
+pub async fn forecast(lat: f64, lon: f64) -> reqwest::Result<Forecast> {
+    request(
+        "openweather_forecast",
+        "/data/2.5/forecast",
+        &[
+            ("lat", lat.to_string().as_str()),
+            ("lon", lon.to_string().as_str()),
+            ("units", "metric"),
+        ],
+    ).await
+}
+
+pub async fn air_pollution(lat: f64, lon: f64) -> reqwest::Result<Option<AirQuality>> {
+    let air_pollution: AirPollution = request(
+        "openweather_air_pollution",
+        "/data/2.5/air_pollution",
+        &[("lat", lat.to_string().as_str()), ("lon", lon.to_string().as_str())],
+    ).await?;
+
+    let air_quality = air_pollution.list.into_iter().next().map(|entry| AirQuality {
+        aqi: entry.main.aqi,
+        pm2_5: entry.components.pm2_5,
+        pm10: entry.components.pm10,
+        o3: entry.components.o3,
+    });
+
+    Ok(air_quality)
+}
+
+/// Fetches the additional conditions at the coordinates of a current weather response
+pub async fn additional_conditions(lat: f64, lon: f64) -> AdditionalConditions {
+    let (air_quality, one_call) = tokio::join!(air_pollution(lat, lon), one_call(lat, lon));
+
+    let current = one_call.ok().and_then(|o| o.current);
+
+    AdditionalConditions {
+        air_quality: air_quality.ok().flatten(),
+        uv_index: current.as_ref().map(|c| c.uvi),
+        wind_gust: current.and_then(|c| c.wind_gust),
+    }
+}
+
+pub async fn poll_alerts(http: Arc<Http>) {
+    let mut interval = tokio::time::interval(config::WEATHER_ALERTS_POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let coordinates = alert_coordinates(&ALERT_SUBSCRIPTIONS.read());
+
+        for (lat, lon) in coordinates {
+            let alerts = match one_call(lat, lon).await {
+                Ok(one_call) => one_call.alerts,
+                Err(e) => {
+                    warn!("Failed to fetch weather alerts: {}", e);
+
+                    continue;
+                }
+            };
+
+            let now = chrono::Utc::now().timestamp();
+            let mut pending = Vec::new();
+
+            let updated = ALERT_SUBSCRIPTIONS.update(|subscriptions| {
+                for subscription in subscriptions.iter_mut().filter(|s| s.lat == lat && s.lon == lon) {
+                    let options = ReplyOptions::new(
+                        get_guild_language(subscription.guild_id),
+                        get_theme(subscription.guild_id),
+                    );
+
+                    for alert in take_new_alerts(subscription, &alerts, now) {
+                        let description = format_alert(&subscription.location, alert, options.language);
+
+                        pending.push((subscription.channel_id, description, options.clone()));
+                    }
+                }
+            }).await;
+
+            if let Err(e) = updated {
+                error!("Failed to save weather alert subscriptions: {}", e);
+
+                continue;
+            }
+
+            for (channel_id, description, options) in pending {
+                if let Err(e) = ChannelId(channel_id)
+                    .send_default_reply(&http, description, EmbedType::Warning, &options)
+                    .await
+                {
+                    warn!("Failed to post a weather alert to {}: {}", channel_id, e);
+                }
+            }
+        }
+    }
+}
+
+/// The locations to fetch alerts for, as channels subscribed to the same location share a single request
+fn alert_coordinates(subscriptions: &[AlertSubscription]) -> Vec<(f64, f64)> {
+    let mut coordinates = subscriptions.iter()
+        .map(|s| (s.lat, s.lon))
+        .collect::<Vec<(f64, f64)>>();
+
+    coordinates.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    coordinates.dedup();
+
+    coordinates
+}
+
+/// Forgets the alerts of the subscription that have ended and marks the ones in effect it has not been sent yet as seen,
+/// returning the latter
+fn take_new_alerts<'a>(subscription: &mut AlertSubscription, alerts: &'a [WeatherAlert], now: i64) -> Vec<&'a WeatherAlert> {
+    subscription.seen_alerts.retain(|_, end| *end >= now);
+
+    alerts.iter()
+        .filter(|alert| alert.end >= now)
+        .filter(|alert| subscription.seen_alerts.insert(alert.key(), alert.end).is_none())
+        .collect()
+}
+
+fn format_alert(location: &str, alert: &WeatherAlert, language: Language) -> String {
+    let description = strip_str(alert.description.trim(), 1500, true).unwrap_or_default();
+
+    format!(
+        "**{}**\n{}\n\n{}\n\n*{}*",
+        tr!(language, "alerts-alert-title", event = alert.event.as_str(), location = location),
+        tr!(language, "alerts-alert-period", start = alert.start, end = alert.end),
+        description,
+        tr!(language, "alerts-alert-issuer", sender = alert.sender_name.as_str()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription(channel_id: u64, lat: f64, lon: f64) -> AlertSubscription {
+        AlertSubscription {
+            channel_id,
+            guild_id: None,
+            location: String::from("Oslo, NO"),
+            lat,
+            lon,
+            seen_alerts: HashMap::new(),
+        }
+    }
+
+    fn alert(event: &str, start: i64, end: i64) -> WeatherAlert {
+        WeatherAlert {
+            sender_name: String::from("MET Norway"),
+            event: event.to_owned(),
+            start,
+            end,
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn subscriptions_to_the_same_location_share_a_request() {
+        let subscriptions = vec![
+            subscription(1, 59.9, 10.7),
+            subscription(2, 52.5, 13.4),
+            subscription(3, 59.9, 10.7),
+            subscription(4, f64::NAN, 10.7),
+        ];
+
+        let coordinates = alert_coordinates(&subscriptions);
+
+        assert_eq!(coordinates.len(), 3);
+        assert_eq!(coordinates[..2], [(52.5, 13.4), (59.9, 10.7)]);
+        assert!(coordinates[2].0.is_nan());
+    }
+
+    #[test]
+    fn alerts_are_sent_once_and_forgotten_when_they_end() {
+        let mut subscription = subscription(1, 59.9, 10.7);
+        let alerts = vec![alert("Wind", 0, 100), alert("Snow", 50, 200)];
+
+        let keys = |alerts: Vec<&WeatherAlert>| alerts.iter().map(|a| a.event.clone()).collect::<Vec<String>>();
+
+        assert_eq!(keys(take_new_alerts(&mut subscription, &alerts, 10)), ["Wind", "Snow"]);
+        assert!(take_new_alerts(&mut subscription, &alerts, 20).is_empty());
+
+        // Only the alerts still in effect are remembered
+        assert!(take_new_alerts(&mut subscription, &[], 150).is_empty());
+        assert_eq!(subscription.seen_alerts.len(), 1);
+        assert!(subscription.seen_alerts.contains_key(&alerts[1].key()));
+
+        // An ended alert that is still reported is not sent again
+        assert!(take_new_alerts(&mut subscription, &alerts[..1], 150).is_empty());
+        assert_eq!(subscription.seen_alerts.len(), 1);
+    }
+}